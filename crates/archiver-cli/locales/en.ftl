@@ -0,0 +1,376 @@
+# English messages for the archiver CLI.
+# Placeables such as { $name } are filled in by the `t!` macro in `src/i18n.rs`.
+
+## General
+
+tui-not-implemented = TUI mode is not yet implemented. Use a subcommand like 'run' or 'list'.
+help-hint = For help, run 'archive --help'.
+unsupported-platform = Error: This application is currently only supported on Linux.
+operation-cancelled = Operation cancelled.
+name-or-all-required = You must specify a project name or use the --all flag.
+
+## Errors
+
+error-init-logging = Failed to initialize logging
+error-load-settings = Failed to load settings. Try running 'archive init'
+error-load-existing-settings = Failed to load existing settings.
+error-archive-process = The archiving process failed
+error-list-records = Failed to retrieve list of archived projects
+error-home-not-found = Could not find HOME directory
+error-save-settings = Failed to save updated settings
+error-create-config-dir = Could not create config directory
+error-serialize-settings = Could not serialize settings to TOML
+error-write-config = Could not write config to '{ $path }'
+error-forecast = Failed to forecast upcoming archive candidates
+error-duplicates = Failed to look for duplicated projects
+error-stats = Failed to read the disk usage history
+error-remote = Failed to work on the remote machine '{ $name }'
+error-digest = Failed to build the digest
+error-notify = Could not send the desktop notification
+error-artifacts = Failed to archive build artifacts
+error-plan = Failed to plan the run
+error-write-plan = Could not write the plan to '{ $path }'
+error-read-plan = Could not read the plan '{ $path }'
+error-apply-plan = Failed to apply the plan
+error-resume = Failed to resume the interrupted run
+error-serve = Could not serve the dashboard on '{ $addr }'
+error-index = Failed to maintain the archive log
+error-dates = Invalid 'timezone' or 'date_format' setting
+error-label = Error
+error-code-hint = Run 'archiver explain { $code }' for likely causes and fixes.
+
+## init / config
+
+init-welcome = Welcome to Auto Archiver setup!
+init-overwrite-prompt = A configuration file already exists. Do you want to overwrite it?
+init-cancelled = Initialization cancelled.
+init-saved = Configuration saved successfully!
+config-updating = Updating Auto Archiver configuration...
+config-updated = Configuration updated successfully!
+prompt-projects-dir = Enter the path to your projects directory (separate several with commas)
+prompt-archive-dir = Enter the path for the archive directory
+prompt-inactivity-days = Archive projects after how many days of inactivity?
+
+## run
+
+run-nothing-to-archive = No projects needed archiving.
+run-nothing-to-resume = No interrupted run to resume.
+run-resume-hint = Run 'archiver run --resume' to continue with the projects left.
+run-dry-run-header = -- DRY RUN --
+run-dry-run-footer = Run without --dry-run to perform these actions.
+plan-archive = Will archive { $count } project(s) ({ $size }):
+plan-clean = Will clean { $count } project(s) before archiving ({ $size }):
+plan-delete = Will delete { $count } project(s) archived for over { $days } days ({ $size }):
+plan-skipped = Will skip { $count } inactive project(s):
+plan-totals = Plan: { $archive } to archive ({ $moved }), { $cleaned } to clean, { $delete } to delete ({ $freed }), { $skipped } skipped.
+run-archived = Successfully archived { $count } project(s).
+run-nothing-to-delete = No archived projects are older than { $days } days.
+run-deleted = Permanently deleted { $count } project(s) archived for over { $days } days.
+run-suggest-exclusion = '{ $name }' has been restored { $count } times and is being archived again. Exclude it with 'archiver exclude { $name }', or snooze it with 'archiver exclude add { $name } --until <DATE>'.
+table-archived = Projects archived
+table-bytes-moved = Moved to the archive
+table-bytes-cleaned = Freed by cleanup
+table-deleted = Archives deleted
+table-bytes-freed = Freed by deletion
+table-skipped = Inactive but skipped
+table-failed = Failures
+table-duration = Duration
+table-archive-log = Archive log
+table-run-summary = Run summary
+
+## delete
+
+delete-warning = Warning: This operation is permanent and cannot be undone.
+delete-archive-empty = Archive is already empty.
+delete-all-prompt = Are you sure you want to permanently delete ALL { $count } projects?
+delete-all-confirm-count = To confirm, please type the number of projects to delete ({ $count })
+delete-all-wrong-count = Incorrect number entered. Deletion cancelled.
+delete-all-done = Successfully deleted { $count } projects.
+delete-prompt = Are you sure you want to permanently delete '{ $name }'?
+delete-group-empty = No archived projects in group '{ $group }'.
+delete-group-prompt = Are you sure you want to permanently delete the { $count } project(s) of group '{ $group }'?
+delete-done = Project '{ $name }' deleted successfully.
+
+## restore
+
+restore-all-prompt = Restore all projects from the archive?
+restore-all-done = Successfully restored { $count } projects.
+restore-done = Project '{ $name }' restored successfully.
+restore-matching-none = No archived projects match the given filters.
+restore-matching-prompt = Restore the { $count } matching project(s)?
+restore-preview-header = Restore preview for '{ $name }':
+restore-preview-from = From:  { $path }
+restore-preview-to = To:    { $path }
+restore-preview-size = Size:  { $size }
+restore-preview-destination-exists = The destination already exists, restoring would fail.
+restore-preview-destination-free = The destination is free.
+restore-preview-footer = Run without --preview to restore the project.
+
+## forecast
+
+forecast-empty = No projects will become archive candidates in the next { $days } days.
+forecast-header = { $count } project(s) will become archive candidates in the next { $days } days:
+forecast-entry = { $name } on { $date } (in { $days } days)
+invalid-period = Invalid period '{ $value }', expected a number of days such as '30d' or weeks such as '4w'.
+
+## duplicates
+
+duplicates-empty = No duplicated projects found.
+duplicates-header = { $count } group(s) of duplicated projects:
+duplicates-remote = Same remote: { $url }
+duplicates-content = Identical content
+duplicates-keep = keep  { $path } (last active { $date })
+duplicates-copy = copy  { $path } (last active { $date })
+duplicates-hint = Set archive_duplicates = true to archive the older copies on the next run.
+
+## plan
+
+plan-written = Plan written to { $path }:
+plan-apply-hint = Review it, then run 'archiver apply <file>' to carry it out.
+
+## artifacts
+
+artifacts-disabled = Artifact-only archiving is off. Set 'artifact_days' to enable it.
+artifacts-none = No build artifacts to show.
+artifacts-would-move = Would move the artifacts of { $count } project(s) ({ $size }):
+artifacts-moved = Moved the artifacts of { $count } project(s) ({ $size }):
+artifacts-list = Artifacts of { $count } project(s) in the archive ({ $size }):
+artifacts-restored = Restored the build artifacts of '{ $name }' ({ $size }).
+
+## serve
+
+serve-listening = Serving the dashboard at { $url } (Ctrl-C to stop).
+
+## digest
+
+digest-header = archiver digest, { $start } to { $end }
+digest-summary = { $runs } run(s): archived { $archived } project(s), deleted { $deleted } from the archive, freed { $freed }.
+digest-usage = Projects: { $active }, archive: { $archive }.
+digest-upcoming = { $count } archived project(s) will be deleted in the next { $days } days.
+digest-archived-on = archived on { $date }
+
+## index
+
+index-compacted = Dropped { $count } line(s) of history from { $path }.
+index-converted = Converted { $count } record(s) into { $path }.
+index-nothing-to-convert = Nothing to convert; the archive log is already { $path }.
+
+## remote
+
+remote-none = No remote machines configured. Add them to the 'remotes' setting.
+remote-dirs = projects: { $projects }; archive: { $archive }
+remote-no-projects = No projects found on '{ $name }'.
+remote-would-archive = Would archive { $count } project(s) on '{ $name }':
+remote-archived = Archived { $count } project(s) on '{ $name }':
+
+## stats
+
+stats-active = Projects directories
+stats-archive = Archive
+stats-archived-projects = Archived projects
+stats-freed = Freed since { $date }
+stats-runs = Runs recorded
+stats-trend-empty = No disk usage recorded yet. Every real run adds a sample.
+stats-trend-header = Disk usage over { $runs } recorded run(s):
+stats-trend-week = week of { $date }
+stats-trend-entry = { $period } active { $active } { $change } archive { $archive }   freed { $freed }
+stats-trend-footer = Active usage changed by { $change } since { $date }.
+
+## list
+
+list-empty = No projects are currently archived.
+list-header = Archived projects:
+list-entry = { $name } (Archived on: { $date })
+list-entry-stats = { $language }, { $lines } lines of code
+list-entry-group = group { $group }
+list-tree-missing = (missing from disk)
+
+## settings
+
+settings-migrated = Your settings file was upgraded from version { $from } to { $to }. The original was saved as { $backup }.
+settings-migration-failed = Could not upgrade your settings file
+
+## paths
+
+paths-header = Configuration paths:
+paths-projects-dir = Projects directory: { $path }
+paths-archive-dir = Archive directory:  { $path }
+paths-index-dir = Archive log in:     { $path }
+paths-config-file = Config file:        { $path }
+
+## exclude
+
+exclude-removed = Project '{ $name }' has been removed from the exclusion list.
+exclude-not-listed = Project '{ $name }' was not on the exclusion list. No changes made.
+exclude-already-listed = Project '{ $name }' is already on the exclusion list.
+exclude-added = Project '{ $name }' has been added to the exclusion list.
+exclude-added-until = Project '{ $name }' is excluded from archiving until { $date }.
+exclude-updated = Project '{ $name }' is now excluded permanently.
+exclude-list-empty = The exclusion list is empty.
+exclude-list-header = Exclusions (matched against { $path }):
+exclude-kind-exact = exact
+exclude-kind-glob = glob
+exclude-status-exists = exists
+exclude-status-missing = not found
+exclude-status-matches = { $count ->
+    [one] 1 match
+   *[other] { $count } matches
+}
+exclude-expiry-never = permanent
+exclude-expiry-until = until { $date }
+exclude-expiry-expired = expired on { $date }
+
+## simulate
+
+simulate-dir-exists = '{ $path }' already exists; simulations only generate into a new directory.
+simulate-generated = Generated { $count } projects ({ $size } at most) in { $path } in { $seconds }s.
+simulate-dry-run = Dry run: scanned { $scanned } projects, { $archived } would be archived ({ $size }) in { $seconds }s.
+simulate-archive = Archive: scanned { $scanned } projects, archived { $archived } ({ $size }) in { $seconds }s.
+invalid-size = Invalid size '{ $value }', expected a number of bytes with an optional K, M, G or T suffix.
+
+## explain
+
+explain-list-header = Error codes:
+explain-causes = Likely causes
+explain-fixes = How to fix it
+
+explain-a001-title = Permission denied
+explain-a001-causes =
+    The projects directory, the archive directory or a project inside them is not writable
+    by your user, or lives on a read-only filesystem.
+explain-a001-fixes =
+    Check the owner and permissions of the paths shown by 'archiver paths', e.g. with 'ls -ld'.
+    Fix them with 'chown'/'chmod', or point 'archive_dir' to a writable location.
+
+explain-a002-title = Corrupt archive log
+explain-a002-causes =
+    The archive log (archive.json or archive.jsonl) is not valid JSON or contains an invalid record, usually
+    after a manual edit, an interrupted write or a sync conflict.
+explain-a002-fixes =
+    Open the log in the archive directory and fix the reported line, or restore it from a backup.
+    With a synced archive, look for conflict copies of archive.*.json next to it.
+
+explain-a003-title = Invalid settings
+explain-a003-causes =
+    The configuration file has a syntax error or a value of the wrong type, or an ARCHIVER__*
+    environment variable overrides a setting with an invalid value.
+explain-a003-fixes =
+    Run 'archiver config' to rewrite the settings interactively, or fix the file shown by
+    'archiver paths'. Check the environment with 'env | grep ARCHIVER'.
+
+explain-a004-title = Git error
+explain-a004-causes =
+    A repository could not be read: it is corrupt, uses an unsupported extension, or is a
+    partial clone or worktree whose metadata is missing.
+explain-a004-fixes =
+    Run 'git status' and 'git fsck' in the repository. Exclude it with 'archiver exclude <name>'
+    if it cannot be repaired.
+
+explain-a005-title = Home directory not found
+explain-a005-causes =
+    The HOME environment variable is unset or empty, as happens in some service managers and
+    containers.
+explain-a005-fixes =
+    Set HOME for the process running the archiver, e.g. with 'Environment=HOME=/home/you' in a
+    systemd unit.
+
+explain-a006-title = Project not found in the archive
+explain-a006-causes =
+    No archived project has this name or relative path. It may have been restored or deleted
+    already, or the name is misspelled.
+explain-a006-fixes =
+    Run 'archiver list' to see the archived projects and their exact names. Close names are
+    suggested with the error; set 'loose_name_matching = true' to also match names that differ
+    only in case or Unicode normalization.
+
+explain-a007-title = Ambiguous project name
+explain-a007-causes =
+    Several archived projects share this name, e.g. 'api' from two organizations in a nested
+    layout.
+explain-a007-fixes =
+    Use the relative path shown by 'archiver list' instead, e.g. 'github.com/org/api'.
+
+explain-a008-title = Unsafe path
+explain-a008-causes =
+    A project name contains separators or '..', or a record in the archive log points outside
+    the archive directory. The operation was refused to avoid touching unrelated files.
+explain-a008-fixes =
+    Use a plain project name. If the log was edited by hand, fix the 'archive_path' of the
+    record so that it lies inside the archive directory.
+
+explain-a009-title = Destination already exists
+explain-a009-causes =
+    A directory already exists where a project would be moved, e.g. the project was recreated
+    or copied back after it was archived.
+explain-a009-fixes =
+    Check with 'archiver restore <name> --preview', then move or remove the existing directory
+    before trying again.
+
+explain-a010-title = Path not found
+explain-a010-causes =
+    The projects or archive directory does not exist, or a project was moved or deleted by hand
+    while the archiver was working on it.
+explain-a010-fixes =
+    Check the paths shown by 'archiver paths' and create the missing directories, or update them
+    with 'archiver config'.
+
+explain-a011-title = Forge query failed
+explain-a011-causes =
+    The 'gh' or 'glab' command used to check for open pull/merge requests is not installed, not
+    authenticated, or could not reach the server.
+explain-a011-fixes =
+    Run 'gh auth status' or 'glab auth status'. Disable 'check_open_reviews' if you do not use
+    the integration.
+
+explain-a012-title = Remote machine unreachable
+explain-a012-causes =
+    The remote is not listed in the 'remotes' setting, SSH could not connect to its host, or a
+    command run there failed (missing directory, missing permissions, a destination that already
+    exists in its archive).
+explain-a012-fixes =
+    Check that 'ssh <host> true' works without a password prompt, and that the directories of
+    the remote exist on that machine. Run 'archiver remote list' to review the configuration.
+
+explain-a013-title = Restored project does not match its archive
+explain-a013-causes =
+    After a restore, the files of the project differ from those it had when it was archived:
+    files were removed, added or changed inside the archive, or the move was interrupted.
+explain-a013-fixes =
+    The project was moved back into the archive and is still in the archive log. Compare it
+    with your remote or backups, and fix or replace the archived copy before restoring it again.
+
+explain-a014-title = Saved plan is out of date
+explain-a014-causes =
+    Since the plan was made, a project it archives was removed, got new activity or became
+    protected, or an archive it deletes was restored, deleted or is no longer due for deletion.
+explain-a014-fixes =
+    Nothing was changed. Make a new plan with 'archiver plan --out <file>', review it and apply
+    that one instead.
+
+explain-a015-title = Run interrupted
+explain-a015-causes =
+    The run was stopped with Ctrl-C. The project being moved was finished and the archive log
+    lists everything archived so far; the projects left were saved as a resume point.
+explain-a015-fixes =
+    Run 'archiver run --resume' to continue with the projects left, or a plain 'archiver run' to
+    scan again from scratch.
+
+explain-a016-title = Corrupt state file
+explain-a016-causes =
+    A file the archiver keeps besides the archive log (resume.json, restores.json, artifacts.json, a
+    saved plan, a metadata sidecar or a cache in the state directory) is not valid JSON, usually after
+    a manual edit or an interrupted write.
+explain-a016-fixes =
+    Fix the file or delete it. Caches and restore counts are rebuilt; a deleted resume point or
+    saved plan is simply gone.
+
+explain-a099-title = Unexpected error
+explain-a099-causes =
+    An error without a more specific code.
+explain-a099-fixes =
+    Run the command again with -vv and check the log file in the state directory. Please report
+    it if the problem persists.
+
+## mangen
+
+mangen-written = Wrote { $count } man pages to { $path }.
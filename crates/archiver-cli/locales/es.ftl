@@ -0,0 +1,451 @@
+# Mensajes en español para la CLI de archiver.
+# Los marcadores como { $name } los rellena la macro `t!` de `src/i18n.rs`.
+
+## General
+
+tui-not-implemented = El modo TUI aún no está implementado. Usa un subcomando como 'run' o 'list'.
+help-hint = Para obtener ayuda, ejecuta 'archive --help'.
+unsupported-platform = Error: Por ahora esta aplicación solo es compatible con Linux.
+operation-cancelled = Operación cancelada.
+name-or-all-required = Debes indicar el nombre de un proyecto o usar la opción --all.
+
+## Errores
+
+error-init-logging = No se pudo inicializar el registro de logs
+error-load-settings = No se pudo cargar la configuración. Prueba a ejecutar 'archive init'
+error-load-existing-settings = No se pudo cargar la configuración existente.
+error-archive-process = Falló el proceso de archivado
+error-list-records = No se pudo obtener la lista de proyectos archivados
+error-home-not-found = No se encontró el directorio HOME
+error-save-settings = No se pudo guardar la configuración actualizada
+error-create-config-dir = No se pudo crear el directorio de configuración
+error-serialize-settings = No se pudo serializar la configuración a TOML
+error-write-config = No se pudo escribir la configuración en '{ $path }'
+error-forecast = No se pudo pronosticar los próximos candidatos a archivar
+error-duplicates = No se pudieron buscar proyectos duplicados
+error-stats = No se pudo leer el historial de uso de disco
+error-remote = Falló el trabajo en la máquina remota '{ $name }'
+error-digest = No se pudo generar el resumen
+error-notify = No se pudo enviar la notificación de escritorio
+error-artifacts = No se pudieron archivar los artefactos de compilación
+error-plan = No se pudo planificar la ejecución
+error-write-plan = No se pudo escribir el plan en '{ $path }'
+error-read-plan = No se pudo leer el plan '{ $path }'
+error-apply-plan = No se pudo aplicar el plan
+error-resume = No se pudo reanudar la ejecución interrumpida
+error-serve = No se pudo servir el panel en '{ $addr }'
+error-index = No se pudo mantener el registro del archivo
+error-dates = Ajuste 'timezone' o 'date_format' no válido
+error-label = Error
+error-code-hint = Ejecuta 'archiver explain { $code }' para ver causas probables y soluciones.
+
+## init / config
+
+init-welcome = ¡Bienvenido a la configuración de Auto Archiver!
+init-overwrite-prompt = Ya existe un archivo de configuración. ¿Quieres sobrescribirlo?
+init-cancelled = Inicialización cancelada.
+init-saved = ¡Configuración guardada correctamente!
+config-updating = Actualizando la configuración de Auto Archiver...
+config-updated = ¡Configuración actualizada correctamente!
+prompt-projects-dir = Introduce la ruta de tu directorio de proyectos (separa varios con comas)
+prompt-archive-dir = Introduce la ruta del directorio de archivo
+prompt-inactivity-days = ¿Tras cuántos días de inactividad se archivan los proyectos?
+
+## run
+
+run-nothing-to-archive = Ningún proyecto necesitaba archivarse.
+run-nothing-to-resume = No hay ninguna ejecución interrumpida que reanudar.
+run-resume-hint = Ejecuta 'archiver run --resume' para continuar con los proyectos restantes.
+run-dry-run-header = -- SIMULACIÓN --
+run-dry-run-footer = Ejecuta sin --dry-run para aplicar estas acciones.
+plan-archive = { $count ->
+    [one] Se archivará 1 proyecto ({ $size }):
+   *[other] Se archivarán { $count } proyectos ({ $size }):
+}
+plan-clean = { $count ->
+    [one] Se limpiará 1 proyecto antes de archivarlo ({ $size }):
+   *[other] Se limpiarán { $count } proyectos antes de archivarlos ({ $size }):
+}
+plan-delete = { $count ->
+    [one] Se eliminará 1 proyecto archivado hace más de { $days } días ({ $size }):
+   *[other] Se eliminarán { $count } proyectos archivados hace más de { $days } días ({ $size }):
+}
+plan-skipped = { $count ->
+    [one] Se omitirá 1 proyecto inactivo:
+   *[other] Se omitirán { $count } proyectos inactivos:
+}
+plan-totals = Plan: { $archive } por archivar ({ $moved }), { $cleaned } por limpiar, { $delete } por eliminar ({ $freed }), { $skipped } omitidos.
+run-archived = { $count ->
+    [one] Se archivó { $count } proyecto correctamente.
+   *[other] Se archivaron { $count } proyectos correctamente.
+}
+run-nothing-to-delete = Ningún proyecto archivado tiene más de { $days } días.
+run-deleted = { $count ->
+    [one] Se eliminó permanentemente 1 proyecto archivado hace más de { $days } días.
+   *[other] Se eliminaron permanentemente { $count } proyectos archivados hace más de { $days } días.
+}
+run-suggest-exclusion = '{ $name }' se ha restaurado { $count } veces y se vuelve a archivar. Exclúyelo con 'archiver exclude { $name }', o pospónlo con 'archiver exclude add { $name } --until <FECHA>'.
+table-archived = Proyectos archivados
+table-bytes-moved = Movido al archivo
+table-bytes-cleaned = Liberado por la limpieza
+table-deleted = Archivos eliminados
+table-bytes-freed = Liberado por eliminación
+table-skipped = Inactivos pero omitidos
+table-failed = Fallos
+table-duration = Duración
+table-archive-log = Registro del archivo
+table-run-summary = Resumen de la ejecución
+
+## delete
+
+delete-warning = Atención: esta operación es permanente y no se puede deshacer.
+delete-archive-empty = El archivo ya está vacío.
+delete-all-prompt = ¿Seguro que quieres eliminar permanentemente TODOS los { $count } proyectos?
+delete-all-confirm-count = Para confirmar, escribe el número de proyectos a eliminar ({ $count })
+delete-all-wrong-count = Número incorrecto. Eliminación cancelada.
+delete-all-done = { $count ->
+    [one] Se eliminó { $count } proyecto correctamente.
+   *[other] Se eliminaron { $count } proyectos correctamente.
+}
+delete-prompt = ¿Seguro que quieres eliminar permanentemente '{ $name }'?
+delete-group-empty = No hay proyectos archivados en el grupo '{ $group }'.
+delete-group-prompt = { $count ->
+    [one] ¿Seguro que quieres eliminar permanentemente el proyecto del grupo '{ $group }'?
+   *[other] ¿Seguro que quieres eliminar permanentemente los { $count } proyectos del grupo '{ $group }'?
+}
+delete-done = Proyecto '{ $name }' eliminado correctamente.
+
+## restore
+
+restore-all-prompt = ¿Restaurar todos los proyectos del archivo?
+restore-all-done = { $count ->
+    [one] Se restauró { $count } proyecto correctamente.
+   *[other] Se restauraron { $count } proyectos correctamente.
+}
+restore-done = Proyecto '{ $name }' restaurado correctamente.
+restore-matching-none = Ningún proyecto archivado coincide con los filtros indicados.
+restore-matching-prompt = { $count ->
+    [one] ¿Restaurar el proyecto que coincide?
+   *[other] ¿Restaurar los { $count } proyectos que coinciden?
+}
+restore-preview-header = Vista previa de la restauración de '{ $name }':
+restore-preview-from = Desde:  { $path }
+restore-preview-to = Hacia:  { $path }
+restore-preview-size = Tamaño: { $size }
+restore-preview-destination-exists = El destino ya existe, la restauración fallaría.
+restore-preview-destination-free = El destino está libre.
+restore-preview-footer = Ejecuta sin --preview para restaurar el proyecto.
+
+## forecast
+
+forecast-empty = Ningún proyecto será candidato a archivarse en los próximos { $days } días.
+forecast-header = { $count ->
+    [one] 1 proyecto será candidato a archivarse en los próximos { $days } días:
+   *[other] { $count } proyectos serán candidatos a archivarse en los próximos { $days } días:
+}
+forecast-entry = { $name } el { $date } (en { $days } días)
+invalid-period = Periodo '{ $value }' no válido, se espera un número de días como '30d' o de semanas como '4w'.
+
+## duplicates
+
+duplicates-empty = No se encontraron proyectos duplicados.
+duplicates-header = { $count ->
+    [one] 1 grupo de proyectos duplicados:
+   *[other] { $count } grupos de proyectos duplicados:
+}
+duplicates-remote = Mismo remoto: { $url }
+duplicates-content = Contenido idéntico
+duplicates-keep = conservar  { $path } (última actividad { $date })
+duplicates-copy = copia      { $path } (última actividad { $date })
+duplicates-hint = Activa archive_duplicates = true para archivar las copias más antiguas en la próxima ejecución.
+
+## plan
+
+plan-written = Plan escrito en { $path }:
+plan-apply-hint = Revísalo y luego ejecuta 'archiver apply <archivo>' para llevarlo a cabo.
+
+## artifacts
+
+artifacts-disabled = El archivado solo de artefactos está desactivado. Define 'artifact_days' para activarlo.
+artifacts-none = No hay artefactos de compilación que mostrar.
+artifacts-would-move = { $count ->
+    [one] Se moverían los artefactos de 1 proyecto ({ $size }):
+   *[other] Se moverían los artefactos de { $count } proyectos ({ $size }):
+}
+artifacts-moved = { $count ->
+    [one] Se movieron los artefactos de 1 proyecto ({ $size }):
+   *[other] Se movieron los artefactos de { $count } proyectos ({ $size }):
+}
+artifacts-list = { $count ->
+    [one] Artefactos de 1 proyecto en el archivo ({ $size }):
+   *[other] Artefactos de { $count } proyectos en el archivo ({ $size }):
+}
+artifacts-restored = Se restauraron los artefactos de compilación de '{ $name }' ({ $size }).
+
+## serve
+
+serve-listening = Sirviendo el panel en { $url } (Ctrl-C para detenerlo).
+
+## digest
+
+digest-header = Resumen de archiver, del { $start } al { $end }
+digest-summary = { $runs ->
+    [one] 1 ejecución
+   *[other] { $runs } ejecuciones
+}: { $archived ->
+    [one] se archivó 1 proyecto
+   *[other] se archivaron { $archived } proyectos
+}, { $deleted ->
+    [one] se eliminó 1 del archivo
+   *[other] se eliminaron { $deleted } del archivo
+} y se liberaron { $freed }.
+digest-usage = Proyectos: { $active }, archivo: { $archive }.
+digest-upcoming = { $count ->
+    [one] 1 proyecto archivado se eliminará en los próximos { $days } días.
+   *[other] { $count } proyectos archivados se eliminarán en los próximos { $days } días.
+}
+digest-archived-on = archivado el { $date }
+
+## index
+
+index-compacted = { $count ->
+    [one] Se eliminó 1 línea de historial de { $path }.
+   *[other] Se eliminaron { $count } líneas de historial de { $path }.
+}
+index-converted = { $count ->
+    [one] Se convirtió 1 registro en { $path }.
+   *[other] Se convirtieron { $count } registros en { $path }.
+}
+index-nothing-to-convert = Nada que convertir; el registro del archivo ya es { $path }.
+
+## remote
+
+remote-none = No hay máquinas remotas configuradas. Añádelas al ajuste 'remotes'.
+remote-dirs = proyectos: { $projects }; archivo: { $archive }
+remote-no-projects = No se encontraron proyectos en '{ $name }'.
+remote-would-archive = { $count ->
+    [one] Se archivaría 1 proyecto en '{ $name }':
+   *[other] Se archivarían { $count } proyectos en '{ $name }':
+}
+remote-archived = { $count ->
+    [one] Se archivó 1 proyecto en '{ $name }':
+   *[other] Se archivaron { $count } proyectos en '{ $name }':
+}
+
+## stats
+
+stats-active = Directorios de proyectos
+stats-archive = Archivo
+stats-archived-projects = Proyectos archivados
+stats-freed = Liberado desde { $date }
+stats-runs = Ejecuciones registradas
+stats-trend-empty = Aún no hay uso de disco registrado. Cada ejecución real añade una muestra.
+stats-trend-header = { $runs ->
+    [one] Uso de disco en 1 ejecución registrada:
+   *[other] Uso de disco en { $runs } ejecuciones registradas:
+}
+stats-trend-week = semana del { $date }
+stats-trend-entry = { $period } activo { $active } { $change } archivo { $archive }   liberado { $freed }
+stats-trend-footer = El uso activo cambió en { $change } desde el { $date }.
+
+## list
+
+list-empty = No hay proyectos archivados actualmente.
+list-header = Proyectos archivados:
+list-entry = { $name } (Archivado el: { $date })
+list-entry-stats = { $language }, { $lines } líneas de código
+list-entry-group = grupo { $group }
+list-tree-missing = (no está en el disco)
+
+## settings
+
+settings-migrated = Tu archivo de configuración se actualizó de la versión { $from } a la { $to }. El original se guardó como { $backup }.
+settings-migration-failed = No se pudo actualizar tu archivo de configuración
+
+## paths
+
+paths-header = Rutas de configuración:
+paths-projects-dir = Directorio de proyectos:    { $path }
+paths-archive-dir = Directorio de archivo:      { $path }
+paths-index-dir = Registro de archivo en:     { $path }
+paths-config-file = Archivo de configuración:   { $path }
+
+## exclude
+
+exclude-removed = El proyecto '{ $name }' se ha quitado de la lista de exclusión.
+exclude-not-listed = El proyecto '{ $name }' no estaba en la lista de exclusión. No se hicieron cambios.
+exclude-already-listed = El proyecto '{ $name }' ya está en la lista de exclusión.
+exclude-added = El proyecto '{ $name }' se ha añadido a la lista de exclusión.
+exclude-added-until = El proyecto '{ $name }' queda excluido del archivado hasta el { $date }.
+exclude-updated = El proyecto '{ $name }' queda excluido de forma permanente.
+exclude-list-empty = La lista de exclusión está vacía.
+exclude-list-header = Exclusiones (comparadas con { $path }):
+exclude-kind-exact = exacta
+exclude-kind-glob = glob
+exclude-status-exists = existe
+exclude-status-missing = no encontrado
+exclude-status-matches = { $count ->
+    [one] 1 coincidencia
+   *[other] { $count } coincidencias
+}
+exclude-expiry-never = permanente
+exclude-expiry-until = hasta el { $date }
+exclude-expiry-expired = caducó el { $date }
+
+## simulate
+
+simulate-dir-exists = '{ $path }' ya existe; las simulaciones solo se generan en un directorio nuevo.
+simulate-generated = Se generaron { $count } proyectos ({ $size } como máximo) en { $path } en { $seconds }s.
+simulate-dry-run = Simulación: { $scanned } proyectos analizados, { $archived } se archivarían ({ $size }) en { $seconds }s.
+simulate-archive = Archivado: { $scanned } proyectos analizados, { $archived } archivados ({ $size }) en { $seconds }s.
+invalid-size = Tamaño '{ $value }' no válido, se espera un número de bytes con un sufijo opcional K, M, G o T.
+
+## explain
+
+explain-list-header = Códigos de error:
+explain-causes = Causas probables
+explain-fixes = Cómo solucionarlo
+
+explain-a001-title = Permiso denegado
+explain-a001-causes =
+    El directorio de proyectos, el del archivo o un proyecto dentro de ellos no es escribible
+    por tu usuario, o está en un sistema de archivos de solo lectura.
+explain-a001-fixes =
+    Revisa el propietario y los permisos de las rutas que muestra 'archiver paths', por ejemplo
+    con 'ls -ld'. Corrígelos con 'chown'/'chmod', o apunta 'archive_dir' a una ruta escribible.
+
+explain-a002-title = Registro del archivo dañado
+explain-a002-causes =
+    El registro del archivo (archive.json o archive.jsonl) no es JSON válido o contiene un registro inválido,
+    normalmente tras una edición manual, una escritura interrumpida o un conflicto de sincronización.
+explain-a002-fixes =
+    Abre el registro en el directorio del archivo y corrige la línea indicada, o recupéralo de una
+    copia de seguridad. Si el archivo se sincroniza, busca copias en conflicto de archive.*.json.
+
+explain-a003-title = Configuración no válida
+explain-a003-causes =
+    El archivo de configuración tiene un error de sintaxis o un valor de tipo incorrecto, o una
+    variable de entorno ARCHIVER__* sobrescribe un ajuste con un valor no válido.
+explain-a003-fixes =
+    Ejecuta 'archiver config' para reescribir la configuración, o corrige el archivo que muestra
+    'archiver paths'. Revisa el entorno con 'env | grep ARCHIVER'.
+
+explain-a004-title = Error de Git
+explain-a004-causes =
+    No se pudo leer un repositorio: está dañado, usa una extensión no soportada, o es un clon
+    parcial o un worktree sin sus metadatos.
+explain-a004-fixes =
+    Ejecuta 'git status' y 'git fsck' en el repositorio. Exclúyelo con 'archiver exclude <nombre>'
+    si no se puede reparar.
+
+explain-a005-title = Directorio personal no encontrado
+explain-a005-causes =
+    La variable de entorno HOME no está definida o está vacía, como ocurre en algunos gestores de
+    servicios y contenedores.
+explain-a005-fixes =
+    Define HOME para el proceso que ejecuta el archiver, por ejemplo con
+    'Environment=HOME=/home/tu' en una unidad de systemd.
+
+explain-a006-title = Proyecto no encontrado en el archivo
+explain-a006-causes =
+    Ningún proyecto archivado tiene este nombre o ruta relativa. Puede que ya se haya restaurado
+    o eliminado, o que el nombre esté mal escrito.
+explain-a006-fixes =
+    Ejecuta 'archiver list' para ver los proyectos archivados y sus nombres exactos. El error
+    sugiere los nombres parecidos; activa 'loose_name_matching = true' para aceptar también
+    nombres que solo difieren en mayúsculas o en la normalización Unicode.
+
+explain-a007-title = Nombre de proyecto ambiguo
+explain-a007-causes =
+    Varios proyectos archivados comparten este nombre, por ejemplo 'api' de dos organizaciones en
+    una disposición anidada.
+explain-a007-fixes =
+    Usa la ruta relativa que muestra 'archiver list', por ejemplo 'github.com/org/api'.
+
+explain-a008-title = Ruta insegura
+explain-a008-causes =
+    Un nombre de proyecto contiene separadores o '..', o un registro del archivo apunta fuera del
+    directorio del archivo. La operación se rechazó para no tocar archivos ajenos.
+explain-a008-fixes =
+    Usa un nombre de proyecto simple. Si el registro se editó a mano, corrige el 'archive_path'
+    para que quede dentro del directorio del archivo.
+
+explain-a009-title = El destino ya existe
+explain-a009-causes =
+    Ya existe un directorio donde se movería el proyecto, por ejemplo porque se volvió a crear o
+    se copió de vuelta después de archivarlo.
+explain-a009-fixes =
+    Compruébalo con 'archiver restore <nombre> --preview' y mueve o elimina el directorio
+    existente antes de reintentar.
+
+explain-a010-title = Ruta no encontrada
+explain-a010-causes =
+    El directorio de proyectos o el del archivo no existe, o un proyecto se movió o eliminó a mano
+    mientras el archiver trabajaba con él.
+explain-a010-fixes =
+    Revisa las rutas que muestra 'archiver paths' y crea los directorios que falten, o cámbialas
+    con 'archiver config'.
+
+explain-a011-title = Falló la consulta a la forja
+explain-a011-causes =
+    El comando 'gh' o 'glab' usado para buscar pull/merge requests abiertas no está instalado, no
+    tiene sesión iniciada o no pudo contactar con el servidor.
+explain-a011-fixes =
+    Ejecuta 'gh auth status' o 'glab auth status'. Desactiva 'check_open_reviews' si no usas la
+    integración.
+
+explain-a012-title = Máquina remota inaccesible
+explain-a012-causes =
+    El remoto no figura en el ajuste 'remotes', SSH no pudo conectar con su host, o falló un
+    comando ejecutado allí (directorio inexistente, permisos insuficientes, un destino que ya
+    existe en su archivo).
+explain-a012-fixes =
+    Comprueba que 'ssh <host> true' funciona sin pedir contraseña y que los directorios del
+    remoto existen en esa máquina. Ejecuta 'archiver remote list' para revisar la configuración.
+
+explain-a013-title = El proyecto restaurado no coincide con su archivo
+explain-a013-causes =
+    Tras restaurarlo, los archivos del proyecto difieren de los que tenía al archivarlo: se
+    borraron, añadieron o modificaron archivos dentro del archivo, o el movimiento se
+    interrumpió.
+explain-a013-fixes =
+    El proyecto se devolvió al archivo y sigue en el registro del archivo. Compáralo con tu
+    remoto o tus copias de seguridad, y corrige o reemplaza la copia archivada antes de
+    restaurarlo de nuevo.
+
+explain-a014-title = El plan guardado está desactualizado
+explain-a014-causes =
+    Desde que se hizo el plan, un proyecto que archiva se eliminó, tuvo actividad nueva o quedó
+    protegido, o un archivo que elimina se restauró, se eliminó o ya no toca eliminarlo.
+explain-a014-fixes =
+    No se cambió nada. Haz un plan nuevo con 'archiver plan --out <archivo>', revísalo y aplica
+    ese en su lugar.
+
+explain-a015-title = Ejecución interrumpida
+explain-a015-causes =
+    La ejecución se detuvo con Ctrl-C. Se terminó de mover el proyecto en curso y el registro
+    del archivo incluye todo lo archivado hasta entonces; los proyectos restantes se guardaron
+    como punto de reanudación.
+explain-a015-fixes =
+    Ejecuta 'archiver run --resume' para continuar con los proyectos restantes, o un simple
+    'archiver run' para volver a escanear desde cero.
+
+explain-a016-title = Archivo de estado dañado
+explain-a016-causes =
+    Un archivo que el archiver guarda además del registro del archivo (resume.json, restores.json,
+    artifacts.json, un plan guardado, un sidecar de metadatos o una caché del directorio de estado)
+    no es JSON válido, normalmente tras una edición manual o una escritura interrumpida.
+explain-a016-fixes =
+    Corrige el archivo o bórralo. Las cachés y los recuentos de restauraciones se vuelven a generar;
+    un punto de reanudación o un plan guardado que se borre simplemente se pierde.
+
+explain-a099-title = Error inesperado
+explain-a099-causes =
+    Un error sin un código más específico.
+explain-a099-fixes =
+    Vuelve a ejecutar el comando con -vv y revisa el archivo de log en el directorio de estado.
+    Repórtalo si el problema persiste.
+
+## mangen
+
+mangen-written = Se escribieron { $count } páginas de manual en { $path }.
@@ -0,0 +1,87 @@
+//! Localization of the user-facing CLI output.
+//!
+//! Messages live in Fluent catalogs under `locales/`, embedded at compile time. The language
+//! is taken from `ARCHIVER_LANG`, then the usual `LC_ALL`/`LC_MESSAGES`/`LANG` variables, and
+//! any message missing from the selected catalog falls back to English.
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+const LANG_VARS: [&str; 4] = ["ARCHIVER_LANG", "LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// Available catalogs, English first as it is the fallback.
+const CATALOGS: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+];
+
+struct Catalogs {
+    selected: FluentBundle<FluentResource>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+static CATALOGS_CELL: OnceLock<Catalogs> = OnceLock::new();
+
+/// Translates a message, falling back to English and then to the bare key.
+pub fn tr(key: &str, args: Option<&FluentArgs>) -> String {
+    let catalogs = CATALOGS_CELL.get_or_init(|| Catalogs {
+        selected: bundle_for(&detect_language()),
+        fallback: bundle_for("en"),
+    });
+
+    [&catalogs.selected, &catalogs.fallback]
+        .into_iter()
+        .find_map(|bundle| {
+            let pattern = bundle.get_message(key)?.value()?;
+            let mut errors = vec![];
+            Some(
+                bundle
+                    .format_pattern(pattern, args, &mut errors)
+                    .into_owned(),
+            )
+        })
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Returns the two-letter code of the first supported language found in the environment.
+fn detect_language() -> String {
+    LANG_VARS
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .filter(|value| !value.is_empty())
+        .map(|value| value.chars().take(2).collect::<String>().to_lowercase())
+        .find(|code| CATALOGS.iter().any(|(lang, _)| lang == code))
+        .unwrap_or_else(|| "en".to_string())
+}
+
+fn bundle_for(lang: &str) -> FluentBundle<FluentResource> {
+    let (lang, source) = CATALOGS
+        .iter()
+        .find(|(code, _)| *code == lang)
+        .unwrap_or(&CATALOGS[0]);
+    let lang_id: LanguageIdentifier = lang.parse().expect("catalog language codes are valid");
+    let resource =
+        FluentResource::try_new(source.to_string()).expect("embedded catalogs are valid Fluent");
+
+    let mut bundle = FluentBundle::new_concurrent(vec![lang_id]);
+    // Unicode isolation marks would end up in plain terminal output.
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .expect("embedded catalogs have no duplicate messages");
+    bundle
+}
+
+/// Formats a localized message: `t!("key")` or `t!("key", name = value, ...)`.
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::tr($key, None)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::tr($key, Some(&args))
+    }};
+}
+pub(crate) use t;
@@ -1,14 +1,31 @@
 use anyhow::{Context, Result, anyhow};
-use archiver_core::{ActionPlan, Archiver, Settings};
-use clap::{ArgAction, ColorChoice, Parser, Subcommand};
+use archiver_core::{
+    Archiver, DuplicateKey, ErrorCode, Exclusion, LogFormat, Outcome, ProjectName, RemoteProject,
+    RestoreFilter, RunSummary, SavedPlan, Settings, TreeNode, UsageSample, dashboard, interrupt,
+    prompt::PromptStatus,
+    simulate::{self, SimulationSpec},
+    trend::{self, Period},
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use clap::{ArgAction, ColorChoice, CommandFactory, Parser, Subcommand, ValueEnum};
 use console::style;
 use dialoguer::{Confirm, Input};
 use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
 use tracing::level_filters::LevelFilter;
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{fmt, Layer};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, fmt};
+
+mod i18n;
+#[cfg(feature = "otel")]
+mod telemetry;
+mod ui;
+
+use i18n::t;
 
 /// A CLI tool to automatically archive inactive git repositories.
 #[derive(Parser, Debug)]
@@ -23,6 +40,30 @@ struct Cli {
     #[arg(long, value_name = "WHEN", global = true, default_value_t = ColorChoice::Auto)]
     color: ColorChoice,
 
+    /// Plain-ASCII accessible output for screen readers and dumb terminals:
+    /// no box-drawing characters, emoji, spinners or color-only cues.
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Format of the log files: 'text' or 'json' (JSON lines). Overrides the `log_format` setting.
+    #[arg(long, value_name = "FORMAT", global = true)]
+    log_format: Option<LogFormat>,
+
+    /// Maximum number of projects worked on at the same time; 0 uses one per CPU. Overrides
+    /// the `jobs` setting.
+    #[arg(short, long, value_name = "N", global = true)]
+    jobs: Option<usize>,
+
+    /// Timezone dates are shown in: 'UTC', 'local' or a name such as 'Europe/Madrid'.
+    /// Overrides the `timezone` setting.
+    #[arg(long, value_name = "TZ", global = true)]
+    timezone: Option<String>,
+
+    /// How dates are shown, as a strftime pattern (e.g. '%d/%m/%Y'). Overrides the
+    /// `date_format` setting.
+    #[arg(long, value_name = "FORMAT", global = true)]
+    date_format: Option<String>,
+
     /// If no subcommand is provided, the TUI will be launched.
     #[command(subcommand)]
     command: Option<Commands>,
@@ -40,6 +81,26 @@ enum Commands {
         /// Perform a dry run without moving any files.
         #[arg(long)]
         dry_run: bool,
+        /// Tag the projects archived in this run, to restore them together later.
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        /// Archive the projects of this run in a group (overrides `group_by_root`).
+        #[arg(long, value_name = "GROUP")]
+        group: Option<String>,
+        /// Continue a run stopped with Ctrl-C with the projects it had left.
+        #[arg(long, conflicts_with_all = ["dry_run", "tags"])]
+        resume: bool,
+    },
+    /// Saves what a run would do to a file, to review it before applying it.
+    Plan {
+        /// File to write the plan to, as JSON.
+        #[arg(long, value_name = "FILE")]
+        out: PathBuf,
+    },
+    /// Applies a plan saved by `plan`, refusing if the projects or the archive changed since.
+    Apply {
+        /// Plan file written by `plan --out`.
+        plan: PathBuf,
     },
     /// Restore one or all archived projects.
     #[command(visible_alias = "r")]
@@ -49,6 +110,24 @@ enum Commands {
         /// Restore all projects from the archive.
         #[arg(long, short, conflicts_with = "name")]
         all: bool,
+        /// Show what would be moved where, without restoring anything.
+        #[arg(long, requires = "name")]
+        preview: bool,
+        /// Add the restored project to the exclusion list (default: `exclude_on_restore`).
+        #[arg(long, requires = "name")]
+        and_exclude: bool,
+        /// Restore the projects archived on or after this date (YYYY-MM-DD).
+        #[arg(long, value_name = "DATE", conflicts_with_all = ["name", "all"])]
+        archived_after: Option<NaiveDate>,
+        /// Restore the projects archived before this date (YYYY-MM-DD).
+        #[arg(long, value_name = "DATE", conflicts_with_all = ["name", "all"])]
+        archived_before: Option<NaiveDate>,
+        /// Restore the projects archived with this tag.
+        #[arg(long, value_name = "TAG", conflicts_with_all = ["name", "all"])]
+        tag: Option<String>,
+        /// Restore the projects of this group.
+        #[arg(long, value_name = "GROUP", conflicts_with_all = ["name", "all"])]
+        group: Option<String>,
     },
     /// Delete one or all projects permanently from the archive.
     #[command(visible_alias = "d")]
@@ -58,65 +137,435 @@ enum Commands {
         /// Delete ALL projects from the archive. This is irreversible.
         #[arg(long, short, conflicts_with = "name")]
         all: bool,
+        /// Delete every project of this group from the archive. This is irreversible.
+        #[arg(long, value_name = "GROUP", conflicts_with_all = ["name", "all"])]
+        group: Option<String>,
     },
-    /// Add or remove a project from the exclusion list.
-    #[command(visible_alias = "e")]
+    /// Manage the exclusion list. `exclude <NAME>` is short for `exclude add <NAME>`, and
+    /// `exclude` alone lists the exclusions.
+    #[command(visible_alias = "e", args_conflicts_with_subcommands = true)]
     Exclude {
+        #[command(subcommand)]
+        action: Option<ExcludeAction>,
         /// The name of the project to add or remove.
-        project_name: String,
+        project_name: Option<ProjectName>,
         /// Remove the project from the exclusion list.
-        #[arg(long, short)]
+        #[arg(long, short, requires = "project_name")]
         remove: bool,
     },
+    /// List the projects that will become archive candidates soon.
+    #[command(visible_alias = "f")]
+    Forecast {
+        /// How far ahead to look, in days (`30`, `30d`) or weeks (`4w`).
+        #[arg(long, value_name = "PERIOD", default_value = "30d", value_parser = parse_days)]
+        within: u64,
+    },
+    /// List projects that are copies of each other (same remote or same content).
+    Duplicates,
+    /// Show the disk usage of the projects and the archive, or its evolution with --trend.
+    Stats {
+        /// Show how disk usage evolved over the recorded runs.
+        #[arg(long)]
+        trend: bool,
+        /// Period to group the trend by.
+        #[arg(long, value_enum, default_value_t = TrendPeriod::Week, requires = "trend")]
+        by: TrendPeriod,
+    },
+    /// Move only the build artifacts (e.g. node_modules) of idle projects into the archive,
+    /// leaving their sources in place. Requires the 'artifact_days' setting.
+    Artifacts {
+        #[command(subcommand)]
+        action: ArtifactsAction,
+    },
+    /// Summarize the runs of the last complete week or month, e.g. from a systemd timer.
+    Digest {
+        /// Period to summarize.
+        #[arg(long, value_enum, default_value_t = TrendPeriod::Week)]
+        by: TrendPeriod,
+        /// Also send the digest as a desktop notification.
+        #[arg(long)]
+        notify: bool,
+    },
+    /// Prints the number of projects waiting to be archived, for a shell prompt (e.g. `3⚑`).
+    /// Nothing is printed when there are none.
+    PromptStatus {
+        /// Count the candidates now instead of reading the cached count.
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Serves a read-only HTML dashboard of the candidates, the archive and the recent runs.
+    Serve {
+        /// Address to listen on. Use 0.0.0.0 to reach it from other machines.
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+        addr: String,
+    },
+    /// Scan and archive the projects of the machines listed in the 'remotes' setting, over SSH.
+    Remote {
+        #[command(subcommand)]
+        action: RemoteAction,
+    },
+    /// Maintain the archive log: compact the append-only format or convert between formats.
+    Index {
+        #[command(subcommand)]
+        action: IndexAction,
+    },
     /// List all currently archived projects.
     #[command(visible_alias = "l")]
-    List,
+    List {
+        /// Only list the projects of this group.
+        #[arg(long, value_name = "GROUP")]
+        group: Option<String>,
+        /// Show the archive as a tree of archive directories, groups (or years) and projects,
+        /// with their sizes on disk.
+        #[arg(long)]
+        tree: bool,
+    },
     /// Show the configuration paths being used.
     Paths,
+    /// Generate synthetic projects and run the pipeline on them, to benchmark scanning and
+    /// archiving or reproduce scaling bugs. Never touches the configured directories.
+    #[command(hide = true)]
+    Simulate {
+        /// Number of projects to generate.
+        #[arg(long, default_value_t = 100)]
+        projects: usize,
+        /// Upper bound for the total size of the generated files (e.g. 500M, 1G).
+        #[arg(long, value_name = "SIZE", default_value = "64M", value_parser = parse_size)]
+        max_size: u64,
+        /// Seed for the generator; the same seed produces the same tree.
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+        /// Directory to generate into. Defaults to a temporary directory, removed afterwards.
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+    /// Explain an error code (e.g. A002): its likely causes and how to fix it.
+    Explain {
+        /// The error code to explain. Lists every code when omitted.
+        code: Option<ErrorCode>,
+    },
+    /// Generate roff man pages for archiver and all its subcommands.
+    Mangen {
+        /// Directory to write the pages into (created if missing).
+        dir: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ExcludeAction {
+    /// Add a project name or glob pattern (e.g. 'scratch-*') to the exclusion list.
+    Add {
+        /// The project name or glob pattern.
+        pattern: ProjectName,
+        /// Only exclude the project(s) until this date, inclusive (YYYY-MM-DD).
+        #[arg(long, value_name = "DATE")]
+        until: Option<NaiveDate>,
+    },
+    /// Remove a project name or glob pattern from the exclusion list.
+    #[command(visible_alias = "rm")]
+    Remove {
+        /// The project name or glob pattern, as listed.
+        pattern: ProjectName,
+    },
+    /// Show every exclusion, whether it matches existing projects, and its expiry.
+    #[command(visible_alias = "ls")]
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum ArtifactsAction {
+    /// Move the artifacts of the projects idle for 'artifact_days' into the archive.
+    Run {
+        /// Show what would be moved without moving anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Show the projects whose artifacts are in the archive.
+    #[command(visible_alias = "ls")]
+    List,
+    /// Move the archived artifacts of a project back into it.
+    Restore {
+        /// The name of the project.
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum RemoteAction {
+    /// Show the configured remote machines.
+    #[command(visible_alias = "ls")]
+    List,
+    /// List the projects of a remote machine with their last activity.
+    Scan {
+        /// The name of the remote, as configured.
+        name: String,
+    },
+    /// Archive the inactive projects of a remote machine into its own archive directory.
+    Run {
+        /// The name of the remote, as configured.
+        name: String,
+        /// Show what would be archived without moving anything.
+        #[arg(long)]
+        dry_run: bool,
+        /// Tag the projects archived in this run.
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum IndexAction {
+    /// Rewrite the append-only archive log with only the current records, dropping history.
+    Compact,
+    /// Convert the archive log to the format set in 'index_format'.
+    Convert,
+}
+
+/// Period `stats --trend` groups the usage history by.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum TrendPeriod {
+    Week,
+    Month,
+}
+
+impl From<TrendPeriod> for Period {
+    fn from(period: TrendPeriod) -> Self {
+        match period {
+            TrendPeriod::Week => Self::Week,
+            TrendPeriod::Month => Self::Month,
+        }
+    }
 }
 
 #[cfg(target_os = "linux")]
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
-    let _guard = init_tracing(cli.verbose, cli.color).context("Failed to initialize logging")?;
+    // The prompt only reads the cached count: no team policy download and no logging, which
+    // could hold up the prompt or write into it.
+    if let Some(Commands::PromptStatus { refresh: false }) = cli.command {
+        ui::init(cli.color, cli.ascii);
+        print_prompt_status();
+        return Ok(ExitCode::SUCCESS);
+    }
+    let refreshing_prompt = matches!(cli.command, Some(Commands::PromptStatus { refresh: true }));
+    // Migrate before anything loads the settings, and report it once the output is set up.
+    let migration = Settings::config_path()
+        .ok()
+        .filter(|path| path.exists())
+        .map(|path| archiver_core::migrate::migrate_file(&path));
+    // The team policy does not set the log format: it is fetched later, once its failures
+    // can be logged.
+    let log_format = cli
+        .log_format
+        .or_else(|| Settings::new_local().ok().map(|s| s.log_format))
+        .unwrap_or_default();
+    let colors = ui::init(cli.color, cli.ascii);
+    let _guard = init_tracing(cli.verbose, colors, log_format).context(t!("error-init-logging"))?;
+    // A file that cannot be migrated is reported here; commands that need the settings then
+    // fail on it, while the others (e.g. `init`, `explain`) still work.
+    match migration {
+        Some(Ok(report)) => report_migration(&report),
+        Some(Err(e)) => {
+            report_error(&anyhow::Error::from(e).context(t!("settings-migration-failed")))
+        }
+        None => {}
+    }
 
     match cli.command {
-        Some(command) => handle_command(command),
+        Some(command) => {
+            let handled = handle_command(
+                command,
+                Overrides {
+                    jobs: cli.jobs,
+                    timezone: cli.timezone,
+                    date_format: cli.date_format,
+                },
+            );
+            // Whether it counted or failed, the refresh is over and the next prompt may start one.
+            if refreshing_prompt {
+                release_prompt_refresh();
+            }
+            match handled {
+                Ok(()) => Ok(ExitCode::SUCCESS),
+                Err(e) => {
+                    report_error(&e);
+                    Ok(ExitCode::FAILURE)
+                }
+            }
+        }
         None => {
-            println!("TUI mode is not yet implemented. Use a subcommand like 'run' or 'list'.");
-            println!("For help, run 'archive --help'.");
-            Ok(())
+            println!("{}", t!("tui-not-implemented"));
+            println!("{}", t!("help-hint"));
+            Ok(ExitCode::SUCCESS)
         }
     }
 }
 
+/// Tells the user their settings file was upgraded, and where the original was saved.
+fn report_migration(report: &archiver_core::migrate::MigrationReport) {
+    let Some(backup) = &report.backup else {
+        return;
+    };
+    println!(
+        "{}",
+        style(t!(
+            "settings-migrated",
+            from = report.from_version,
+            to = archiver_core::migrate::CURRENT_VERSION,
+            backup = backup.display().to_string()
+        ))
+        .yellow()
+    );
+    for step in &report.applied {
+        println!("  - {}", step);
+    }
+}
+
+/// Prints an error with its cause chain, tagged with the stable code of the underlying
+/// archiver error when there is one, and a pointer to `archiver explain`.
+fn report_error(error: &anyhow::Error) {
+    let code = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<archiver_core::Error>())
+        .map(|e| e.code());
+    let label = match code {
+        Some(code) => format!("{}[{}]:", t!("error-label"), code),
+        None => format!("{}:", t!("error-label")),
+    };
+    eprintln!("{} {:?}", style(label).red().bold(), error);
+    if let Some(code) = code {
+        eprintln!("\n{}", t!("error-code-hint", code = code.as_str()));
+    }
+}
+
 #[cfg(not(target_os = "linux"))]
 fn main() -> Result<()> {
-    println!("Error: This application is currently only supported on Linux.");
+    println!("{}", t!("unsupported-platform"));
     std::process::exit(1);
 }
 
-fn handle_command(command: Commands) -> Result<()> {
+/// Global flags that override settings.
+struct Overrides {
+    jobs: Option<usize>,
+    timezone: Option<String>,
+    date_format: Option<String>,
+}
+
+impl Overrides {
+    fn apply(self, settings: &mut Settings) {
+        if let Some(jobs) = self.jobs {
+            settings.jobs = jobs;
+        }
+        if let Some(timezone) = self.timezone {
+            settings.timezone = timezone;
+        }
+        if let Some(date_format) = self.date_format {
+            settings.date_format = date_format;
+        }
+    }
+}
+
+fn handle_command(command: Commands, overrides: Overrides) -> Result<()> {
     // Los comandos que no necesitan un `Archiver` se manejan primero.
     match command {
         Commands::Init => return handle_init(),
         Commands::Config => return handle_config(),
+        Commands::Simulate {
+            projects,
+            max_size,
+            seed,
+            dir,
+        } => {
+            let spec = SimulationSpec {
+                projects,
+                max_size,
+                seed,
+            };
+            return handle_simulate(&spec, dir);
+        }
+        Commands::Explain { code } => {
+            handle_explain(code);
+            return Ok(());
+        }
         Commands::Exclude {
+            action,
             project_name,
             remove,
-        } => return handle_exclude(&project_name, remove),
+        } => {
+            return match (action, project_name) {
+                (Some(ExcludeAction::Add { pattern, until }), _) => {
+                    handle_exclude(&pattern, false, until)
+                }
+                (Some(ExcludeAction::Remove { pattern }), _) => {
+                    handle_exclude(&pattern, true, None)
+                }
+                (None, Some(name)) => handle_exclude(&name, remove, None),
+                (Some(ExcludeAction::List), _) | (None, None) => handle_exclude_list(),
+            };
+        }
+        Commands::Mangen { dir } => return handle_mangen(&dir),
         _ => {}
     }
 
-    let settings =
-        Settings::new().context("Failed to load settings. Try running 'archive init'")?;
+    let mut settings = Settings::new().context(t!("error-load-settings"))?;
+    overrides.apply(&mut settings);
+    ui::set_date_formatter(settings.date_formatter().context(t!("error-dates"))?);
+    if let Commands::Run {
+        group: Some(group), ..
+    } = &command
+    {
+        settings.archive_group = Some(group.clone());
+    }
     let archiver = Archiver::new(settings);
 
     match command {
-        Commands::Run { dry_run } => handle_run(&archiver, dry_run)?,
-        Commands::Restore { name, all } => handle_restore(&archiver, name, all)?,
-        Commands::Delete { name, all } => handle_delete(&archiver, name, all)?,
-        Commands::List => handle_list(&archiver)?,
+        Commands::Run { resume: true, .. } => handle_resume(&archiver)?,
+        Commands::Run { dry_run, tags, .. } => handle_run(&archiver, dry_run, &tags)?,
+        Commands::Plan { out } => handle_plan(&archiver, &out)?,
+        Commands::Apply { plan } => handle_apply(&archiver, &plan)?,
+        Commands::Restore {
+            name,
+            all,
+            preview,
+            and_exclude,
+            archived_after,
+            archived_before,
+            tag,
+            group,
+        } => {
+            let filter = RestoreFilter {
+                archived_after: archived_after.map(start_of_day),
+                archived_before: archived_before.map(start_of_day),
+                tag,
+                group,
+            };
+            match name {
+                Some(name) if preview => handle_restore_preview(&archiver, &name)?,
+                None if filter.archived_after.is_some()
+                    || filter.archived_before.is_some()
+                    || filter.tag.is_some()
+                    || filter.group.is_some() =>
+                {
+                    handle_restore_matching(&archiver, &filter)?
+                }
+                name => handle_restore(&archiver, name, all, and_exclude)?,
+            }
+        }
+        Commands::Delete {
+            group: Some(group), ..
+        } => handle_delete_group(&archiver, group)?,
+        Commands::Delete { name, all, .. } => handle_delete(&archiver, name, all)?,
+        Commands::Forecast { within } => handle_forecast(&archiver, within)?,
+        Commands::Duplicates => handle_duplicates(&archiver)?,
+        Commands::Stats { trend, by } => handle_stats(&archiver, trend, by.into())?,
+        Commands::Artifacts { action } => handle_artifacts(&archiver, action)?,
+        Commands::Digest { by, notify } => handle_digest(&archiver, by.into(), notify)?,
+        // Without `--refresh`, `main` prints the cache before anything is loaded.
+        Commands::PromptStatus { .. } => refresh_prompt_status(&archiver),
+        Commands::Serve { addr } => handle_serve(&archiver, &addr)?,
+        Commands::Remote { action } => handle_remote(&archiver, action)?,
+        Commands::Index { action } => handle_index(&archiver, action)?,
+        Commands::List { group, tree } => handle_list(&archiver, group.as_deref(), tree)?,
         Commands::Paths => handle_paths(archiver.settings())?,
         _ => unreachable!(),
     }
@@ -124,54 +573,64 @@ fn handle_command(command: Commands) -> Result<()> {
 }
 
 fn handle_init() -> Result<()> {
-    println!("{}", style("Welcome to Auto Archiver setup!").bold());
+    println!("{}", style(t!("init-welcome")).bold());
     let config_path = Settings::config_path()?;
     if config_path.exists() {
-        let overwrite = Confirm::new()
-            .with_prompt("A configuration file already exists. Do you want to overwrite it?")
+        let overwrite = Confirm::with_theme(&*ui::theme())
+            .with_prompt(t!("init-overwrite-prompt"))
             .default(false)
             .interact()?;
         if !overwrite {
-            println!("Initialization cancelled.");
+            println!("{}", t!("init-cancelled"));
             return Ok(());
         }
     }
     let new_settings = interactive_config_update(None)?;
     save_settings(&new_settings)?;
-    println!(
-        "\n{}",
-        style("Configuration saved successfully!").green().bold()
-    );
+    println!("\n{}", style(t!("init-saved")).green().bold());
     Ok(())
 }
 
 fn handle_config() -> Result<()> {
-    println!(
-        "{}",
-        style("Updating Auto Archiver configuration...").bold()
-    );
-    let existing_settings = Settings::new().context("Failed to load existing settings.")?;
+    println!("{}", style(t!("config-updating")).bold());
+    let existing_settings = Settings::new_local().context(t!("error-load-existing-settings"))?;
     let new_settings = interactive_config_update(Some(&existing_settings))?;
     save_settings(&new_settings)?;
-    println!(
-        "\n{}",
-        style("Configuration updated successfully!").green().bold()
-    );
+    println!("\n{}", style(t!("config-updated")).green().bold());
     Ok(())
 }
 
+/// Keeps the logging backends alive (and flushing) until the end of `main`.
+struct TracingGuard {
+    _file: WorkerGuard,
+    #[cfg(feature = "otel")]
+    _otel: Option<telemetry::OtelGuard>,
+}
+
 /// Initializes a dual logging system: to console and to a daily rolling file.
-fn init_tracing(verbosity: u8, color: ColorChoice) -> Result<WorkerGuard> {
+/// With the `otel` feature, spans are also exported to an OTLP collector if one is configured.
+fn init_tracing(verbosity: u8, colors: bool, log_format: LogFormat) -> Result<TracingGuard> {
     let log_dir = Settings::log_path()?;
     fs::create_dir_all(&log_dir)?;
 
     // Configuración del logger de fichero
-    let file_appender = tracing_appender::rolling::daily(log_dir, "archive.log");
+    let file_name = match log_format {
+        LogFormat::Text => "archive.log",
+        LogFormat::Json => "archive.jsonl",
+    };
+    let file_appender = tracing_appender::rolling::daily(log_dir, file_name);
     let (non_blocking_appender, guard) = tracing_appender::non_blocking(file_appender);
-    let file_layer = fmt::layer()
-        .with_writer(non_blocking_appender)
-        .with_ansi(false)
-        .with_filter(LevelFilter::DEBUG); // Siempre guardar desde DEBUG en el fichero
+    let file_layer = match log_format {
+        LogFormat::Text => fmt::layer()
+            .with_writer(non_blocking_appender)
+            .with_ansi(false)
+            .boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .with_writer(non_blocking_appender)
+            .boxed(),
+    }
+    .with_filter(LevelFilter::DEBUG); // Siempre guardar desde DEBUG en el fichero
 
     // Configuración del logger de consola
     let console_level = match verbosity {
@@ -181,256 +640,1416 @@ fn init_tracing(verbosity: u8, color: ColorChoice) -> Result<WorkerGuard> {
     };
     let console_layer = fmt::layer()
         .with_writer(std::io::stdout)
-        .with_ansi(color != ColorChoice::Never)
+        .with_ansi(colors)
         .with_filter(console_level);
 
-    tracing_subscriber::registry()
-        .with(file_layer)
-        .with(console_layer)
-        .init();
+    #[allow(unused_mut)]
+    let mut layers = vec![file_layer.boxed(), console_layer.boxed()];
+
+    #[cfg(feature = "otel")]
+    let otel_guard = telemetry::otlp_layer()?.map(|(layer, otel_guard)| {
+        layers.push(layer);
+        otel_guard
+    });
+
+    tracing_subscriber::registry().with(layers).init();
 
     // Devolvemos el guardián para que viva en el scope de main.
-    Ok(guard)
+    Ok(TracingGuard {
+        _file: guard,
+        #[cfg(feature = "otel")]
+        _otel: otel_guard,
+    })
 }
 
+fn handle_run(archiver: &Archiver, dry_run: bool, tags: &[String]) -> Result<()> {
+    if !dry_run {
+        interrupt::install_handler();
+    }
+    report_run(archiver, archiver.run_with_summary(dry_run, tags))
+}
 
-fn handle_run(archiver: &Archiver, dry_run: bool) -> Result<()> {
-    let plan = archiver
-        .run_archive_process(dry_run)
-        .context("The archiving process failed")?;
+fn handle_resume(archiver: &Archiver) -> Result<()> {
+    interrupt::install_handler();
+    match archiver.resume_run().context(t!("error-resume"))? {
+        Some(summary) => report_run(archiver, summary),
+        None => {
+            println!("{}", t!("run-nothing-to-resume"));
+            Ok(())
+        }
+    }
+}
 
-    let inactive_projects: Vec<_> = plan
-        .into_iter()
-        .filter(|p| *p != ActionPlan::Nothing)
-        .collect();
+fn handle_plan(archiver: &Archiver, out: &std::path::Path) -> Result<()> {
+    let plan = archiver.plan().context(t!("error-plan"))?;
+    plan.write(out)
+        .with_context(|| t!("error-write-plan", path = out.display().to_string()))?;
+    println!(
+        "{}",
+        style(t!("plan-written", path = out.display().to_string())).bold()
+    );
+    if plan.archive.is_empty() && plan.delete.is_empty() {
+        println!("{}", t!("run-nothing-to-archive"));
+    }
+    for project in &plan.archive {
+        println!(
+            "  {} {} {}",
+            style("+").green(),
+            style(format!("{:<30}", project.name)).green(),
+            style(project.path.display()).dim()
+        );
+    }
+    for record in &plan.delete {
+        println!(
+            "  {} {} {}",
+            style("-").red(),
+            style(format!("{:<30}", record.label())).red(),
+            style(record.archive_path.display()).dim()
+        );
+    }
+    println!("\n{}", t!("plan-apply-hint"));
+    Ok(())
+}
 
-    if inactive_projects.is_empty() {
-        println!("No projects needed archiving.");
+fn handle_apply(archiver: &Archiver, path: &std::path::Path) -> Result<()> {
+    let plan = SavedPlan::read(path)
+        .with_context(|| t!("error-read-plan", path = path.display().to_string()))?;
+    interrupt::install_handler();
+    let summary = archiver.apply_plan(&plan).context(t!("error-apply-plan"))?;
+    report_run(archiver, summary)
+}
+
+/// Records a run and prints its outcome, or its plan on a dry run.
+fn report_run(archiver: &Archiver, summary: RunSummary) -> Result<()> {
+    let dry_run = summary.dry_run;
+    let summary_path = Settings::run_summary_path()?;
+    if let Err(e) = summary.write(&summary_path) {
+        tracing::warn!(path = %summary_path.display(), error = %e, "Could not write the run summary.");
+    }
+    if !dry_run {
+        record_usage(archiver, &summary);
+    }
+    update_prompt_status(&summary);
+    if dry_run {
+        if let Some(error) = summary.error {
+            return Err(anyhow!(error)).context(t!("error-archive-process"));
+        }
+        print_plan(archiver, &summary);
+        print_exclusion_suggestions(archiver, &summary);
         return Ok(());
     }
 
-    if dry_run {
-        println!("{}", style("-- DRY RUN --").yellow().bold());
+    let archived = summary.with_outcome(Outcome::Archived).count();
+    if archived == 0 {
+        println!("{}", t!("run-nothing-to-archive"));
+    } else {
+        println!("{}", t!("run-archived", count = archived));
+    }
+
+    if archiver.settings().enable_auto_delete {
+        let deleted = summary.with_outcome(Outcome::Deleted).count();
+        let days = archiver.deletion_policy().min_archived_days;
+        if deleted == 0 {
+            println!("{}", t!("run-nothing-to-delete", days = days));
+        } else {
+            println!(
+                "{}",
+                style(t!("run-deleted", count = deleted, days = days)).red()
+            );
+        }
+    }
+
+    println!();
+    print_run_table(archiver, &summary, &summary_path);
+    print_exclusion_suggestions(archiver, &summary);
+    if let Some(error) = summary.error {
+        if matches!(archiver.resume_point(), Ok(Some(_))) {
+            println!("\n{}", style(t!("run-resume-hint")).yellow());
+        }
+        return Err(anyhow!(error)).context(t!("error-archive-process"));
+    }
+    Ok(())
+}
+
+/// Suggests excluding the projects archived again after being restored several times.
+fn print_exclusion_suggestions(archiver: &Archiver, summary: &RunSummary) {
+    for count in archiver.exclusion_suggestions(summary) {
         println!(
-            "The following {} project(s) would be archived:",
-            inactive_projects.len()
+            "\n{} {}",
+            style("?").yellow(),
+            t!(
+                "run-suggest-exclusion",
+                name = count.name.as_str(),
+                count = count.count
+            )
         );
-        for case in inactive_projects {
-            if let ActionPlan::Archive { project_name, .. } = case {
-                println!("- {}", style(project_name).cyan());
-            }
-        }
-        println!("\nRun without --dry-run to perform these actions.");
+    }
+}
+
+/// Appends the disk usage after a real run to the history shown by `stats --trend`.
+fn record_usage(archiver: &Archiver, summary: &RunSummary) {
+    let sample = UsageSample {
+        archived_projects: summary.with_outcome(Outcome::Archived).count(),
+        deleted_projects: summary.with_outcome(Outcome::Deleted).count(),
+        ..archiver.usage_sample(summary.bytes_freed + summary.bytes_cleaned)
+    };
+    let recorded = Settings::usage_history_path().and_then(|path| trend::append(&path, &sample));
+    if let Err(e) = recorded {
+        tracing::warn!(error = %e, "Could not record disk usage.");
+    }
+}
+
+/// Prints the totals of a real run, with the failures and where the results were recorded.
+fn print_run_table(archiver: &Archiver, summary: &RunSummary, summary_path: &std::path::Path) {
+    let failed = if summary.failed > 0 {
+        style(summary.failed).red().to_string()
     } else {
+        summary.failed.to_string()
+    };
+    let mut rows = vec![
+        (t!("table-archived"), summary.archived.to_string()),
+        (
+            t!("table-bytes-moved"),
+            ui::format_size(summary.bytes_moved),
+        ),
+        (
+            t!("table-bytes-cleaned"),
+            ui::format_size(summary.bytes_cleaned),
+        ),
+    ];
+    if archiver.settings().enable_auto_delete {
+        rows.push((t!("table-deleted"), summary.deleted.to_string()));
+        rows.push((
+            t!("table-bytes-freed"),
+            ui::format_size(summary.bytes_freed),
+        ));
+    }
+    rows.extend([
+        (t!("table-skipped"), summary.skipped.to_string()),
+        (t!("table-failed"), failed),
+        (
+            t!("table-duration"),
+            format!("{:.2}s", summary.duration_ms as f64 / 1000.0),
+        ),
+        (
+            t!("table-archive-log"),
+            archiver.archive_log_path().display().to_string(),
+        ),
+        (t!("table-run-summary"), summary_path.display().to_string()),
+    ]);
+    ui::print_table(&rows);
+
+    for project in summary.with_outcome(Outcome::Failed) {
         println!(
-            "Successfully archived {} project(s).",
-            inactive_projects.len()
+            "{} {}: {}",
+            style("x").red(),
+            style(&project.name).red(),
+            project.reason.as_deref().unwrap_or_default()
         );
     }
-
-    Ok(())
 }
 
-fn handle_delete(archiver: &Archiver, name: Option<String>, all: bool) -> Result<()> {
+/// Prints a dry run as a plan grouped by action (archive, clean, delete, skip), with sizes and
+/// totals, in the spirit of `terraform plan`.
+fn print_plan(archiver: &Archiver, summary: &RunSummary) {
+    let archive: Vec<_> = summary.with_outcome(Outcome::WouldArchive).collect();
+    let clean: Vec<_> = archive
+        .iter()
+        .filter(|project| !project.cleaned.is_empty())
+        .collect();
+    let delete: Vec<_> = summary.with_outcome(Outcome::WouldDelete).collect();
+    let skipped: Vec<_> = summary.with_outcome(Outcome::Skipped).collect();
+
+    println!("{}", style(t!("run-dry-run-header")).yellow().bold());
+    if archive.is_empty() && delete.is_empty() && skipped.is_empty() {
+        println!("{}", t!("run-nothing-to-archive"));
+    }
+    let size = |bytes: Option<u64>| ui::format_size(bytes.unwrap_or(0));
+
+    if !archive.is_empty() {
+        let header = t!(
+            "plan-archive",
+            count = archive.len(),
+            size = ui::format_size(summary.bytes_moved)
+        );
+        println!("\n{}", style(header).green().bold());
+        for project in &archive {
+            let name = format!("{:<30}", project.name);
+            println!(
+                "  {} {} {:>10}  {}",
+                style("+").green(),
+                style(name).green(),
+                size(project.bytes),
+                style(project.path.display()).dim()
+            );
+        }
+    }
+
+    if !clean.is_empty() {
+        let header = t!(
+            "plan-clean",
+            count = clean.len(),
+            size = ui::format_size(summary.bytes_cleaned)
+        );
+        println!("\n{}", style(header).yellow().bold());
+        for project in &clean {
+            let folders: Vec<_> = project
+                .cleaned
+                .iter()
+                .map(|folder| folder.display().to_string())
+                .collect();
+            let name = format!("{:<30}", project.name);
+            println!(
+                "  {} {} {:>10}  {}",
+                style("~").yellow(),
+                style(name).yellow(),
+                size(project.cleaned_bytes),
+                style(folders.join(", ")).dim()
+            );
+        }
+    }
+
+    if !delete.is_empty() {
+        let header = t!(
+            "plan-delete",
+            count = delete.len(),
+            days = archiver.deletion_policy().min_archived_days,
+            size = ui::format_size(summary.bytes_freed)
+        );
+        println!("\n{}", style(header).red().bold());
+        for project in &delete {
+            let name = format!("{:<30}", project.name);
+            println!(
+                "  {} {} {:>10}",
+                style("-").red(),
+                style(name).red(),
+                size(project.bytes)
+            );
+        }
+    } else if archiver.settings().enable_auto_delete {
+        let days = archiver.deletion_policy().min_archived_days;
+        println!("\n{}", t!("run-nothing-to-delete", days = days));
+    }
+
+    if !skipped.is_empty() {
+        println!(
+            "\n{}",
+            style(t!("plan-skipped", count = skipped.len())).bold()
+        );
+        for project in &skipped {
+            let name = format!("{:<30}", project.name);
+            println!(
+                "  {} {} {}",
+                style("!").dim(),
+                name,
+                style(project.reason.as_deref().unwrap_or_default()).dim()
+            );
+        }
+    }
+
     println!(
-        "{}",
-        style("Warning: This operation is permanent and cannot be undone.")
-            .red()
-            .bold()
+        "\n{}",
+        style(t!(
+            "plan-totals",
+            archive = archive.len(),
+            moved = ui::format_size(summary.bytes_moved),
+            cleaned = ui::format_size(summary.bytes_cleaned),
+            delete = delete.len(),
+            freed = ui::format_size(summary.bytes_freed),
+            skipped = skipped.len()
+        ))
+        .bold()
     );
+    println!("{}", t!("run-dry-run-footer"));
+}
+
+fn handle_delete(archiver: &Archiver, name: Option<String>, all: bool) -> Result<()> {
+    println!("{}", style(t!("delete-warning")).red().bold());
     if all {
         let records_to_delete = archiver.get_archive_records()?.len();
         if records_to_delete == 0 {
-            println!("Archive is already empty.");
+            println!("{}", t!("delete-archive-empty"));
             return Ok(());
         }
-        if !Confirm::new()
-            .with_prompt(format!(
-                "Are you sure you want to permanently delete ALL {} projects?",
-                records_to_delete
-            ))
+        if !Confirm::with_theme(&*ui::theme())
+            .with_prompt(t!("delete-all-prompt", count = records_to_delete))
             .default(false)
             .interact()?
         {
-            println!("Operation cancelled.");
+            println!("{}", t!("operation-cancelled"));
             return Ok(());
         }
-        let confirmation: u64 = Input::new()
-            .with_prompt(format!(
-                "To confirm, please type the number of projects to delete ({})",
-                records_to_delete
-            ))
+        let confirmation: u64 = Input::with_theme(&*ui::theme())
+            .with_prompt(t!("delete-all-confirm-count", count = records_to_delete))
             .interact_text()?;
         if confirmation != records_to_delete as u64 {
-            return Err(anyhow!("Incorrect number entered. Deletion cancelled."));
+            return Err(anyhow!(t!("delete-all-wrong-count")));
         }
         let count = archiver.delete_all()?;
-        println!("Successfully deleted {} projects.", style(count).red());
+        println!("{}", style(t!("delete-all-done", count = count)).red());
     } else if let Some(project_name) = name {
-        if !Confirm::new()
-            .with_prompt(format!(
-                "Are you sure you want to permanently delete '{}'?",
-                project_name
-            ))
+        if !Confirm::with_theme(&*ui::theme())
+            .with_prompt(t!("delete-prompt", name = project_name.as_str()))
             .default(false)
             .interact()?
         {
-            println!("Operation cancelled.");
+            println!("{}", t!("operation-cancelled"));
             return Ok(());
         }
         archiver.delete_project(&project_name)?;
         println!(
-            "Project '{}' deleted successfully.",
-            style(project_name).cyan()
+            "{}",
+            t!("delete-done", name = style(project_name).cyan().to_string())
         );
     } else {
-        return Err(anyhow!(
-            "You must specify a project name or use the --all flag."
-        ));
+        return Err(anyhow!(t!("name-or-all-required")));
+    }
+    Ok(())
+}
+
+fn handle_delete_group(archiver: &Archiver, group: String) -> Result<()> {
+    println!("{}", style(t!("delete-warning")).red().bold());
+    let filter = RestoreFilter {
+        group: Some(group.clone()),
+        ..Default::default()
+    };
+    let matching = archiver
+        .get_archive_records()?
+        .iter()
+        .filter(|record| filter.matches(record))
+        .count();
+    if matching == 0 {
+        println!("{}", t!("delete-group-empty", group = group.as_str()));
+        return Ok(());
     }
+    if !Confirm::with_theme(&*ui::theme())
+        .with_prompt(t!(
+            "delete-group-prompt",
+            count = matching,
+            group = group.as_str()
+        ))
+        .default(false)
+        .interact()?
+    {
+        println!("{}", t!("operation-cancelled"));
+        return Ok(());
+    }
+    let count = archiver.delete_matching(&filter)?;
+    println!("{}", style(t!("delete-all-done", count = count)).red());
     Ok(())
 }
 
-fn handle_restore(archiver: &Archiver, name: Option<String>, all: bool) -> Result<()> {
+fn handle_restore(
+    archiver: &Archiver,
+    name: Option<String>,
+    all: bool,
+    and_exclude: bool,
+) -> Result<()> {
     if all {
-        if !Confirm::new()
-            .with_prompt("Restore all projects from the archive?")
+        if !Confirm::with_theme(&*ui::theme())
+            .with_prompt(t!("restore-all-prompt"))
             .default(false)
             .interact()?
         {
-            println!("Operation cancelled.");
+            println!("{}", t!("operation-cancelled"));
             return Ok(());
         }
         let count = archiver.restore_all()?;
-        println!("Successfully restored {} projects.", style(count).green());
+        println!("{}", style(t!("restore-all-done", count = count)).green());
     } else if let Some(project_name) = name {
-        archiver.restore_project(&project_name)?;
+        let record = archiver.restore_project(&project_name)?;
         println!(
-            "Project '{}' restored successfully.",
-            style(project_name).cyan()
+            "{}",
+            t!(
+                "restore-done",
+                name = style(record.label()).cyan().to_string()
+            )
         );
+        if and_exclude || archiver.settings().exclude_on_restore {
+            handle_exclude(&record.name, false, None)?;
+        }
     } else {
-        return Err(anyhow!(
-            "You must specify a project name or use the --all flag."
+        return Err(anyhow!(t!("name-or-all-required")));
+    }
+    Ok(())
+}
+
+fn handle_restore_matching(archiver: &Archiver, filter: &RestoreFilter) -> Result<()> {
+    let matching = archiver
+        .get_archive_records()?
+        .iter()
+        .filter(|record| filter.matches(record))
+        .count();
+    if matching == 0 {
+        println!("{}", t!("restore-matching-none"));
+        return Ok(());
+    }
+    if !Confirm::with_theme(&*ui::theme())
+        .with_prompt(t!("restore-matching-prompt", count = matching))
+        .default(false)
+        .interact()?
+    {
+        println!("{}", t!("operation-cancelled"));
+        return Ok(());
+    }
+    let count = archiver.restore_matching(filter)?;
+    println!("{}", style(t!("restore-all-done", count = count)).green());
+    Ok(())
+}
+
+/// Midnight UTC at the start of `date`, the boundary used by the date filters.
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+fn handle_restore_preview(archiver: &Archiver, name: &str) -> Result<()> {
+    let preview = archiver.preview_restore(name)?;
+    let record = &preview.record;
+    println!(
+        "{}",
+        style(t!("restore-preview-header", name = record.label())).bold()
+    );
+    println!(
+        "{}",
+        t!(
+            "restore-preview-from",
+            path = record.archive_path.display().to_string()
+        )
+    );
+    println!(
+        "{}",
+        t!(
+            "restore-preview-to",
+            path = record.original_path.display().to_string()
+        )
+    );
+    println!(
+        "{}",
+        t!(
+            "restore-preview-size",
+            size = ui::format_size(preview.size_bytes)
+        )
+    );
+    if preview.destination_exists {
+        println!("{}", style(t!("restore-preview-destination-exists")).red());
+    } else {
+        println!("{}", t!("restore-preview-destination-free"));
+    }
+    println!("\n{}", t!("restore-preview-footer"));
+    Ok(())
+}
+
+fn handle_forecast(archiver: &Archiver, within_days: u64) -> Result<()> {
+    let upcoming = archiver
+        .forecast(chrono::Duration::days(within_days as i64))
+        .context(t!("error-forecast"))?;
+    if upcoming.is_empty() {
+        println!("{}", t!("forecast-empty", days = within_days));
+        return Ok(());
+    }
+    println!(
+        "{}",
+        style(t!(
+            "forecast-header",
+            count = upcoming.len(),
+            days = within_days
+        ))
+        .bold()
+    );
+    let now = Utc::now();
+    for candidate in upcoming {
+        let name = format!("{:<30}", style(candidate.project.name.as_str()).cyan());
+        let date = ui::format_date(candidate.candidate_at);
+        let days = (candidate.candidate_at - now).num_days();
+        println!(
+            "- {}",
+            t!("forecast-entry", name = name, date = date, days = days)
+        );
+    }
+    Ok(())
+}
+
+fn handle_duplicates(archiver: &Archiver) -> Result<()> {
+    let groups = archiver.find_duplicates().context(t!("error-duplicates"))?;
+    if groups.is_empty() {
+        println!("{}", t!("duplicates-empty"));
+        return Ok(());
+    }
+    println!(
+        "{}",
+        style(t!("duplicates-header", count = groups.len())).bold()
+    );
+    for group in &groups {
+        let title = match &group.key {
+            DuplicateKey::Remote(url) => t!("duplicates-remote", url = url),
+            DuplicateKey::Content(_) => t!("duplicates-content"),
+        };
+        println!("\n{}", style(title).cyan());
+        for (index, project) in group.projects.iter().enumerate() {
+            let key = if index == 0 {
+                "duplicates-keep"
+            } else {
+                "duplicates-copy"
+            };
+            println!(
+                "  {}",
+                t!(
+                    key,
+                    path = project.path.display().to_string(),
+                    date = ui::format_date(project.last_activity)
+                )
+            );
+        }
+    }
+    if !archiver.settings().archive_duplicates {
+        println!("\n{}", style(t!("duplicates-hint")).dim());
+    }
+    Ok(())
+}
+
+fn handle_artifacts(archiver: &Archiver, action: ArtifactsAction) -> Result<()> {
+    let (records, header) = match action {
+        ArtifactsAction::Run { dry_run } => {
+            if archiver.settings().artifact_days.is_none() {
+                println!("{}", t!("artifacts-disabled"));
+                return Ok(());
+            }
+            let records = archiver
+                .archive_artifacts(dry_run)
+                .context(t!("error-artifacts"))?;
+            let key = if dry_run {
+                "artifacts-would-move"
+            } else {
+                "artifacts-moved"
+            };
+            (records, key)
+        }
+        ArtifactsAction::List => {
+            let records = archiver.artifact_records().context(t!("error-artifacts"))?;
+            (records, "artifacts-list")
+        }
+        ArtifactsAction::Restore { name } => {
+            let record = archiver.restore_artifacts(&name)?;
+            println!(
+                "{}",
+                t!(
+                    "artifacts-restored",
+                    name = name.as_str(),
+                    size = ui::format_size(record.bytes)
+                )
+            );
+            return Ok(());
+        }
+    };
+
+    if records.is_empty() {
+        println!("{}", t!("artifacts-none"));
+        return Ok(());
+    }
+    let total = records.iter().map(|record| record.bytes).sum();
+    println!(
+        "{}",
+        style(t!(
+            header,
+            count = records.len(),
+            size = ui::format_size(total)
+        ))
+        .bold()
+    );
+    for record in &records {
+        let folders: Vec<_> = record
+            .folders
+            .iter()
+            .map(|folder| folder.display().to_string())
+            .collect();
+        println!(
+            "  {:<30} {:>10}  {}",
+            record.name,
+            ui::format_size(record.bytes),
+            style(folders.join(", ")).dim()
+        );
+    }
+    Ok(())
+}
+
+fn handle_serve(archiver: &Archiver, addr: &str) -> Result<()> {
+    let listener = std::net::TcpListener::bind(addr)
+        .with_context(|| t!("error-serve", addr = addr.to_string()))?;
+    let url = format!("http://{}/", listener.local_addr()?);
+    println!("{}", t!("serve-listening", url = url.as_str()));
+    dashboard::serve(archiver, listener)
+        .with_context(|| t!("error-serve", addr = addr.to_string()))?;
+    Ok(())
+}
+
+/// Counts the archive candidates and caches the count for `prompt-status`.
+fn refresh_prompt_status(archiver: &Archiver) {
+    let Ok(path) = Settings::prompt_status_path() else {
+        return;
+    };
+    let Ok(candidates) = archiver.pending_candidates() else {
+        return;
+    };
+    let status = PromptStatus::new(candidates);
+    if let Err(e) = status.write(&path) {
+        tracing::warn!(path = %path.display(), error = %e, "Could not cache the prompt status.");
+    }
+    print_candidate_flag(&status);
+}
+
+/// Prints the cached count, and starts a refresh in the background when it is missing or
+/// stale and no other refresh is running. Reads nothing but the local files.
+fn print_prompt_status() {
+    let Ok(path) = Settings::prompt_status_path() else {
+        return;
+    };
+    let cached = PromptStatus::read(&path).ok().flatten();
+    let cache_minutes = Settings::new_local()
+        .map(|settings| settings.prompt_cache_minutes)
+        .unwrap_or_else(|_| Settings::default().prompt_cache_minutes);
+    let max_age = chrono::Duration::minutes(cache_minutes as i64);
+    if !cached
+        .as_ref()
+        .is_some_and(|status| status.is_fresh(max_age, Utc::now()))
+    {
+        refresh_prompt_status_in_background();
+    }
+    if let Some(status) = cached {
+        print_candidate_flag(&status);
+    }
+}
+
+fn print_candidate_flag(status: &PromptStatus) {
+    if status.candidates > 0 {
+        let flag = if ui::ascii() { "!" } else { "⚑" };
+        println!("{}{}", status.candidates, flag);
+    }
+}
+
+fn refresh_prompt_status_in_background() {
+    let Ok(marker) = Settings::prompt_refresh_marker_path() else {
+        return;
+    };
+    if !archiver_core::prompt::claim_refresh(&marker, Utc::now()).unwrap_or(false) {
+        return;
+    }
+    let spawned = std::env::current_exe().and_then(|exe| {
+        std::process::Command::new(exe)
+            .args(["prompt-status", "--refresh"])
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+    });
+    if spawned.is_err() {
+        archiver_core::prompt::release_refresh(&marker);
+    }
+}
+
+fn release_prompt_refresh() {
+    if let Ok(marker) = Settings::prompt_refresh_marker_path() {
+        archiver_core::prompt::release_refresh(&marker);
+    }
+}
+
+/// Caches the candidates left after a run for `prompt-status`: those a dry run would archive,
+/// none after a complete real run.
+fn update_prompt_status(summary: &RunSummary) {
+    let candidates = if summary.dry_run {
+        summary.with_outcome(Outcome::WouldArchive).count()
+    } else if summary.error.is_none() {
+        0
+    } else {
+        return;
+    };
+    let written =
+        Settings::prompt_status_path().and_then(|path| PromptStatus::new(candidates).write(&path));
+    if let Err(e) = written {
+        tracing::warn!(error = %e, "Could not cache the prompt status.");
+    }
+}
+
+fn handle_digest(archiver: &Archiver, period: Period, notify: bool) -> Result<()> {
+    let history = trend::read(&Settings::usage_history_path()?).context(t!("error-digest"))?;
+    let digest = archiver
+        .digest(&history, period)
+        .context(t!("error-digest"))?;
+
+    let mut lines = vec![t!(
+        "digest-summary",
+        runs = digest.runs,
+        archived = digest.archived_projects,
+        deleted = digest.deleted_projects,
+        freed = ui::format_size(digest.freed_bytes)
+    )];
+    if let Some(sample) = &digest.last_sample {
+        lines.push(t!(
+            "digest-usage",
+            active = ui::format_size(sample.active_bytes),
+            archive = ui::format_size(sample.archive_bytes)
+        ));
+    }
+    if archiver.settings().enable_auto_delete {
+        lines.push(t!(
+            "digest-upcoming",
+            count = digest.upcoming_deletions.len(),
+            days = period.length().num_days()
+        ));
+    }
+    let title = t!(
+        "digest-header",
+        start = digest.start.to_string(),
+        end = digest.end.to_string()
+    );
+
+    println!("{}", style(&title).bold());
+    for line in &lines {
+        println!("{}", line);
+    }
+    for record in &digest.upcoming_deletions {
+        println!(
+            "  {} {:<30} {}",
+            style("-").red(),
+            record.label(),
+            style(t!(
+                "digest-archived-on",
+                date = ui::format_date(record.archived_at)
+            ))
+            .dim()
+        );
+    }
+
+    if notify {
+        notify_rust::Notification::new()
+            .appname("archiver")
+            .summary(&title)
+            .body(&lines.join("\n"))
+            .show()
+            .context(t!("error-notify"))?;
+    }
+    Ok(())
+}
+
+fn handle_index(archiver: &Archiver, action: IndexAction) -> Result<()> {
+    let path = archiver.archive_log_path().display().to_string();
+    match action {
+        IndexAction::Compact => {
+            let dropped = archiver.compact_archive_log().context(t!("error-index"))?;
+            println!("{}", t!("index-compacted", count = dropped, path = path));
+        }
+        IndexAction::Convert => match archiver.convert_archive_log().context(t!("error-index"))? {
+            Some(count) => println!("{}", t!("index-converted", count = count, path = path)),
+            None => println!("{}", t!("index-nothing-to-convert", path = path)),
+        },
+    }
+    Ok(())
+}
+
+fn handle_remote(archiver: &Archiver, action: RemoteAction) -> Result<()> {
+    let (name, dry_run, tags) = match action {
+        RemoteAction::List => {
+            let remotes = &archiver.settings().remotes;
+            if remotes.is_empty() {
+                println!("{}", t!("remote-none"));
+            }
+            for remote in remotes {
+                let dirs: Vec<_> = remote
+                    .projects_dirs
+                    .iter()
+                    .map(|dir| dir.display().to_string())
+                    .collect();
+                println!(
+                    "{:<16} {}  {}",
+                    style(&remote.name).bold(),
+                    remote.host,
+                    style(t!(
+                        "remote-dirs",
+                        projects = dirs.join(", "),
+                        archive = remote.archive_dir.display().to_string()
+                    ))
+                    .dim()
+                );
+            }
+            return Ok(());
+        }
+        RemoteAction::Scan { name } => {
+            let remote = archiver.remote(&name)?;
+            let projects = archiver
+                .scan_remote(remote)
+                .context(t!("error-remote", name = name.as_str()))?;
+            if projects.is_empty() {
+                println!("{}", t!("remote-no-projects", name = name.as_str()));
+            }
+            for RemoteProject { project, .. } in &projects {
+                println!(
+                    "{:<30} {}  {}",
+                    project.name,
+                    ui::format_date(project.last_activity),
+                    style(project.path.display()).dim()
+                );
+            }
+            return Ok(());
+        }
+        RemoteAction::Run {
+            name,
+            dry_run,
+            tags,
+        } => (name, dry_run, tags),
+    };
+
+    let remote = archiver.remote(&name)?;
+    let summary = archiver.run_remote(remote, dry_run, &tags);
+    let outcome = if dry_run {
+        Outcome::WouldArchive
+    } else {
+        Outcome::Archived
+    };
+    let archived: Vec<_> = summary.with_outcome(outcome).collect();
+    if dry_run {
+        println!("{}", style(t!("run-dry-run-header")).yellow().bold());
+    }
+    if archived.is_empty() {
+        println!("{}", t!("run-nothing-to-archive"));
+    } else {
+        let key = if dry_run {
+            "remote-would-archive"
+        } else {
+            "remote-archived"
+        };
+        println!(
+            "{}",
+            style(t!(key, count = archived.len(), name = name.as_str())).bold()
+        );
+        for project in &archived {
+            println!(
+                "  {} {:<30} {}",
+                style("+").green(),
+                project.name,
+                style(project.path.display()).dim()
+            );
+        }
+    }
+    if let Some(error) = summary.error {
+        return Err(anyhow!(error)).context(t!("error-remote", name = name.as_str()));
+    }
+    if dry_run {
+        println!("{}", t!("run-dry-run-footer"));
+    }
+    Ok(())
+}
+
+fn handle_stats(archiver: &Archiver, show_trend: bool, period: Period) -> Result<()> {
+    let history = trend::read(&Settings::usage_history_path()?).context(t!("error-stats"))?;
+    if show_trend {
+        print_trend(&history, period);
+        return Ok(());
+    }
+    let usage = archiver.usage_sample(0);
+    let archived = archiver
+        .get_archive_records()
+        .context(t!("error-list-records"))?
+        .len();
+    let mut rows = vec![
+        (t!("stats-active"), ui::format_size(usage.active_bytes)),
+        (t!("stats-archive"), ui::format_size(usage.archive_bytes)),
+        (t!("stats-archived-projects"), archived.to_string()),
+    ];
+    if let Some(first) = history.first() {
+        let freed: u64 = history.iter().map(|sample| sample.freed_bytes).sum();
+        rows.push((
+            t!("stats-freed", date = ui::format_date(first.at)),
+            ui::format_size(freed),
         ));
+        rows.push((t!("stats-runs"), history.len().to_string()));
     }
+    ui::print_table(&rows);
     Ok(())
 }
 
-fn handle_list(archiver: &Archiver) -> Result<()> {
-    let records = archiver
+/// Prints the usage history grouped by `period`, with the change in active usage between
+/// periods, growth in yellow and shrinking in green.
+fn print_trend(history: &[trend::UsageSample], period: Period) {
+    let (Some(first), Some(last)) = (history.first(), history.last()) else {
+        println!("{}", t!("stats-trend-empty"));
+        return;
+    };
+    println!(
+        "{}",
+        style(t!("stats-trend-header", runs = history.len())).bold()
+    );
+    let signed_change = |from: u64, to: u64| {
+        let change = ui::format_size(from.abs_diff(to));
+        match to.cmp(&from) {
+            std::cmp::Ordering::Greater => style(format!("+{}", change)).yellow(),
+            std::cmp::Ordering::Less => style(format!("-{}", change)).green(),
+            std::cmp::Ordering::Equal => style("=".to_string()).dim(),
+        }
+    };
+    let mut previous = None;
+    for point in trend::trend(history, period) {
+        let label = match period {
+            Period::Week => t!("stats-trend-week", date = point.period_start.to_string()),
+            Period::Month => point.period_start.format("%Y-%m").to_string(),
+        };
+        let change = previous.map_or_else(String::new, |from| {
+            format!("({})", signed_change(from, point.active_bytes))
+        });
+        println!(
+            "  {}",
+            t!(
+                "stats-trend-entry",
+                period = format!("{:<18}", label),
+                active = format!("{:>10}", ui::format_size(point.active_bytes)),
+                change = console::pad_str(&change, 13, console::Alignment::Left, None),
+                archive = format!("{:>10}", ui::format_size(point.archive_bytes)),
+                freed = format!("{:>10}", ui::format_size(point.freed_bytes))
+            )
+        );
+        previous = Some(point.active_bytes);
+    }
+    println!(
+        "\n{}",
+        t!(
+            "stats-trend-footer",
+            change = signed_change(first.active_bytes, last.active_bytes).to_string(),
+            date = ui::format_date(first.at)
+        )
+    );
+}
+
+/// Prints the children of `node` below it, largest first, with `glyphs` for a child, the last
+/// child and a continuing branch.
+fn print_tree_children(node: &TreeNode, prefix: &str, glyphs: &[&str; 3]) {
+    for (index, child) in node.children.iter().enumerate() {
+        let last = index + 1 == node.children.len();
+        let size = style(ui::format_size(child.bytes)).yellow();
+        let line = match child.archived_at {
+            Some(archived_at) => {
+                let missing = if child.missing {
+                    format!("  {}", style(t!("list-tree-missing")).red())
+                } else {
+                    String::new()
+                };
+                format!(
+                    "{}  {}  {}{}",
+                    style(&child.name).cyan(),
+                    size,
+                    style(ui::format_date(archived_at)).dim(),
+                    missing
+                )
+            }
+            None => format!("{}  {}", style(&child.name).bold(), size),
+        };
+        println!("{}{}{}", prefix, glyphs[if last { 1 } else { 0 }], line);
+        let child_prefix = format!("{}{}", prefix, if last { "    " } else { glyphs[2] });
+        print_tree_children(child, &child_prefix, glyphs);
+    }
+}
+
+/// Parses a period in days: `30`, `30d` or `4w`.
+fn parse_days(value: &str) -> std::result::Result<u64, String> {
+    let (number, factor) = match value.strip_suffix('w') {
+        Some(weeks) => (weeks, 7),
+        None => (value.strip_suffix('d').unwrap_or(value), 1),
+    };
+    number
+        .parse::<u64>()
+        .map(|n| n * factor)
+        .map_err(|_| t!("invalid-period", value = value))
+}
+
+fn handle_list(archiver: &Archiver, group: Option<&str>, tree: bool) -> Result<()> {
+    let records: Vec<_> = archiver
         .get_archive_records()
-        .context("Failed to retrieve list of archived projects")?;
+        .context(t!("error-list-records"))?
+        .into_iter()
+        .filter(|record| group.is_none_or(|group| record.group.as_deref() == Some(group)))
+        .collect();
     if records.is_empty() {
-        println!("No projects are currently archived.");
+        println!("{}", t!("list-empty"));
+    } else if tree {
+        let glyphs = if ui::ascii() {
+            ["|-- ", "`-- ", "|   "]
+        } else {
+            ["├── ", "└── ", "│   "]
+        };
+        for root in archiver_core::tree::build(archiver.settings(), records) {
+            println!(
+                "{}  {}",
+                style(&root.name).bold(),
+                style(ui::format_size(root.bytes)).yellow()
+            );
+            print_tree_children(&root, "", &glyphs);
+        }
     } else {
-        println!("{}", style("Archived projects:").bold());
+        println!("{}", style(t!("list-header")).bold());
         for record in records {
+            let name = format!("{:<30}", style(record.label()).cyan());
+            let date = ui::format_date(record.archived_at);
+            let stats = record
+                .code_stats
+                .as_ref()
+                .and_then(|stats| {
+                    let language = stats.primary_language()?.to_string();
+                    Some(t!(
+                        "list-entry-stats",
+                        language = language,
+                        lines = stats.total_code()
+                    ))
+                })
+                .map(|stats| format!(" [{}]", style(stats).dim()))
+                .unwrap_or_default();
+            let group = record
+                .group
+                .as_ref()
+                .map(|group| format!(" [{}]", style(t!("list-entry-group", group = group)).dim()))
+                .unwrap_or_default();
             println!(
-                "- {:<30} (Archived on: {})",
-                style(&record.name).cyan(),
-                record.archived_at.date_naive()
+                "- {}{}{}",
+                t!("list-entry", name = name, date = date),
+                group,
+                stats
             );
         }
     }
     Ok(())
 }
 
-fn handle_paths(settings: &Settings) -> Result<()> {
-    println!("{}", style("Configuration paths:").bold());
+fn handle_simulate(spec: &SimulationSpec, dir: Option<PathBuf>) -> Result<()> {
+    let root = match &dir {
+        Some(dir) => dir.clone(),
+        None => std::env::temp_dir().join(format!("archiver-simulate-{}", std::process::id())),
+    };
+    if root.exists() {
+        return Err(anyhow!(t!(
+            "simulate-dir-exists",
+            path = root.display().to_string()
+        )));
+    }
+    let projects_dir = root.join("projects");
+    let started = Instant::now();
+    simulate::generate(&projects_dir, spec)?;
+    let generated = started.elapsed();
     println!(
-        "- Projects directory: {}",
-        style(settings.projects_dir.display()).yellow()
+        "{}",
+        t!(
+            "simulate-generated",
+            count = spec.projects,
+            size = ui::format_size(spec.max_size),
+            path = root.display().to_string(),
+            seconds = format!("{:.2}", generated.as_secs_f64())
+        )
     );
+
+    let archiver = Archiver::new(Settings {
+        projects_dirs: vec![projects_dir],
+        archive_dir: root.join("archive"),
+        ..Settings::default()
+    });
+    for dry_run in [true, false] {
+        let summary = archiver.run_with_summary(dry_run, &[]);
+        if let Some(error) = summary.error {
+            return Err(anyhow!(error)).context(t!("error-archive-process"));
+        }
+        let key = if dry_run {
+            "simulate-dry-run"
+        } else {
+            "simulate-archive"
+        };
+        println!(
+            "{}",
+            t!(
+                key,
+                scanned = summary.scanned,
+                archived = summary.archived,
+                size = ui::format_size(summary.bytes_moved),
+                seconds = format!("{:.2}", summary.duration_ms as f64 / 1000.0)
+            )
+        );
+    }
+
+    if dir.is_none() {
+        fs::remove_dir_all(&root)?;
+    }
+    Ok(())
+}
+
+/// Writes `archiver.1` and one `archiver-<subcommand>.1` page per visible subcommand, nested
+/// subcommands included, straight from the clap definitions.
+fn handle_mangen(dir: &std::path::Path) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    // The package is `archiver-cli`, but the installed binary is `archiver`.
+    let mut command = Cli::command().name("archiver").bin_name("archiver");
+    command.build();
+    let written = write_man_pages(&command, "archiver", dir)?;
     println!(
-        "- Archive directory:  {}",
-        style(settings.archive_dir.display()).yellow()
+        "{}",
+        t!(
+            "mangen-written",
+            count = written,
+            path = dir.display().to_string()
+        )
     );
+    Ok(())
+}
+
+fn write_man_pages(command: &clap::Command, name: &str, dir: &std::path::Path) -> Result<usize> {
+    let command = command.clone().name(name.to_string());
+    let mut page = Vec::new();
+    clap_mangen::Man::new(command.clone()).render(&mut page)?;
+    fs::write(dir.join(format!("{}.1", name)), page)?;
+
+    let mut written = 1;
+    for subcommand in command.get_subcommands() {
+        if subcommand.is_hide_set() || subcommand.get_name() == "help" {
+            continue;
+        }
+        let sub_name = format!("{}-{}", name, subcommand.get_name());
+        written += write_man_pages(subcommand, &sub_name, dir)?;
+    }
+    Ok(written)
+}
+
+/// Parses a size in bytes with an optional binary suffix: `512`, `64K`, `500M`, `1G`.
+fn parse_size(value: &str) -> std::result::Result<u64, String> {
+    let upper = value.trim().to_ascii_uppercase();
+    let digits = upper.trim_end_matches(['B', 'I']);
+    let (number, shift) = match digits.chars().last() {
+        Some('K') => (&digits[..digits.len() - 1], 10),
+        Some('M') => (&digits[..digits.len() - 1], 20),
+        Some('G') => (&digits[..digits.len() - 1], 30),
+        Some('T') => (&digits[..digits.len() - 1], 40),
+        _ => (digits, 0),
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(1 << shift))
+        .ok_or_else(|| t!("invalid-size", value = value))
+}
+
+fn handle_explain(code: Option<ErrorCode>) {
+    let Some(code) = code else {
+        println!("{}", style(t!("explain-list-header")).bold());
+        for code in ErrorCode::ALL {
+            println!("  {}  {}", style(code).yellow(), explanation(code, "title"));
+        }
+        return;
+    };
     println!(
-        "- Config file:        {}",
-        style(Settings::config_path()?.display()).yellow()
+        "{}",
+        style(format!("{}: {}", code, explanation(code, "title"))).bold()
     );
+    println!("\n{}", style(t!("explain-causes")).underlined());
+    println!("{}", explanation(code, "causes"));
+    println!("\n{}", style(t!("explain-fixes")).underlined());
+    println!("{}", explanation(code, "fixes"));
+}
+
+/// Looks up a part (`title`, `causes` or `fixes`) of the explanation of an error code.
+fn explanation(code: ErrorCode, part: &str) -> String {
+    i18n::tr(
+        &format!("explain-{}-{}", code.as_str().to_lowercase(), part),
+        None,
+    )
+}
+
+fn handle_paths(settings: &Settings) -> Result<()> {
+    println!("{}", style(t!("paths-header")).bold());
+    let config_path = Settings::config_path()?;
+    let projects_dirs = settings
+        .projects_dirs
+        .iter()
+        .map(|dir| ("paths-projects-dir", dir.as_path()));
+    for (key, path) in projects_dirs.chain([
+        ("paths-archive-dir", settings.archive_dir.as_path()),
+        ("paths-index-dir", settings.index_dir()),
+        ("paths-config-file", &config_path),
+    ]) {
+        println!(
+            "- {}",
+            t!(key, path = style(path.display()).yellow().to_string())
+        );
+    }
     Ok(())
 }
 
 fn interactive_config_update(existing: Option<&Settings>) -> Result<Settings> {
-    let theme = dialoguer::theme::ColorfulTheme::default();
-    let home_dir = std::env::var("HOME").context("Could not find HOME directory")?;
+    let theme = ui::theme();
+    let home_dir = std::env::var("HOME").context(t!("error-home-not-found"))?;
 
-    let projects_dir: String = Input::with_theme(&theme)
-        .with_prompt("Enter the path to your projects directory")
+    let projects_dirs: String = Input::with_theme(&*theme)
+        .with_prompt(t!("prompt-projects-dir"))
         .default(existing.map_or_else(
             || format!("{}/projects", home_dir),
-            |s| s.projects_dir.to_string_lossy().to_string(),
+            |s| {
+                let dirs: Vec<_> = s
+                    .projects_dirs
+                    .iter()
+                    .map(|d| d.to_string_lossy())
+                    .collect();
+                dirs.join(", ")
+            },
         ))
         .interact_text()?;
 
-    let archive_dir: String = Input::with_theme(&theme)
-        .with_prompt("Enter the path for the archive directory")
+    let archive_dir: String = Input::with_theme(&*theme)
+        .with_prompt(t!("prompt-archive-dir"))
         .default(existing.map_or_else(
             || format!("{}/.archive", home_dir),
             |s| s.archive_dir.to_string_lossy().to_string(),
         ))
         .interact_text()?;
 
-    let inactivity_days: u64 = Input::with_theme(&theme)
-        .with_prompt("Archive projects after how many days of inactivity?")
+    let inactivity_days: u64 = Input::with_theme(&*theme)
+        .with_prompt(t!("prompt-inactivity-days"))
         .default(existing.map_or(30, |s| s.inactivity_days))
         .interact_text()?;
 
     Ok(Settings {
-        projects_dir: projects_dir.into(),
+        projects_dirs: projects_dirs
+            .split(',')
+            .map(str::trim)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .collect(),
         archive_dir: archive_dir.into(),
         inactivity_days,
-        cleanup_rules: existing.map_or_else(Vec::new, |s| s.cleanup_rules.clone()),
-        enable_auto_delete: existing.map_or(false, |s| s.enable_auto_delete),
-        days_before_delete: existing.map_or(365, |s| s.days_before_delete),
-        exclude: existing.map_or_else(Vec::new, |s| s.exclude.clone()),
+        ..existing.cloned().unwrap_or_default()
     })
 }
 
-fn handle_exclude(project_name: &str, remove: bool) -> Result<()> {
-    let mut settings = Settings::new().unwrap_or_default();
-    if remove {
-        if let Some(pos) = settings.exclude.iter().position(|p| p == project_name) {
+fn handle_exclude(
+    project_name: &ProjectName,
+    remove: bool,
+    until: Option<NaiveDate>,
+) -> Result<()> {
+    let mut settings = Settings::new_local().unwrap_or_default();
+    let position = settings
+        .exclude
+        .iter()
+        .position(|e| project_name == e.pattern());
+    let name = style(project_name.as_str()).yellow().to_string();
+    match position {
+        Some(pos) if remove => {
             settings.exclude.remove(pos);
-            println!(
-                "Project '{}' has been removed from the exclusion list.",
-                style(project_name).yellow()
-            );
-        } else {
-            println!(
-                "Project '{}' was not on the exclusion list. No changes made.",
-                style(project_name).yellow()
-            );
+            println!("{}", t!("exclude-removed", name = name));
+        }
+        None if remove => {
+            println!("{}", t!("exclude-not-listed", name = name));
             return Ok(());
         }
-    } else {
-        if settings.exclude.iter().any(|p| p == project_name) {
-            println!(
-                "Project '{}' is already on the exclusion list.",
-                style(project_name).yellow()
-            );
+        Some(pos) if settings.exclude[pos].until() == until => {
+            println!("{}", t!("exclude-already-listed", name = name));
             return Ok(());
         }
-        settings.exclude.push(project_name.to_string());
+        Some(pos) => {
+            settings.exclude[pos] = Exclusion::new(project_name.as_str(), until);
+            println!("{}", exclude_added_message(&name, until, "exclude-updated"));
+        }
+        None => {
+            settings
+                .exclude
+                .push(Exclusion::new(project_name.as_str(), until));
+            println!("{}", exclude_added_message(&name, until, "exclude-added"));
+        }
+    }
+    save_settings(&settings).context(t!("error-save-settings"))
+}
+
+fn exclude_added_message(name: &str, until: Option<NaiveDate>, key: &str) -> String {
+    match until {
+        Some(date) => t!("exclude-added-until", name = name, date = date.to_string()),
+        None => t!(key, name = name),
+    }
+}
+
+fn handle_exclude_list() -> Result<()> {
+    let settings = Settings::new().context(t!("error-load-settings"))?;
+    if settings.exclude.is_empty() {
+        println!("{}", t!("exclude-list-empty"));
+        return Ok(());
+    }
+
+    let names: Vec<String> = settings
+        .projects_dirs
+        .iter()
+        .flat_map(|dir| project_dir_names(dir, settings.scan_depth))
+        .collect();
+    let dirs: Vec<_> = settings
+        .projects_dirs
+        .iter()
+        .map(|dir| dir.display().to_string())
+        .collect();
+    let today = chrono::Local::now().date_naive();
+    println!(
+        "{}",
+        style(t!("exclude-list-header", path = dirs.join(", "))).bold()
+    );
+    for exclusion in &settings.exclude {
+        let matching = names.iter().filter(|n| exclusion.matches(n)).count();
+        let kind = if exclusion.is_glob() {
+            t!("exclude-kind-glob")
+        } else {
+            t!("exclude-kind-exact")
+        };
+        let status = if exclusion.is_glob() {
+            t!("exclude-status-matches", count = matching)
+        } else if matching > 0 {
+            t!("exclude-status-exists")
+        } else {
+            t!("exclude-status-missing")
+        };
+        let status = if matching > 0 {
+            style(status).green()
+        } else {
+            style(status).dim()
+        };
+        let expiry = match exclusion.until() {
+            None => style(t!("exclude-expiry-never")),
+            Some(date) if exclusion.is_expired(today) => {
+                style(t!("exclude-expiry-expired", date = date.to_string())).red()
+            }
+            Some(date) => style(t!("exclude-expiry-until", date = date.to_string())),
+        };
         println!(
-            "Project '{}' has been added to the exclusion list.",
-            style(project_name).yellow()
+            "- {:<30} {:<6} {:<16} {}",
+            style(exclusion.pattern()).cyan(),
+            kind,
+            status,
+            expiry
         );
     }
-    save_settings(&settings).context("Failed to save updated settings")
+    Ok(())
+}
+
+/// Names of the directories up to `depth` levels below `dir`, which exclusions are matched
+/// against while scanning.
+fn project_dir_names(dir: &std::path::Path, depth: usize) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names = Vec::new();
+    for entry in entries.flatten() {
+        if !entry.path().is_dir() {
+            continue;
+        }
+        names.push(entry.file_name().to_string_lossy().into_owned());
+        if depth > 1 {
+            names.extend(project_dir_names(&entry.path(), depth - 1));
+        }
+    }
+    names
 }
 
 /// Helper to serialize and save settings to the config file.
 fn save_settings(settings: &Settings) -> Result<()> {
     let path = Settings::config_path()?;
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).context("Could not create config directory")?;
+        fs::create_dir_all(parent).context(t!("error-create-config-dir"))?;
     }
-    let toml_string =
-        toml::to_string_pretty(settings).context("Could not serialize settings to TOML")?;
+    let toml_string = toml::to_string_pretty(settings).context(t!("error-serialize-settings"))?;
     fs::write(&path, toml_string)
-        .with_context(|| format!("Could not write config to '{}'", path.display()))?;
+        .with_context(|| t!("error-write-config", path = path.display().to_string()))?;
     Ok(())
 }
@@ -0,0 +1,59 @@
+//! Optional export of the tracing spans to an OpenTelemetry collector (Jaeger, Tempo, ...).
+//!
+//! Only compiled with the `otel` feature, and only active when one of the standard
+//! `OTEL_EXPORTER_OTLP_*ENDPOINT` environment variables is set.
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing::Subscriber;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::Layer;
+use tracing_subscriber::registry::LookupSpan;
+
+const ENDPOINT_VARS: [&str; 2] = [
+    "OTEL_EXPORTER_OTLP_ENDPOINT",
+    "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+];
+const SERVICE_NAME: &str = "archiver";
+
+type BoxedLayer<S> = Box<dyn Layer<S> + Send + Sync>;
+
+/// Flushes the pending spans when dropped at the end of `main`.
+pub struct OtelGuard(SdkTracerProvider);
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.0.shutdown() {
+            eprintln!("Failed to flush OpenTelemetry spans: {}", e);
+        }
+    }
+}
+
+/// Builds the OTLP layer, or returns `None` if no collector endpoint is configured.
+pub fn otlp_layer<S>() -> Result<Option<(BoxedLayer<S>, OtelGuard)>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    if !ENDPOINT_VARS
+        .iter()
+        .any(|var| std::env::var_os(var).is_some())
+    {
+        return Ok(None);
+    }
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()
+        .context("Failed to build the OTLP span exporter")?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+        .build();
+
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer(SERVICE_NAME))
+        .with_filter(LevelFilter::INFO)
+        .boxed();
+    Ok(Some((layer, OtelGuard(provider))))
+}
@@ -0,0 +1,116 @@
+//! Centralized output styling, so every command obeys the same color and accessibility modes.
+//!
+//! The color mode comes from `--color`; in `auto` mode colors are disabled when `NO_COLOR` is
+//! set (see <https://no-color.org>), when stdout is not a color-capable terminal, or when the
+//! plain-ASCII accessible mode (`--ascii`) is on.
+use archiver_core::DateFormatter;
+use chrono::{DateTime, Utc};
+use clap::ColorChoice;
+use console::{Alignment, Term, measure_text_width, pad_str};
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ASCII: AtomicBool = AtomicBool::new(false);
+static DATES: OnceLock<DateFormatter> = OnceLock::new();
+
+/// Resolves the output modes and applies them to `console` (and therefore `dialoguer`).
+/// Returns whether colors are enabled, for the remaining outputs such as the log layer.
+pub fn init(choice: ColorChoice, ascii: bool) -> bool {
+    ASCII.store(ascii, Ordering::Relaxed);
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            !ascii
+                && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                && Term::stdout().features().colors_supported()
+        }
+    };
+    console::set_colors_enabled(enabled);
+    console::set_colors_enabled_stderr(enabled);
+    enabled
+}
+
+/// Whether output must stick to plain ASCII: no box-drawing, emoji, spinners or color-only cues.
+pub fn ascii() -> bool {
+    ASCII.load(Ordering::Relaxed)
+}
+
+/// Theme for interactive prompts matching the current output modes.
+/// The colorful theme relies on Unicode symbols, so it is never used in ASCII mode.
+pub fn theme() -> Box<dyn Theme> {
+    if console::colors_enabled() && !ascii() {
+        Box::new(ColorfulTheme::default())
+    } else {
+        Box::new(SimpleTheme)
+    }
+}
+
+/// Sets how dates are shown, from the `timezone` and `date_format` settings. Dates are shown in
+/// UTC as `YYYY-MM-DD` until it is called.
+pub fn set_date_formatter(formatter: DateFormatter) {
+    let _ = DATES.set(formatter);
+}
+
+/// Formats the date of a timestamp in the configured timezone and format.
+pub fn format_date(at: DateTime<Utc>) -> String {
+    DATES.get_or_init(DateFormatter::default).date(at)
+}
+
+/// Formats a size in bytes with binary units, e.g. `1.5 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Prints a two-column table of labels and values, framed with box-drawing characters, or
+/// with `+`, `-` and `|` in ASCII mode. Values may be styled.
+pub fn print_table(rows: &[(String, String)]) {
+    let (h, v, top, bottom) = if ascii() {
+        ('-', '|', ['+', '+', '+'], ['+', '+', '+'])
+    } else {
+        ('─', '│', ['┌', '┬', '┐'], ['└', '┴', '┘'])
+    };
+    let label_width = rows
+        .iter()
+        .map(|(label, _)| measure_text_width(label))
+        .max()
+        .unwrap_or(0);
+    let value_width = rows
+        .iter()
+        .map(|(_, value)| measure_text_width(value))
+        .max()
+        .unwrap_or(0);
+    let rule = |[left, mid, right]: [char; 3]| {
+        let line = |width: usize| h.to_string().repeat(width + 2);
+        format!(
+            "{}{}{}{}{}",
+            left,
+            line(label_width),
+            mid,
+            line(value_width),
+            right
+        )
+    };
+
+    println!("{}", rule(top));
+    for (label, value) in rows {
+        println!(
+            "{v} {} {v} {} {v}",
+            pad_str(label, label_width, Alignment::Left, None),
+            pad_str(value, value_width, Alignment::Left, None),
+        );
+    }
+    println!("{}", rule(bottom));
+}
@@ -5,12 +5,1055 @@ use std::process::Command;
 #[test]
 fn test_paths_command_runs_successfully() {
     let mut cmd = Command::cargo_bin("archiver").unwrap();
-    
+
     cmd.arg("paths");
-    
+
     cmd.assert()
-        .success() 
+        .success()
         .stdout(predicate::str::contains("Configuration paths:"))
         .stdout(predicate::str::contains("Projects directory:"))
         .stdout(predicate::str::contains("Archive directory:"));
 }
+
+#[test]
+fn test_paths_command_is_localized() {
+    let mut cmd = Command::cargo_bin("archiver").unwrap();
+
+    cmd.arg("paths").env("ARCHIVER_LANG", "es");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Rutas de configuración:"))
+        .stdout(predicate::str::contains("Directorio de proyectos:"));
+}
+
+#[test]
+fn test_color_flag_controls_styled_output() {
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["--color", "always", "paths"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b["));
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["--color", "auto", "paths"])
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[").not());
+}
+
+#[test]
+fn test_ascii_mode_prints_plain_ascii() {
+    let output = Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["--ascii", "paths"])
+        .env("ARCHIVER_LANG", "en")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_ascii());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("\x1b["));
+}
+
+#[test]
+fn test_restore_and_exclude_adds_the_project_to_the_exclusion_list() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let projects_dir = home.path().join("projects");
+    let archive_dir = home.path().join("archive");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(&projects_dir).unwrap();
+    std::fs::create_dir_all(archive_dir.join("old_project")).unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+            projects_dir, archive_dir
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        archive_dir.join("archive.json"),
+        format!(
+            r#"[{{"name":"old_project","original_path":{:?},"archive_path":{:?},"archived_at":"2024-01-01T00:00:00Z"}}]"#,
+            projects_dir.join("old_project"),
+            archive_dir.join("old_project")
+        ),
+    )
+    .unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["restore", "old_project", "--and-exclude"])
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("added to the exclusion list"));
+
+    assert!(projects_dir.join("old_project").exists());
+    let settings = std::fs::read_to_string(config_dir.join("settings.toml")).unwrap();
+    assert!(settings.contains("old_project"));
+}
+
+#[test]
+fn test_explain_prints_causes_and_fixes() {
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["explain", "a002"])
+        .env("ARCHIVER_LANG", "en")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("A002: Corrupt archive log"))
+        .stdout(predicate::str::contains("How to fix it"));
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["explain", "A999"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_errors_are_reported_with_their_code() {
+    let home = tempfile::tempdir().unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["restore", "missing"])
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Error[A006]:"))
+        .stderr(predicate::str::contains("archiver explain A006"));
+}
+
+#[test]
+fn test_simulate_runs_the_pipeline_on_a_synthetic_tree() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("sim");
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args([
+            "simulate",
+            "--projects",
+            "10",
+            "--max-size",
+            "256K",
+            "--dir",
+        ])
+        .arg(&root)
+        .env("ARCHIVER_LANG", "en")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Generated 10 projects"))
+        .stdout(predicate::str::contains("Archive: scanned 10 projects"));
+
+    assert!(root.join("archive").exists());
+}
+
+#[test]
+fn test_mangen_writes_a_page_per_visible_subcommand() {
+    let dir = tempfile::tempdir().unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .arg("mangen")
+        .arg(dir.path())
+        .env("ARCHIVER_LANG", "en")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("man pages"));
+
+    let main_page = std::fs::read_to_string(dir.path().join("archiver.1")).unwrap();
+    assert!(main_page.contains(".TH archiver"));
+    let run_page = std::fs::read_to_string(dir.path().join("archiver-run.1")).unwrap();
+    assert!(run_page.contains("dry\\-run"));
+    assert!(dir.path().join("archiver-restore.1").exists());
+    assert!(!dir.path().join("archiver-simulate.1").exists());
+    assert!(!dir.path().join("archiver-help.1").exists());
+}
+
+#[test]
+fn test_exclude_list_shows_kind_status_and_expiry() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let projects_dir = home.path().join("projects");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(projects_dir.join("dotfiles")).unwrap();
+    std::fs::create_dir_all(projects_dir.join("scratch-a")).unwrap();
+    std::fs::create_dir_all(projects_dir.join("scratch-b")).unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\nexclude = [\"dotfiles\", \"gone\"]\n",
+            projects_dir,
+            home.path().join("archive")
+        ),
+    )
+    .unwrap();
+    let archiver = || {
+        let mut cmd = Command::cargo_bin("archiver").unwrap();
+        cmd.env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env("ARCHIVER_LANG", "en")
+            .env("NO_COLOR", "1");
+        cmd
+    };
+
+    archiver()
+        .args(["exclude", "add", "scratch-*", "--until", "2020-01-31"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("until 2020-01-31"));
+
+    archiver()
+        .args(["exclude", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"dotfiles\s+exact\s+exists\s+permanent").unwrap())
+        .stdout(predicate::str::is_match(r"gone\s+exact\s+not found").unwrap())
+        .stdout(
+            predicate::str::is_match(r"scratch-\*\s+glob\s+2 matches\s+expired on 2020-01-31")
+                .unwrap(),
+        );
+
+    archiver()
+        .args(["exclude", "remove", "scratch-*"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("removed from the exclusion list"));
+    let settings = std::fs::read_to_string(config_dir.join("settings.toml")).unwrap();
+    assert!(!settings.contains("scratch"));
+}
+
+#[test]
+fn test_dry_run_prints_a_plan_grouped_by_action() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let project = home.path().join("projects/old_app");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(project.join("node_modules")).unwrap();
+    std::fs::write(project.join("package.json"), "{}").unwrap();
+    std::fs::write(project.join("node_modules/dep.js"), "x").unwrap();
+    std::fs::write(project.join(".gitignore"), "node_modules\n").unwrap();
+    for args in [&["init"][..], &["add", "."], &["commit", "-m", "old"]] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(&project)
+            .env("GIT_AUTHOR_DATE", "2023-01-01T12:00:00Z")
+            .env("GIT_COMMITTER_DATE", "2023-01-01T12:00:00Z")
+            .output()
+            .unwrap();
+    }
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n\n[[cleanup_rules]]\ndetection_file = \"package.json\"\nfolders_to_delete = [\"node_modules\"]\n",
+            home.path().join("projects"),
+            home.path().join("archive")
+        ),
+    )
+    .unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["run", "--dry-run"])
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Will archive 1 project(s)"))
+        .stdout(predicate::str::is_match(r"\+ old_app\s+").unwrap())
+        .stdout(predicate::str::contains(
+            "Will clean 1 project(s) before archiving",
+        ))
+        .stdout(predicate::str::is_match(r"~ old_app\s+1 B\s+node_modules").unwrap())
+        .stdout(predicate::str::contains("Plan: 1 to archive"));
+
+    assert!(project.join("node_modules").exists());
+}
+
+#[test]
+fn test_run_prints_a_summary_table() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let archive_dir = home.path().join("archive");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(home.path().join("projects")).unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+            home.path().join("projects"),
+            archive_dir
+        ),
+    )
+    .unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["--ascii", "run"])
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_STATE_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"\| Projects archived\s+\| 0\s+\|").unwrap())
+        .stdout(predicate::str::contains("| Failures"))
+        .stdout(predicate::str::contains(
+            archive_dir.join("archive.json").display().to_string(),
+        ))
+        .stdout(predicate::str::contains("last-run.json"));
+}
+
+#[test]
+fn test_legacy_settings_are_migrated_with_a_backup() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let projects_dir = home.path().join("projects");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dir = {:?}\narchive_dir = {:?}\n",
+            projects_dir,
+            home.path().join("archive")
+        ),
+    )
+    .unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .arg("paths")
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("upgraded from version 1 to 2"))
+        .stdout(predicate::str::contains(projects_dir.display().to_string()));
+
+    assert!(config_dir.join("settings.toml.v1.bak").exists());
+    let settings = std::fs::read_to_string(config_dir.join("settings.toml")).unwrap();
+    assert!(settings.contains("projects_dirs"));
+}
+
+#[test]
+fn test_list_settings_can_be_set_from_the_environment() {
+    let home = tempfile::tempdir().unwrap();
+    let work = home.path().join("work");
+    let side = home.path().join("side");
+    std::fs::create_dir_all(work.join("dotfiles")).unwrap();
+    std::fs::create_dir_all(side.join("tmp-a")).unwrap();
+    let archiver = || {
+        let mut cmd = Command::cargo_bin("archiver").unwrap();
+        cmd.env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env("ARCHIVER_LANG", "en")
+            .env("NO_COLOR", "1")
+            .env(
+                "ARCHIVER__PROJECTS_DIRS",
+                format!("{},{}", work.display(), side.display()),
+            )
+            .env("ARCHIVER__ARCHIVE_DIR", home.path().join("archive"))
+            .env("ARCHIVER__EXCLUDE", "dotfiles, tmp-*")
+            .env(
+                "ARCHIVER__CLEANUP_RULES",
+                r#"[{"detection_file": "package.json", "folders_to_delete": ["node_modules"]}]"#,
+            );
+        cmd
+    };
+
+    archiver()
+        .args(["exclude", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"dotfiles\s+exact\s+exists").unwrap())
+        .stdout(predicate::str::is_match(r"tmp-\*\s+glob\s+1 match").unwrap());
+
+    archiver()
+        .arg("paths")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(work.display().to_string()))
+        .stdout(predicate::str::contains(side.display().to_string()));
+
+    archiver()
+        .arg("paths")
+        .env("ARCHIVER__CLEANUP_RULES", "[{broken")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("ARCHIVER__CLEANUP_RULES"));
+}
+
+#[test]
+fn test_duplicates_lists_copies_with_the_one_to_keep_first() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let projects_dir = home.path().join("projects");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    for name in ["notes", "notes-copy"] {
+        std::fs::create_dir_all(projects_dir.join(name)).unwrap();
+        std::fs::write(projects_dir.join(name).join("todo.txt"), "same").unwrap();
+    }
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+            projects_dir,
+            home.path().join("archive")
+        ),
+    )
+    .unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .arg("duplicates")
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1 group(s) of duplicated projects",
+        ))
+        .stdout(predicate::str::contains("Identical content"))
+        .stdout(predicate::str::is_match(r"keep  .*notes").unwrap())
+        .stdout(predicate::str::is_match(r"copy  .*notes").unwrap())
+        .stdout(predicate::str::contains("archive_duplicates = true"));
+}
+
+#[test]
+fn test_remote_run_reports_unknown_remotes_with_their_code() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\n\n[[remotes]]\nname = \"nas\"\nhost = \"me@nas\"\nprojects_dirs = [\"~/code\"]\narchive_dir = \"~/archive\"\n",
+            home.path().join("projects")
+        ),
+    )
+    .unwrap();
+    let archiver = || {
+        let mut command = Command::cargo_bin("archiver").unwrap();
+        command
+            .env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env("ARCHIVER_LANG", "en")
+            .env("NO_COLOR", "1");
+        command
+    };
+
+    archiver()
+        .args(["remote", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("me@nas"))
+        .stdout(predicate::str::contains(
+            "projects: ~/code; archive: ~/archive",
+        ));
+    archiver()
+        .args(["remote", "run", "laptop", "--dry-run"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("A012"));
+}
+
+#[test]
+fn test_digest_summarizes_the_last_complete_week() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let state_dir = home.path().join(".local/state/archiver");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(&state_dir).unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+            home.path().join("projects"),
+            home.path().join("archive")
+        ),
+    )
+    .unwrap();
+    let last_week = chrono::Utc::now() - chrono::Duration::days(7);
+    let sample = |archived: usize, freed: u64| {
+        format!(
+            r#"{{"at":"{}","active_bytes":2048,"archive_bytes":4096,"freed_bytes":{},"archived_projects":{},"deleted_projects":0}}"#,
+            last_week.to_rfc3339(),
+            freed,
+            archived
+        )
+    };
+    std::fs::write(
+        state_dir.join("usage-history.jsonl"),
+        format!("{}\n{}\n", sample(2, 1024), sample(1, 1024)),
+    )
+    .unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .arg("digest")
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_STATE_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("archiver digest, "))
+        .stdout(predicate::str::contains(
+            "2 run(s): archived 3 project(s), deleted 0 from the archive, freed 2.0 KiB.",
+        ))
+        .stdout(predicate::str::contains(
+            "Projects: 2.0 KiB, archive: 4.0 KiB.",
+        ));
+}
+
+#[test]
+fn test_stats_trend_shows_the_usage_recorded_by_each_run() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let projects_dir = home.path().join("projects");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(projects_dir.join("app")).unwrap();
+    std::fs::write(projects_dir.join("app/main.rs"), "fn main() {}").unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+            projects_dir,
+            home.path().join("archive")
+        ),
+    )
+    .unwrap();
+    let archiver = || {
+        let mut cmd = Command::cargo_bin("archiver").unwrap();
+        cmd.env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_STATE_HOME")
+            .env("ARCHIVER_LANG", "en")
+            .env("NO_COLOR", "1");
+        cmd
+    };
+
+    archiver()
+        .args(["stats", "--trend"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No disk usage recorded yet"));
+
+    archiver().args(["run", "--dry-run"]).assert().success();
+    for _ in 0..2 {
+        archiver().arg("run").assert().success();
+    }
+
+    archiver()
+        .args(["stats", "--trend", "--by", "month"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Disk usage over 2 recorded run(s)",
+        ))
+        .stdout(predicate::str::is_match(r"\d{4}-\d{2}\s+active\s+12 B").unwrap())
+        .stdout(predicate::str::contains("Active usage changed by ="));
+
+    archiver()
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Projects directories\s+.\s+12 B").unwrap())
+        .stdout(predicate::str::is_match(r"Runs recorded\s+.\s+2").unwrap());
+
+    archiver()
+        .args(["stats", "--by", "week"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_plan_is_saved_then_applied() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let project = home.path().join("projects/old_app");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("main.rs"), "fn main() {}").unwrap();
+    for args in [&["init"][..], &["add", "."], &["commit", "-m", "old"]] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(&project)
+            .env("GIT_AUTHOR_DATE", "2023-01-01T12:00:00Z")
+            .env("GIT_COMMITTER_DATE", "2023-01-01T12:00:00Z")
+            .output()
+            .unwrap();
+    }
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+            home.path().join("projects"),
+            home.path().join("archive")
+        ),
+    )
+    .unwrap();
+    let plan = home.path().join("plan.json");
+    let archiver = |args: &[&str]| {
+        let mut command = Command::cargo_bin("archiver").unwrap();
+        command
+            .args(args)
+            .env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_STATE_HOME")
+            .env("ARCHIVER_LANG", "en")
+            .env("NO_COLOR", "1");
+        command
+    };
+
+    archiver(&["plan", "--out", plan.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Plan written to"))
+        .stdout(predicate::str::is_match(r"\+ old_app\s+").unwrap());
+    assert!(project.exists());
+
+    archiver(&["apply", plan.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 project(s)"));
+    assert!(!project.exists());
+    assert!(home.path().join("archive/old_app/main.rs").exists());
+
+    archiver(&["apply", plan.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Error[A014]:"))
+        .stderr(predicate::str::contains("'old_app' no longer exists"));
+}
+
+#[test]
+fn test_resume_without_an_interrupted_run_does_nothing() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(home.path().join("projects")).unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+            home.path().join("projects"),
+            home.path().join("archive")
+        ),
+    )
+    .unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["run", "--resume"])
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_STATE_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No interrupted run to resume."));
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["run", "--resume", "--dry-run"])
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_prompt_status_prints_the_cached_candidate_count() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let project = home.path().join("projects/old_app");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("main.rs"), "fn main() {}").unwrap();
+    for args in [&["init"][..], &["add", "."], &["commit", "-m", "old"]] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(&project)
+            .env("GIT_AUTHOR_DATE", "2023-01-01T12:00:00Z")
+            .env("GIT_COMMITTER_DATE", "2023-01-01T12:00:00Z")
+            .output()
+            .unwrap();
+    }
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+            home.path().join("projects"),
+            home.path().join("archive")
+        ),
+    )
+    .unwrap();
+    let archiver = |args: &[&str]| {
+        let mut command = Command::cargo_bin("archiver").unwrap();
+        command
+            .args(args)
+            .env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_STATE_HOME");
+        command
+    };
+
+    archiver(&["prompt-status", "--refresh"])
+        .assert()
+        .success()
+        .stdout("1⚑\n");
+    archiver(&["--ascii", "prompt-status"])
+        .assert()
+        .success()
+        .stdout("1!\n");
+
+    // A complete run leaves nothing to show.
+    archiver(&["run"]).assert().success();
+    archiver(&["prompt-status"]).assert().success().stdout("");
+}
+
+#[test]
+fn test_index_convert_and_compact() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let archive = home.path().join("archive");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(home.path().join("projects")).unwrap();
+    std::fs::create_dir_all(&archive).unwrap();
+    std::fs::write(archive.join("archive.json"), "[]").unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\nindex_format = \"jsonl\"\n",
+            home.path().join("projects"),
+            archive
+        ),
+    )
+    .unwrap();
+    let archiver = |args: &[&str]| {
+        let mut command = Command::cargo_bin("archiver").unwrap();
+        command
+            .args(args)
+            .env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_STATE_HOME")
+            .env("ARCHIVER_LANG", "en")
+            .env("NO_COLOR", "1");
+        command
+    };
+
+    archiver(&["list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("archiver index convert"));
+
+    archiver(&["index", "convert"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Converted 0 record(s)"));
+    assert!(archive.join("archive.jsonl").exists());
+    assert!(!archive.join("archive.json").exists());
+
+    archiver(&["index", "convert"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to convert"));
+    archiver(&["index", "compact"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Dropped 0 line(s)"));
+}
+
+#[test]
+fn test_dates_are_shown_in_the_chosen_timezone_and_format() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let archive = home.path().join("archive");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(&archive).unwrap();
+    std::fs::write(
+        archive.join("archive.json"),
+        format!(
+            r#"[{{"name": "late_app", "original_path": {:?}, "archive_path": {:?}, "archived_at": "2024-01-01T23:30:00Z"}}]"#,
+            home.path().join("projects/late_app"),
+            archive.join("late_app")
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+            home.path().join("projects"),
+            archive
+        ),
+    )
+    .unwrap();
+    let archiver = |args: &[&str]| {
+        let mut command = Command::cargo_bin("archiver").unwrap();
+        command
+            .args(args)
+            .env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_STATE_HOME")
+            .env("ARCHIVER_LANG", "en")
+            .env("NO_COLOR", "1");
+        command
+    };
+
+    archiver(&["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2024-01-01"));
+    archiver(&[
+        "list",
+        "--timezone",
+        "Europe/Madrid",
+        "--date-format",
+        "%d/%m/%Y",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("02/01/2024"));
+    archiver(&["list", "--timezone", "Mars/Olympus"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown timezone 'Mars/Olympus'"));
+}
+
+#[test]
+fn test_list_tree_shows_groups_and_projects() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let archive = home.path().join("archive");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(archive.join("work/api")).unwrap();
+    std::fs::write(archive.join("work/api/main.rs"), "fn main() {}").unwrap();
+    std::fs::write(
+        archive.join("archive.json"),
+        format!(
+            r#"[{{"name": "api", "original_path": {:?}, "archive_path": {:?}, "archived_at": "2024-03-01T10:00:00Z", "group": "work"}},
+               {{"name": "gone", "original_path": {:?}, "archive_path": {:?}, "archived_at": "2023-05-06T10:00:00Z"}}]"#,
+            home.path().join("projects/api"),
+            archive.join("work/api"),
+            home.path().join("projects/gone"),
+            archive.join("gone")
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+            home.path().join("projects"),
+            archive
+        ),
+    )
+    .unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["--ascii", "list", "--tree"])
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_STATE_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}  12 B",
+            archive.display()
+        )))
+        .stdout(predicate::str::contains("|-- work  12 B"))
+        .stdout(predicate::str::contains("|   `-- api  12 B  2024-03-01"))
+        .stdout(predicate::str::contains("`-- 2023  0 B"))
+        .stdout(predicate::str::contains(
+            "    `-- gone  0 B  2023-05-06  (missing from disk)",
+        ));
+}
+
+#[test]
+fn test_team_policy_is_layered_under_the_local_settings() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let projects_dir = home.path().join("projects");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(projects_dir.join("team-tool")).unwrap();
+    let policy = home.path().join("policy.toml");
+    std::fs::write(
+        &policy,
+        "exclude = [\"team-tool\"]\ninactivity_days = 10\nprojects_dirs = [\"/tmp\"]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!(
+            "projects_dirs = [{:?}]\narchive_dir = {:?}\nexclude = [\"mine\"]\ninactivity_days = 45\n\n[team_policy]\nurl = \"file://{}\"\n",
+            projects_dir,
+            home.path().join("archive"),
+            policy.display()
+        ),
+    )
+    .unwrap();
+    let archiver = || {
+        let mut cmd = Command::cargo_bin("archiver").unwrap();
+        cmd.env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_STATE_HOME")
+            .env("ARCHIVER_LANG", "en")
+            .env("NO_COLOR", "1");
+        cmd
+    };
+
+    archiver()
+        .args(["exclude", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(projects_dir.display().to_string()))
+        .stdout(predicate::str::is_match(r"mine\s+exact").unwrap())
+        .stdout(predicate::str::is_match(r"team-tool\s+exact\s+exists").unwrap());
+    assert!(
+        home.path()
+            .join(".local/state/archiver/team-policy.json")
+            .exists()
+    );
+
+    archiver()
+        .args(["exclude", "add", "other"])
+        .assert()
+        .success();
+    let settings = std::fs::read_to_string(config_dir.join("settings.toml")).unwrap();
+    assert!(settings.contains("other"));
+    assert!(!settings.contains("team-tool"));
+    assert!(settings.contains("inactivity_days = 45"));
+}
+
+#[test]
+fn test_restore_suggests_close_names_and_matches_loosely() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let projects_dir = home.path().join("projects");
+    let archive_dir = home.path().join("archive");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(&projects_dir).unwrap();
+    std::fs::create_dir_all(archive_dir.join("My-Project")).unwrap();
+    let settings = format!(
+        "projects_dirs = [{:?}]\narchive_dir = {:?}\n",
+        projects_dir, archive_dir
+    );
+    std::fs::write(config_dir.join("settings.toml"), &settings).unwrap();
+    std::fs::write(
+        archive_dir.join("archive.json"),
+        format!(
+            r#"[{{"name":"My-Project","original_path":{:?},"archive_path":{:?},"archived_at":"2024-01-01T00:00:00Z"}}]"#,
+            projects_dir.join("My-Project"),
+            archive_dir.join("My-Project")
+        ),
+    )
+    .unwrap();
+    let archiver = || {
+        let mut cmd = Command::cargo_bin("archiver").unwrap();
+        cmd.env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_STATE_HOME")
+            .env("ARCHIVER_LANG", "en")
+            .env("NO_COLOR", "1");
+        cmd
+    };
+
+    archiver()
+        .args(["restore", "my-project"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("A006"))
+        .stderr(predicate::str::contains("Did you mean 'My-Project'?"));
+
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        format!("{settings}loose_name_matching = true\n"),
+    )
+    .unwrap();
+    archiver()
+        .args(["restore", "my-project"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("My-Project"));
+    assert!(projects_dir.join("My-Project").exists());
+}
+
+#[test]
+fn test_settings_that_cannot_be_migrated_are_reported() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        "projects_dir = [unclosed\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .args(["explain", "A003"])
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Could not upgrade your settings file",
+        ))
+        .stderr(predicate::str::contains("A003"));
+}
+
+#[test]
+fn test_prompt_status_reads_only_the_cache() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config/archiver");
+    let state_dir = home.path().join(".local/state/archiver");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::create_dir_all(&state_dir).unwrap();
+    // Loading this policy would warn that it cannot be fetched.
+    std::fs::write(
+        config_dir.join("settings.toml"),
+        "[team_policy]\nurl = \"https://127.0.0.1:9/policy.toml\"\n",
+    )
+    .unwrap();
+    // A refresh is already running, so none is started.
+    let marker = state_dir.join("prompt-status.refreshing");
+    let started = chrono::Utc::now().to_rfc3339();
+    std::fs::write(&marker, &started).unwrap();
+
+    Command::cargo_bin("archiver")
+        .unwrap()
+        .arg("prompt-status")
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_STATE_HOME")
+        .env("ARCHIVER_LANG", "en")
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+    assert_eq!(std::fs::read_to_string(&marker).unwrap(), started);
+}
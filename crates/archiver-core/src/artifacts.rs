@@ -0,0 +1,48 @@
+//! Artifact-only archiving: for projects that are idle but not yet inactive, only the heavy
+//! build folders of their cleanup rules (`target/`, `node_modules/`, `.venv/`) are moved into
+//! the archive, leaving the sources in place.
+//!
+//! Artifacts live under `<archive_dir>/.artifacts/<project>/` and are recorded in
+//! `artifacts.json`, next to the archive log. Restoring them is optional: they can always be
+//! rebuilt instead.
+use crate::error::Result;
+use crate::models::ProjectName;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Directory of the archive holding moved artifacts.
+pub const DIR_NAME: &str = ".artifacts";
+
+/// Name of the file recording the moved artifacts, in the index directory.
+pub const FILE_NAME: &str = "artifacts.json";
+
+/// Build folders moved out of a project that stayed in place.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactRecord {
+    pub name: ProjectName,
+    pub project_path: PathBuf,
+    /// Where the folders were moved, keeping their paths relative to the project.
+    pub archive_path: PathBuf,
+    /// Moved folders, relative to the project.
+    pub folders: Vec<PathBuf>,
+    pub bytes: u64,
+    pub archived_at: DateTime<Utc>,
+}
+
+/// Reads the records at `path`. A missing file means no artifacts were moved.
+pub fn read(path: &Path) -> Result<Vec<ArtifactRecord>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Replaces the records at `path`.
+pub fn write(path: &Path, records: &[ArtifactRecord]) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(records)?)?;
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Removal of the folders matched by cleanup rules, sparing the files of the keep-list.
+//!
+//! Keep globs are matched against paths relative to the project root (`**/.env`,
+//! `**/*.sqlite`, `**/uploads/**`). A folder without protected files is removed at once;
+//! otherwise everything in it is removed except the protected files and the directories
+//! leading to them.
+use crate::error::Result;
+use glob::{MatchOptions, Pattern};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+/// `*` stays within a path component; `**` crosses them.
+const OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Globs of paths that cleanup must never delete.
+#[derive(Debug, Clone, Default)]
+pub struct KeepList {
+    patterns: Vec<Pattern>,
+}
+
+impl KeepList {
+    /// Compiles `globs`, skipping (and logging) invalid ones.
+    pub fn new<'a>(globs: impl IntoIterator<Item = &'a String>) -> Self {
+        let patterns = globs
+            .into_iter()
+            .filter_map(|glob| match Pattern::new(glob) {
+                Ok(pattern) => Some(pattern),
+                Err(e) => {
+                    warn!(%glob, error = %e, "Ignoring invalid keep glob.");
+                    None
+                }
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    /// Whether `relative`, a path relative to the project root, is protected.
+    pub fn protects(&self, relative: &Path) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches_path_with(relative, OPTIONS))
+    }
+
+    /// Protected paths inside `folder`, a folder of `project`. A protected directory is
+    /// listed without its contents; `folder` itself is listed when it is protected.
+    pub fn protected_in(&self, project: &Path, folder: &Path) -> Vec<PathBuf> {
+        if self.patterns.is_empty() {
+            return Vec::new();
+        }
+        let mut protected = Vec::new();
+        let mut walker = WalkDir::new(folder).into_iter();
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else { continue };
+            let Ok(relative) = entry.path().strip_prefix(project) else {
+                continue;
+            };
+            if self.protects(relative) {
+                protected.push(entry.path().to_path_buf());
+                if entry.file_type().is_dir() {
+                    walker.skip_current_dir();
+                }
+            }
+        }
+        protected
+    }
+}
+
+/// Removes `folder`, a folder of `project`, except for the paths `keep` protects. Returns the
+/// paths that were kept.
+pub fn remove_folder(project: &Path, folder: &Path, keep: &KeepList) -> Result<Vec<PathBuf>> {
+    let protected = keep.protected_in(project, folder);
+    if protected.is_empty() {
+        debug!(path = %folder.display(), "Removing folder matched by a cleanup rule.");
+        fs::remove_dir_all(folder)?;
+        return Ok(protected);
+    }
+    debug!(
+        path = %folder.display(),
+        kept = protected.len(),
+        "Removing folder matched by a cleanup rule, except for protected paths."
+    );
+    let is_kept = |path: &Path| {
+        protected
+            .iter()
+            .any(|kept| path.starts_with(kept) || kept.starts_with(path))
+    };
+    for entry in WalkDir::new(folder).contents_first(true) {
+        let entry = entry?;
+        if is_kept(entry.path()) {
+            continue;
+        }
+        if entry.file_type().is_dir() {
+            fs::remove_dir(entry.path())?;
+        } else {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(protected)
+}
@@ -1,7 +1,19 @@
+use crate::dates::DateFormatter;
+use crate::detect::ProjectType;
 use crate::error::{Error, Result};
+use crate::probe::ActivityProbe;
+use crate::remote::RemoteHost;
+use crate::team::TeamPolicy;
+use chrono::NaiveDate;
 use directories::{ProjectDirs, UserDirs};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// The team policy loaded by this process and the settings it gave, if it could be loaded.
+static LOADED_TEAM_POLICY: Mutex<Option<(TeamPolicy, Option<String>)>> = Mutex::new(None);
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CleanupRule {
@@ -9,31 +21,353 @@ pub struct CleanupRule {
     pub detection_file: String,
     /// Folders to be deleted (e.g. “node_modules”).
     pub folders_to_delete: Vec<String>,
+    /// Globs of paths, relative to the project root, that are never deleted even inside
+    /// `folders_to_delete` (e.g. “**/.env”).
+    #[serde(default)]
+    pub keep: Vec<String>,
+}
+
+/// An archive directory besides `archive_dir`, receiving the projects that match all of its
+/// conditions. Routes are tried in order; projects matching none go to `archive_dir`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ArchiveRoute {
+    /// Name of the destination, kept in the records of the projects archived there.
+    pub name: String,
+    /// Directory the projects are moved to (e.g. on an external drive).
+    pub archive_dir: PathBuf,
+    /// Types of the projects routed here (e.g. `["rust", "go"]`). Any type when empty.
+    #[serde(default)]
+    pub project_types: Vec<ProjectType>,
+    /// Projects directories whose projects are routed here. Any when empty.
+    #[serde(default)]
+    pub roots: Vec<PathBuf>,
+    /// Smallest size, in MiB, of the projects routed here.
+    #[serde(default)]
+    pub min_size_mb: Option<u64>,
+    /// Largest size, in MiB, of the projects routed here.
+    #[serde(default)]
+    pub max_size_mb: Option<u64>,
+}
+
+impl ArchiveRoute {
+    /// Whether the project at `path`, of `bytes`, goes to this destination.
+    pub fn matches(&self, path: &Path, bytes: u64) -> bool {
+        const MIB: u64 = 1024 * 1024;
+        (self.roots.is_empty() || self.roots.iter().any(|root| path.starts_with(root)))
+            && self.min_size_mb.is_none_or(|min| bytes >= min * MIB)
+            && self.max_size_mb.is_none_or(|max| bytes <= max * MIB)
+            && (self.project_types.is_empty()
+                || ProjectType::detect(path).is_some_and(|kind| self.project_types.contains(&kind)))
+    }
+}
+
+/// How much of a repository's history `trim_history` keeps. A commit is kept when either
+/// limit selects it.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct HistoryTrim {
+    /// Number of most recent commits to keep.
+    pub keep_commits: Option<usize>,
+    /// Keep the commits of this many last days.
+    pub keep_days: Option<u64>,
+}
+
+/// Format of the rolling log files.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line, for log aggregators such as Loki or Elastic.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(Error::Custom(format!(
+                "Unknown log format '{}', expected 'text' or 'json'.",
+                other
+            ))),
+        }
+    }
+}
+
+/// Storage format of the archive log.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexFormat {
+    /// `archive.json`, one JSON array rewritten on every change.
+    #[default]
+    Json,
+    /// `archive.jsonl`, an append-only log of added and removed records (see `journal`).
+    Jsonl,
+}
+
+/// How archived projects are laid out inside the archive directory.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveLayout {
+    /// Every project is stored directly under the archive directory.
+    #[default]
+    Flat,
+    /// Projects keep their path relative to their projects directory (e.g. `github.com/org/repo`),
+    /// as in GOPATH or ghq style trees. Usually combined with a `scan_depth` of 3.
+    Nested,
+}
+
+/// An entry of the exclusion list: a project name or a glob pattern (e.g. `scratch-*`),
+/// either permanent or only until a date.
+///
+/// ```toml
+/// exclude = ["dotfiles", "scratch-*", { pattern = "thesis", until = "2026-06-30" }]
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Exclusion {
+    Permanent(String),
+    Until { pattern: String, until: NaiveDate },
+}
+
+impl Exclusion {
+    pub fn new(pattern: impl Into<String>, until: Option<NaiveDate>) -> Self {
+        match until {
+            Some(until) => Self::Until {
+                pattern: pattern.into(),
+                until,
+            },
+            None => Self::Permanent(pattern.into()),
+        }
+    }
+
+    /// The project name or glob pattern.
+    pub fn pattern(&self) -> &str {
+        match self {
+            Self::Permanent(pattern) | Self::Until { pattern, .. } => pattern,
+        }
+    }
+
+    /// The last day the exclusion applies, if it expires.
+    pub fn until(&self) -> Option<NaiveDate> {
+        match self {
+            Self::Permanent(_) => None,
+            Self::Until { until, .. } => Some(*until),
+        }
+    }
+
+    /// Whether the pattern is a glob rather than an exact project name.
+    pub fn is_glob(&self) -> bool {
+        self.pattern().contains(['*', '?', '['])
+    }
+
+    /// Whether the exclusion no longer applies on `today`.
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.until().is_some_and(|until| until < today)
+    }
+
+    /// Whether the pattern matches the project `name`, regardless of expiry.
+    pub fn matches(&self, name: &str) -> bool {
+        let pattern = self.pattern();
+        glob::Pattern::new(pattern).map_or(pattern == name, |p| p.matches(name))
+    }
+}
+
+impl From<String> for Exclusion {
+    fn from(pattern: String) -> Self {
+        Self::Permanent(pattern)
+    }
+}
+
+impl From<&str> for Exclusion {
+    fn from(pattern: &str) -> Self {
+        Self::Permanent(pattern.to_string())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(default)]
 pub struct Settings {
-    /// Directory where projects are stored.
-    pub projects_dir: PathBuf,
+    /// Version of the settings schema, used to migrate older files (see `migrate`).
+    pub version: u32,
+
+    /// Directories where projects are stored. Each one is scanned the same way.
+    pub projects_dirs: Vec<PathBuf>,
 
     /// Directory where projects will be archived.
     pub archive_dir: PathBuf,
 
+    /// Other archive directories, each receiving the projects that match its rules (type,
+    /// projects directory, size). Projects matching no route go to `archive_dir`.
+    pub archive_routes: Vec<ArchiveRoute>,
+
+    /// Directory holding the archive log, when it should not live inside `archive_dir`
+    /// (e.g. `~/.local/state/archiver`). Existing logs are moved there on first use.
+    pub index_dir: Option<PathBuf>,
+
     /// Number of days of inactivity before a project is considered for archiving.
     pub inactivity_days: u64,
 
+    /// Inactivity thresholds for specific project types, overriding `inactivity_days`
+    /// (e.g. `node = 30`, `rust = 90`). Types: rust, go, java, python, ruby, php, node.
+    pub inactivity_days_by_type: BTreeMap<ProjectType, u64>,
+
+    /// How many directory levels below each projects directory to look for projects. With a value
+    /// greater than 1 (deep scanning), directories that only group other directories are
+    /// descended into, and workspaces (Cargo, pnpm, yarn, ...) are treated as one project.
+    pub scan_depth: usize,
+
+    /// Files after which the file time scan of a project without Git stops (e.g. for data
+    /// dumps with millions of files). Unlimited if unset. Only this scan is bounded: sizing,
+    /// the permission preflight and the untracked files of Git repositories still see every file.
+    pub mtime_scan_max_files: Option<u64>,
+
+    /// Seconds after which the file time scan of a project without Git stops. Unlimited if
+    /// unset. A project whose scan stopped early is never archived: the files seen may not be
+    /// the newest.
+    pub mtime_scan_timeout_secs: Option<u64>,
+
+    /// Maximum number of projects worked on at the same time (e.g. while scanning their
+    /// activity). `0` runs one job per CPU.
+    pub jobs: usize,
+
+    /// How archived projects are laid out inside the archive directory.
+    pub layout: ArchiveLayout,
+
+    /// Author emails whose commits count as activity. When empty, every commit counts;
+    /// otherwise commits by anyone else (e.g. merged upstream work) are ignored.
+    pub activity_authors: Vec<String>,
+
+    /// Commands giving the last activity of the projects containing a file (e.g. `*.tex`),
+    /// used instead of the git history and file times when they succeed.
+    pub activity_probes: Vec<ActivityProbe>,
+
+    /// Whether the newest untracked (non-ignored) file of a Git repository counts as activity,
+    /// for work that was never committed.
+    pub count_untracked_files: bool,
+
+    /// Whether to count lines of code per language when archiving and keep them in the record.
+    pub collect_code_stats: bool,
+
     /// Rules for cleaning up projects before archiving.
     pub cleanup_rules: Vec<CleanupRule>,
 
+    /// Globs of paths that no cleanup rule may delete, in addition to the `keep` globs of the
+    /// rules (e.g. `**/.env`, `**/*.sqlite`, `**/uploads/**`).
+    pub cleanup_keep: Vec<String>,
+
+    /// Whether archived projects are grouped by the name of the projects directory they come
+    /// from (e.g. `~/work` gives `work`), each group in its own subdirectory of the archive.
+    pub group_by_root: bool,
+
+    /// Group given to every project archived, overriding `group_by_root` (`run --group`).
+    pub archive_group: Option<String>,
+
+    /// Projects idle for more than this many days, but not long enough to be archived, have
+    /// the folders of their cleanup rules moved into the archive (`archiver artifacts run`),
+    /// leaving their sources in place.
+    pub artifact_days: Option<u64>,
+
+    /// Trims the git history of repositories before archiving them, keeping only recent
+    /// commits. Repositories with unpushed commits are archived with their full history.
+    pub trim_history: Option<HistoryTrim>,
+
+    /// Whether to archive every copy of a duplicated project (same remote or same content)
+    /// except the most recently active one, however recently the copies were used.
+    pub archive_duplicates: bool,
+
     /// Whether to enable automatic deletion of archived projects.
     pub enable_auto_delete: bool,
 
     /// Number of days before an archived project is deleted.
     pub days_before_delete: u64,
 
-    /// A list of project names to exclude from archiving.
-    pub exclude: Vec<String>,
+    /// Automatic deletion never happens before this many times `inactivity_days`, whatever
+    /// `days_before_delete` says.
+    pub delete_safety_multiplier: u64,
+
+    /// Project names or glob patterns to exclude from archiving, optionally until a date.
+    pub exclude: Vec<Exclusion>,
+
+    /// Whether a project restored by name is added to `exclude`, so the next run does not
+    /// archive it again right away.
+    pub exclude_on_restore: bool,
+
+    /// Whether project names given to `restore` and `delete` also match archived names that
+    /// differ only in case or Unicode normalization (e.g. `my-project` for `My-Project`).
+    pub loose_name_matching: bool,
+
+    /// Branch patterns (e.g. `wip/*`) that protect a repository from being archived
+    /// while one of them is checked out.
+    pub skip_branches: Vec<String>,
+
+    /// Number of restores after which a project archived again is shown with a suggestion to
+    /// exclude or snooze it. `0` turns the suggestion off.
+    pub suggest_exclusion_after: u32,
+
+    /// Whether repositories with open pull/merge requests authored by the user, or awaiting
+    /// their review, count as active. Requires an authenticated `gh` (GitHub) or `glab` (GitLab).
+    pub check_open_reviews: bool,
+
+    /// Self-hosted GitLab instances (e.g. `gitlab.example.com`), in addition to gitlab.com.
+    pub gitlab_hosts: Vec<String>,
+
+    /// Whether projects bind-mounted into running containers (dev containers, docker-compose
+    /// stacks) count as active. The containers are listed once per run; nothing is checked,
+    /// and nothing is reported, when the engine's socket does not exist.
+    pub check_containers: bool,
+
+    /// Docker (or Podman) API socket. Defaults to a `unix://` `DOCKER_HOST`, then
+    /// `/var/run/docker.sock`.
+    pub docker_socket: Option<PathBuf>,
+
+    /// Whether to remove archived projects from the recent projects list of JetBrains IDEs.
+    pub clean_jetbrains_recent_projects: bool,
+
+    /// Other machines whose projects are scanned and archived over SSH (`archiver remote`),
+    /// each with its own projects and archive directories.
+    pub remotes: Vec<RemoteHost>,
+
+    /// Program used to reach `remotes`, called as `<ssh_program> -- <host> <command>`.
+    pub ssh_program: String,
+
+    /// Seconds network git operations (e.g. fetches) wait for a server before giving up.
+    /// Authentication uses the SSH agent, the default SSH keys and the git credential helpers.
+    pub network_timeout_secs: u64,
+
+    /// Whether the archive index is shared between machines (e.g. on a synced drive).
+    /// Each machine then writes its own log file and reads the union of all of them.
+    pub sync_index: bool,
+
+    /// Name of this machine in a shared archive index. Defaults to the hostname.
+    pub machine_id: Option<String>,
+
+    /// Format of the archive log: `json`, rewritten on every change, or `jsonl`, where every
+    /// change is appended as a line. Ignored with `sync_index`. An existing log is converted
+    /// with `archiver index convert`.
+    pub index_format: IndexFormat,
+
+    /// Settings shared by a team from a URL (e.g. exclusions and retention for shared build
+    /// machines). The local settings override them, except exclusions, which are combined.
+    pub team_policy: Option<TeamPolicy>,
+
+    /// Format of the log files written to the state directory.
+    pub log_format: LogFormat,
+
+    /// Timezone dates are shown in: `UTC`, `local` or a name such as `Europe/Madrid`.
+    /// Dates are always stored in UTC.
+    pub timezone: String,
+
+    /// How dates are shown, as a strftime pattern (e.g. `%d/%m/%Y`).
+    pub date_format: String,
+
+    /// Minutes `prompt-status` shows its cached count before counting again, in the
+    /// background.
+    pub prompt_cache_minutes: u64,
 }
 
 impl Default for Settings {
@@ -41,32 +375,70 @@ impl Default for Settings {
     // This `default()` is mainly for `serde`. The default command is executed with `just`.
     fn default() -> Self {
         Self {
-            projects_dir: PathBuf::new(),
+            version: crate::migrate::CURRENT_VERSION,
+            projects_dirs: vec![],
             archive_dir: PathBuf::new(),
+            archive_routes: vec![],
+            index_dir: None,
             inactivity_days: 30,
+            inactivity_days_by_type: BTreeMap::new(),
+            scan_depth: 1,
+            mtime_scan_max_files: None,
+            mtime_scan_timeout_secs: None,
+            jobs: 0,
+            layout: ArchiveLayout::Flat,
+            activity_authors: vec![],
+            count_untracked_files: false,
+            collect_code_stats: false,
+            activity_probes: vec![],
             cleanup_rules: vec![],
+            cleanup_keep: vec![],
+            artifact_days: None,
+            group_by_root: false,
+            archive_group: None,
+            trim_history: None,
+            archive_duplicates: false,
             enable_auto_delete: false,
             days_before_delete: 365,
+            delete_safety_multiplier: 2,
             exclude: vec![],
+            exclude_on_restore: false,
+            loose_name_matching: false,
+            skip_branches: vec![],
+            suggest_exclusion_after: 3,
+            check_open_reviews: false,
+            gitlab_hosts: vec![],
+            check_containers: true,
+            docker_socket: None,
+            clean_jetbrains_recent_projects: false,
+            remotes: vec![],
+            ssh_program: "ssh".to_string(),
+            network_timeout_secs: 30,
+            sync_index: false,
+            machine_id: None,
+            index_format: IndexFormat::Json,
+            team_policy: None,
+            log_format: LogFormat::Text,
+            timezone: "UTC".to_string(),
+            date_format: "%Y-%m-%d".to_string(),
+            prompt_cache_minutes: 60,
         }
     }
 }
 
 impl Settings {
-    
     const CONFIG_FILE_NAME: &'static str = "settings.toml";
-    
+
     const APP_NAME: &'static str = "archiver";
     const APP_AUTHOR: &'static str = "cuervolu";
     const APP_QUALIFIER: &'static str = "dev";
     const APP_ENV: &'static str = "ARCHIVER";
-    
-    
+
     /// Returns the standard, platform-specific path for the configuration file.
     pub fn config_path() -> Result<PathBuf> {
         ProjectDirs::from(Self::APP_QUALIFIER, Self::APP_AUTHOR, Self::APP_NAME)
             .map(|proj_dirs| proj_dirs.config_dir().join(Self::CONFIG_FILE_NAME))
-            .ok_or(Error::HomeDirNotFound) 
+            .ok_or(Error::HomeDirNotFound)
     }
 
     /// Returns the standard, platform-specific path for the log directory.
@@ -76,24 +448,268 @@ impl Settings {
             .ok_or(Error::HomeDirNotFound)
     }
 
-    /// Loads settings from the config file, applying defaults for missing values.
+    /// Returns the path of the JSON summary of the last run, in the log directory.
+    pub fn run_summary_path() -> Result<PathBuf> {
+        Self::log_path().map(|dir| dir.join("last-run.json"))
+    }
+
+    /// Returns the path of the archive candidates count cached for shell prompts, in the log
+    /// directory.
+    pub fn prompt_status_path() -> Result<PathBuf> {
+        Self::log_path().map(|dir| dir.join("prompt-status.json"))
+    }
+
+    /// Returns the path of the marker held while the prompt count is refreshed in the
+    /// background, in the log directory.
+    pub fn prompt_refresh_marker_path() -> Result<PathBuf> {
+        Self::log_path().map(|dir| dir.join("prompt-status.refreshing"))
+    }
+
+    /// Returns the path of the cached team policy, in the log directory.
+    pub fn team_policy_cache_path() -> Result<PathBuf> {
+        Self::log_path().map(|dir| dir.join("team-policy.json"))
+    }
+
+    /// Returns the path of the disk usage history, in the log directory.
+    pub fn usage_history_path() -> Result<PathBuf> {
+        Self::log_path().map(|dir| dir.join("usage-history.jsonl"))
+    }
+
+    /// Days of inactivity before the project at `path` is considered for archiving, from
+    /// `inactivity_days_by_type` when its type has a threshold, `inactivity_days` otherwise.
+    pub fn inactivity_days_for(&self, path: &Path) -> u64 {
+        if self.inactivity_days_by_type.is_empty() {
+            return self.inactivity_days;
+        }
+        self.inactivity_days_of_type(ProjectType::detect(path))
+    }
+
+    /// Days of inactivity before a project of type `kind` is considered for archiving.
+    pub fn inactivity_days_of_type(&self, kind: Option<ProjectType>) -> u64 {
+        kind.and_then(|kind| self.inactivity_days_by_type.get(&kind).copied())
+            .unwrap_or(self.inactivity_days)
+    }
+
+    /// Whether the project `name` is currently excluded from archiving.
+    pub fn is_excluded(&self, name: &str) -> bool {
+        let today = chrono::Local::now().date_naive();
+        self.exclude
+            .iter()
+            .any(|e| !e.is_expired(today) && e.matches(name))
+    }
+
+    /// Directory holding the archive log: `index_dir`, or the archive directory itself.
+    pub fn index_dir(&self) -> &Path {
+        self.index_dir.as_deref().unwrap_or(&self.archive_dir)
+    }
+
+    /// Every directory projects are archived to: `archive_dir`, then those of `archive_routes`.
+    pub fn archive_dirs(&self) -> impl Iterator<Item = &Path> {
+        std::iter::once(self.archive_dir.as_path()).chain(
+            self.archive_routes
+                .iter()
+                .map(|route| route.archive_dir.as_path()),
+        )
+    }
+
+    /// Number of jobs to run at once: `jobs`, or the number of CPUs when it is `0`.
+    pub fn jobs(&self) -> usize {
+        match self.jobs {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            jobs => jobs,
+        }
+    }
+
+    /// How long network git operations wait for a server.
+    pub fn network_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.network_timeout_secs)
+    }
+
+    /// Formats dates with `timezone` and `date_format`, failing if either is invalid.
+    pub fn date_formatter(&self) -> Result<DateFormatter> {
+        DateFormatter::new(&self.timezone, &self.date_format)
+    }
+
+    /// Returns the name identifying this machine in a shared archive index.
+    pub fn machine_id(&self) -> String {
+        self.machine_id
+            .clone()
+            .unwrap_or_else(crate::sync::hostname)
+    }
+
+    /// Loads settings from the config file over the team policy, applying defaults for missing
+    /// values.
     pub fn new() -> Result<Self> {
+        Self::load(true)
+    }
+
+    /// Loads the settings of this machine only, without the team policy, to edit and save them.
+    pub fn new_local() -> Result<Self> {
+        Self::load(false)
+    }
+
+    fn load(with_team_policy: bool) -> Result<Self> {
         let config_path = Self::config_path()?;
         let config_file_path_str = config_path.to_str().unwrap_or_default();
 
         let user_dirs = UserDirs::new().ok_or(Error::HomeDirNotFound)?;
         let home_dir = user_dirs.home_dir();
-        let projects_default = user_dirs.document_dir().unwrap_or(home_dir).join("projects");
+        let projects_default = user_dirs
+            .document_dir()
+            .unwrap_or(home_dir)
+            .join("projects");
         let archive_default = home_dir.join(".archive");
 
-        let config_builder = config::Config::builder()
-            .add_source(config::File::with_name(config_file_path_str).required(false))
-            .add_source(config::Environment::with_prefix(Self::APP_ENV).separator("__"))
-            .set_default("projects_dir", projects_default.to_str())?
+        if config_path.exists() {
+            crate::migrate::migrate_file(&config_path)?;
+        }
+
+        let local = config::File::with_name(config_file_path_str).required(false);
+        let team_policy = with_team_policy
+            .then(|| Self::team_policy_settings(&local))
+            .flatten();
+        let mut config_builder = config::Config::builder();
+        if let Some(policy) = &team_policy {
+            config_builder =
+                config_builder.add_source(config::File::from_str(policy, config::FileFormat::Toml));
+        }
+        let config_builder = config_builder
+            .add_source(local)
+            .add_source(EnvOverrides::from_env())
+            .set_default(
+                "projects_dirs",
+                vec![projects_default.to_string_lossy().into_owned()],
+            )?
             .set_default("archive_dir", archive_default.to_str())?
             .set_default("inactivity_days", 30)?
             .build()?;
 
-        config_builder.try_deserialize().map_err(Error::Config)
+        let mut settings: Self = config_builder.try_deserialize().map_err(Error::Config)?;
+        // The exclusions of the team are kept along with the local ones instead of replaced.
+        if let Some(team_exclude) = team_policy
+            .and_then(|policy| toml::from_str::<toml::Table>(&policy).ok())
+            .and_then(|mut policy| policy.remove("exclude"))
+            .and_then(|exclude| exclude.try_into::<Vec<Exclusion>>().ok())
+        {
+            for exclusion in team_exclude {
+                if !settings.exclude.contains(&exclusion) {
+                    settings.exclude.push(exclusion);
+                }
+            }
+        }
+        Ok(settings)
+    }
+
+    /// The settings of the team policy configured in `local`, if any. A policy that cannot be
+    /// loaded is left out, so the local settings still apply.
+    fn team_policy_settings(
+        local: &config::File<config::FileSourceFile, config::FileFormat>,
+    ) -> Option<String> {
+        let local = config::Config::builder()
+            .add_source(local.clone())
+            .add_source(EnvOverrides::from_env())
+            .build()
+            .ok()?;
+        let policy: TeamPolicy = local.get("team_policy").ok()?;
+        // Loaded once per process: a command loading the settings again does not fetch again.
+        let mut loaded_before = LOADED_TEAM_POLICY.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((loaded_policy, settings)) = loaded_before.as_ref() {
+            if *loaded_policy == policy {
+                return settings.clone();
+            }
+        }
+        let timeout = local.get("network_timeout_secs").unwrap_or(30);
+        let loaded = Self::team_policy_cache_path().and_then(|cache_path| {
+            crate::team::load(
+                &policy,
+                &cache_path,
+                std::time::Duration::from_secs(timeout),
+            )
+        });
+        let settings = match loaded {
+            Ok(settings) => Some(settings),
+            Err(e) => {
+                tracing::warn!(error = %e, "Could not load the team policy, using the local settings only.");
+                None
+            }
+        };
+        *loaded_before = Some((policy, settings.clone()));
+        settings
+    }
+}
+
+/// `ARCHIVER__*` environment variables as a configuration source.
+///
+/// Like `config::Environment`, `ARCHIVER__SCAN_DEPTH` sets `scan_depth` and a further `__`
+/// descends into tables (`ARCHIVER__INACTIVITY_DAYS_BY_TYPE__RUST`). Values starting with `[`
+/// or `{` are parsed as JSON, which is how lists of tables such as `cleanup_rules` are set.
+/// Other values of list settings are split on commas, so `ARCHIVER__EXCLUDE=dotfiles,tmp-*`
+/// works and an empty value clears the list.
+#[derive(Debug, Clone)]
+struct EnvOverrides {
+    vars: Vec<(String, String)>,
+}
+
+impl EnvOverrides {
+    fn from_env() -> Self {
+        Self {
+            vars: std::env::vars().collect(),
+        }
+    }
+
+    fn parse(
+        name: &str,
+        key: &str,
+        raw: &str,
+        defaults: &serde_json::Value,
+    ) -> std::result::Result<config::Value, config::ConfigError> {
+        let origin = name.to_string();
+        let trimmed = raw.trim();
+        if trimmed.starts_with(['[', '{']) {
+            return serde_json::from_str(trimmed).map_err(|e| {
+                config::ConfigError::Message(format!("invalid JSON in {}: {}", name, e))
+            });
+        }
+        let value = if defaults.get(key).is_some_and(serde_json::Value::is_array) {
+            let items: Vec<config::Value> = trimmed
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| config::Value::new(Some(&origin), item))
+                .collect();
+            config::Value::new(Some(&origin), items)
+        } else {
+            config::Value::new(Some(&origin), raw)
+        };
+        Ok(value)
+    }
+}
+
+impl config::Source for EnvOverrides {
+    fn clone_into_box(&self) -> Box<dyn config::Source + Send + Sync> {
+        Box::new(self.clone())
     }
-}
\ No newline at end of file
+
+    fn collect(
+        &self,
+    ) -> std::result::Result<config::Map<String, config::Value>, config::ConfigError> {
+        let prefix = format!("{}__", Settings::APP_ENV);
+        // Which settings are lists, from their serialized defaults.
+        let defaults = serde_json::to_value(Settings::default()).unwrap_or_default();
+        let mut map = config::Map::new();
+        for (name, raw) in &self.vars {
+            let Some(rest) = name
+                .get(..prefix.len())
+                .filter(|head| head.eq_ignore_ascii_case(&prefix))
+                .and_then(|_| name.get(prefix.len()..))
+                .filter(|rest| !rest.is_empty())
+            else {
+                continue;
+            };
+            let key = rest.to_lowercase().replace("__", ".");
+            let value = Self::parse(name, &key, raw, &defaults)?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
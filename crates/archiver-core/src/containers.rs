@@ -0,0 +1,117 @@
+//! Detection of running containers (dev containers, docker-compose stacks) with bind mounts
+//! into a project, which must not be moved from under them.
+//!
+//! The Docker Engine API is queried directly over its Unix socket, so neither the `docker`
+//! CLI nor any client library is needed. Podman's Docker-compatible socket works too.
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::env;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::debug;
+
+/// Socket used when neither the settings nor `DOCKER_HOST` name one.
+const DEFAULT_SOCKET: &str = "/var/run/docker.sock";
+
+/// A running container and the host directories bind-mounted into it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunningContainer {
+    /// Container name, without the leading `/` of the API.
+    pub name: String,
+    /// Host paths of its bind mounts.
+    pub mounts: Vec<PathBuf>,
+}
+
+impl RunningContainer {
+    /// Whether one of the bind mounts is `project` or lies inside it.
+    pub fn mounts_from(&self, project: &Path) -> bool {
+        let canonical = project.canonicalize().ok();
+        self.mounts.iter().any(|mount| {
+            mount.starts_with(project) || canonical.as_ref().is_some_and(|c| mount.starts_with(c))
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApiContainer {
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    mounts: Vec<ApiMount>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApiMount {
+    #[serde(rename = "Type", default)]
+    kind: String,
+    #[serde(default)]
+    source: PathBuf,
+}
+
+/// The Docker socket to query: `configured`, else a `unix://` `DOCKER_HOST`, else the default
+/// socket.
+pub fn socket_path(configured: Option<&Path>) -> PathBuf {
+    if let Some(path) = configured {
+        return path.to_path_buf();
+    }
+    env::var("DOCKER_HOST")
+        .ok()
+        .and_then(|host| host.strip_prefix("unix://").map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SOCKET))
+}
+
+/// Lists the running containers of the engine listening on `socket`. A missing socket means
+/// no engine, hence no containers.
+pub fn running(socket: &Path) -> Result<Vec<RunningContainer>> {
+    if !socket.exists() {
+        debug!(socket = %socket.display(), "No container engine socket.");
+        return Ok(Vec::new());
+    }
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+    // HTTP/1.0 keeps the response unchunked and closes the connection after it.
+    stream.write_all(b"GET /containers/json HTTP/1.0\r\nHost: docker\r\n\r\n")?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| Error::Custom("Malformed response from the container engine".into()))?;
+    let status = head.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(Error::Custom(format!(
+            "Container engine at '{}' answered '{}'",
+            socket.display(),
+            status
+        )));
+    }
+
+    let containers: Vec<ApiContainer> = serde_json::from_str(body).map_err(|e| {
+        Error::Custom(format!(
+            "Malformed container list from the container engine: {}",
+            e
+        ))
+    })?;
+    Ok(containers
+        .into_iter()
+        .map(|container| RunningContainer {
+            name: container
+                .names
+                .first()
+                .map(|name| name.trim_start_matches('/').to_string())
+                .unwrap_or_default(),
+            mounts: container
+                .mounts
+                .into_iter()
+                .filter(|mount| mount.kind == "bind")
+                .map(|mount| mount.source)
+                .collect(),
+        })
+        .collect())
+}
@@ -0,0 +1,123 @@
+//! Authentication for network git operations, so private repositories can be fetched without
+//! storing secrets in the settings.
+//!
+//! Credentials are tried in the order `git` itself would: the SSH agent, then the default SSH
+//! keys of `~/.ssh`, then the credential helpers configured for the repository
+//! (`credential.helper`). Each is tried once, so a rejected credential fails the operation
+//! instead of looping.
+use crate::error::Result;
+use directories::BaseDirs;
+use git2::{Config, Cred, CredentialType, FetchOptions, RemoteCallbacks, Repository};
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Private keys tried after the SSH agent, in `~/.ssh`.
+const DEFAULT_KEYS: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+static TIMEOUTS: Once = Once::new();
+
+/// Sets how long libgit2 waits to connect to a server and for each of its answers. The
+/// setting is global to the process, so only the first call applies; it must happen before
+/// any thread starts a network operation.
+pub fn init_timeouts(timeout: Duration) {
+    TIMEOUTS.call_once(|| {
+        let millis = timeout.as_millis().try_into().unwrap_or(libc::c_int::MAX);
+        // SAFETY: guarded by `TIMEOUTS`, and called before worker threads use libgit2.
+        let result = unsafe {
+            git2::opts::set_server_connect_timeout_in_milliseconds(millis)
+                .and_then(|_| git2::opts::set_server_timeout_in_milliseconds(millis))
+        };
+        if let Err(e) = result {
+            debug!(error = %e, "Could not set the git network timeouts.");
+        }
+    });
+}
+
+/// Credentials left to try for one operation.
+struct Attempts {
+    config: Config,
+    agent_tried: bool,
+    keys: Vec<PathBuf>,
+    helper_tried: bool,
+}
+
+impl Attempts {
+    fn new(config: Config) -> Self {
+        let mut keys = default_keys();
+        keys.reverse();
+        Self {
+            config,
+            agent_tried: false,
+            keys,
+            helper_tried: false,
+        }
+    }
+
+    fn next(
+        &mut self,
+        url: &str,
+        username: Option<&str>,
+        allowed: CredentialType,
+    ) -> std::result::Result<Cred, git2::Error> {
+        let user = username.unwrap_or("git");
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(user);
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            if !self.agent_tried {
+                self.agent_tried = true;
+                debug!(url, "Trying the SSH agent.");
+                return Cred::ssh_key_from_agent(user);
+            }
+            if let Some(key) = self.keys.pop() {
+                debug!(url, key = %key.display(), "Trying an SSH key.");
+                return Cred::ssh_key(user, None, &key, None);
+            }
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !self.helper_tried {
+            self.helper_tried = true;
+            debug!(url, "Trying the git credential helpers.");
+            return Cred::credential_helper(&self.config, url, username);
+        }
+        Err(git2::Error::from_str(&format!(
+            "no credentials left to authenticate to {url}"
+        )))
+    }
+}
+
+/// The default private keys that exist.
+fn default_keys() -> Vec<PathBuf> {
+    let Some(base_dirs) = BaseDirs::new() else {
+        return Vec::new();
+    };
+    let ssh_dir = base_dirs.home_dir().join(".ssh");
+    DEFAULT_KEYS
+        .iter()
+        .map(|name| ssh_dir.join(name))
+        .filter(|key| key.is_file())
+        .collect()
+}
+
+/// Callbacks authenticating with the agent, default keys and credential helpers of `config`,
+/// and aborting transfers still running after `timeout`.
+pub fn callbacks(config: Config, timeout: Duration) -> RemoteCallbacks<'static> {
+    let mut attempts = Attempts::new(config);
+    let deadline = Instant::now() + timeout;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| attempts.next(url, username, allowed));
+    callbacks.transfer_progress(move |_| Instant::now() < deadline);
+    callbacks
+}
+
+/// Fetches `remote` of the repository at `path` with its configured refspecs.
+pub fn fetch(path: &Path, remote: &str, timeout: Duration) -> Result<()> {
+    let repo = Repository::open(path)?;
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks(repo.config()?, timeout));
+    repo.find_remote(remote)?
+        .fetch::<&str>(&[], Some(&mut options), None)?;
+    debug!(path = %path.display(), remote, "Fetched remote.");
+    Ok(())
+}
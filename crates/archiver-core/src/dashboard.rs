@@ -0,0 +1,247 @@
+//! A read-only HTML dashboard (`archiver serve`): archive candidates, the archive contents and
+//! their sizes, and the recent runs, to check on a machine from a browser.
+//!
+//! The page is rebuilt on every request from the same data the CLI reads. The server only
+//! answers `GET /`, one connection at a time, and never changes anything.
+use crate::config::Settings;
+use crate::error::Result;
+use crate::summary::RunSummary;
+use crate::trend::{self, UsageSample};
+use crate::Archiver;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Runs shown in the recent runs table.
+const RECENT_RUNS: usize = 10;
+
+/// Answers requests on `listener` until it fails.
+pub fn serve(archiver: &Archiver, listener: TcpListener) -> Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        if let Err(e) = answer(archiver, stream) {
+            warn!(error = %e, "Could not answer a dashboard request.");
+        }
+    }
+    Ok(())
+}
+
+fn answer(archiver: &Archiver, mut stream: TcpStream) -> Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Read the headers too: closing with unread data would reset the connection.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim_end().is_empty() {
+        header.clear();
+    }
+    debug!(request = %request_line.trim_end(), "Dashboard request.");
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/")) => {
+            let last_run = Settings::run_summary_path()
+                .and_then(|path| RunSummary::read(&path))
+                .ok()
+                .flatten();
+            let history = Settings::usage_history_path()
+                .and_then(|path| trend::read(&path))
+                .unwrap_or_default();
+            match render(archiver, last_run.as_ref(), &history) {
+                Ok(page) => ("200 OK", page),
+                Err(e) => (
+                    "500 Internal Server Error",
+                    page("Error", &format!("<p>{}</p>", escape(&e.to_string()))),
+                ),
+            }
+        }
+        (Some("GET"), Some(_)) => ("404 Not Found", page("Not found", "")),
+        _ => ("405 Method Not Allowed", page("Read-only", "")),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    Ok(())
+}
+
+/// Builds the dashboard page from the current candidates and archive, the summary of the last
+/// run and the usage history.
+pub fn render(
+    archiver: &Archiver,
+    last_run: Option<&RunSummary>,
+    history: &[UsageSample],
+) -> Result<String> {
+    let dates = archiver.settings().date_formatter()?;
+    let date = |at| dates.date_time(at);
+    let plan = archiver.plan()?;
+    let records = archiver.get_archive_records()?;
+    let archived_bytes: u64 = records
+        .iter()
+        .filter_map(|record| record.manifest.as_ref())
+        .map(|manifest| manifest.bytes)
+        .sum();
+    let mut body = String::new();
+
+    let last_run_cell = last_run.map_or("never".to_string(), |run| {
+        format!(
+            "{}{}",
+            date(run.finished_at),
+            if run.error.is_some() { " (failed)" } else { "" }
+        )
+    });
+    let overview = [
+        ("Archive candidates", plan.archive.len().to_string()),
+        ("Due for deletion", plan.delete.len().to_string()),
+        (
+            "Archived projects",
+            format!("{} ({})", records.len(), size(archived_bytes)),
+        ),
+        ("Last run", last_run_cell),
+    ];
+    body.push_str("<table class=\"overview\">");
+    for (label, value) in overview {
+        let _ = write!(body, "<tr><th>{label}</th><td>{}</td></tr>", escape(&value));
+    }
+    body.push_str("</table>");
+
+    section(
+        &mut body,
+        "Archive candidates",
+        &["Project", "Last activity", "Path"],
+        plan.archive.iter().map(|project| {
+            vec![
+                project.name.to_string(),
+                date(project.last_activity),
+                project.path.display().to_string(),
+            ]
+        }),
+    );
+    section(
+        &mut body,
+        "Due for deletion",
+        &["Project", "Archived", "Path"],
+        plan.delete.iter().map(|record| {
+            vec![
+                record.label(),
+                date(record.archived_at),
+                record.archive_path.display().to_string(),
+            ]
+        }),
+    );
+    section(
+        &mut body,
+        "Archive",
+        &[
+            "Project",
+            "Archived",
+            "Size",
+            "Group",
+            "Destination",
+            "Tags",
+        ],
+        records.iter().map(|record| {
+            vec![
+                record.label(),
+                date(record.archived_at),
+                record
+                    .manifest
+                    .as_ref()
+                    .map_or(String::new(), |manifest| size(manifest.bytes)),
+                record.group.clone().unwrap_or_default(),
+                record.destination.clone().unwrap_or_default(),
+                record.tags.join(", "),
+            ]
+        }),
+    );
+    section(
+        &mut body,
+        "Recent runs",
+        &[
+            "Date", "Archived", "Deleted", "Freed", "Projects", "Archive",
+        ],
+        history.iter().rev().take(RECENT_RUNS).map(|sample| {
+            vec![
+                date(sample.at),
+                sample.archived_projects.to_string(),
+                sample.deleted_projects.to_string(),
+                size(sample.freed_bytes),
+                size(sample.active_bytes),
+                size(sample.archive_bytes),
+            ]
+        }),
+    );
+    if let Some(error) = last_run.and_then(|run| run.error.as_ref()) {
+        let _ = write!(
+            body,
+            "<p class=\"error\">Last run failed: {}</p>",
+            escape(error)
+        );
+    }
+    Ok(page("archiver", &body))
+}
+
+/// Appends a titled table, or a note that it is empty.
+fn section(
+    body: &mut String,
+    title: &str,
+    headers: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+) {
+    let _ = write!(body, "<h2>{title}</h2>");
+    let mut rows = rows.peekable();
+    if rows.peek().is_none() {
+        body.push_str("<p class=\"empty\">None.</p>");
+        return;
+    }
+    body.push_str("<table><tr>");
+    for header in headers {
+        let _ = write!(body, "<th>{header}</th>");
+    }
+    body.push_str("</tr>");
+    for row in rows {
+        body.push_str("<tr>");
+        for cell in row {
+            let _ = write!(body, "<td>{}</td>", escape(&cell));
+        }
+        body.push_str("</tr>");
+    }
+    body.push_str("</table>");
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title><style>\
+         body{{font-family:sans-serif;margin:2em;color:#222}}\
+         table{{border-collapse:collapse;margin-bottom:1em}}\
+         th,td{{text-align:left;padding:.3em .8em;border-bottom:1px solid #ddd}}\
+         .overview th{{font-weight:normal;color:#666}}.empty{{color:#666}}.error{{color:#b00}}\
+         </style></head><body><h1>{title}</h1>{body}</body></html>\n"
+    )
+}
+
+/// Sizes in binary units, as the CLI prints them.
+fn size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
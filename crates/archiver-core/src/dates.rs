@@ -0,0 +1,75 @@
+//! How dates are shown to the user: in UTC, local time or a named timezone (`timezone`), with
+//! a strftime pattern (`date_format`). Records keep their UTC timestamps; only the output
+//! changes.
+use crate::error::{Error, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Zone {
+    Utc,
+    Local,
+    Named(Tz),
+}
+
+/// Formats timestamps in the configured timezone and pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateFormatter {
+    zone: Zone,
+    pattern: String,
+}
+
+impl DateFormatter {
+    /// `timezone` is `UTC`, `local` or an IANA name such as `Europe/Madrid`; `pattern` uses
+    /// strftime syntax (e.g. `%d/%m/%Y`).
+    pub fn new(timezone: &str, pattern: &str) -> Result<Self> {
+        let zone = match timezone.to_ascii_lowercase().as_str() {
+            "utc" => Zone::Utc,
+            "local" => Zone::Local,
+            _ => Zone::Named(timezone.parse().map_err(|_| {
+                Error::Custom(format!(
+                    "Unknown timezone '{}', expected 'UTC', 'local' or a name such as 'Europe/Madrid'.",
+                    timezone
+                ))
+            })?),
+        };
+        if StrftimeItems::new(pattern).any(|item| item == Item::Error) {
+            return Err(Error::Custom(format!(
+                "Invalid date format '{}', expected a strftime pattern such as '%Y-%m-%d'.",
+                pattern
+            )));
+        }
+        Ok(Self {
+            zone,
+            pattern: pattern.to_string(),
+        })
+    }
+
+    /// The date of `at` in the configured timezone.
+    pub fn date(&self, at: DateTime<Utc>) -> String {
+        self.format(at, &self.pattern)
+    }
+
+    /// The date of `at` with the time and the timezone, e.g. `2024-05-01 18:30 CEST`.
+    pub fn date_time(&self, at: DateTime<Utc>) -> String {
+        self.format(at, &format!("{} %H:%M %Z", self.pattern))
+    }
+
+    fn format(&self, at: DateTime<Utc>, pattern: &str) -> String {
+        match self.zone {
+            Zone::Utc => at.format(pattern).to_string(),
+            Zone::Local => at.with_timezone(&Local).format(pattern).to_string(),
+            Zone::Named(tz) => at.with_timezone(&tz).format(pattern).to_string(),
+        }
+    }
+}
+
+impl Default for DateFormatter {
+    fn default() -> Self {
+        Self {
+            zone: Zone::Utc,
+            pattern: "%Y-%m-%d".to_string(),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Detection of project roots while scanning nested directory layouts, and of project types.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Files or directories whose presence marks a directory as a project root.
+const PROJECT_MARKERS: [&str; 10] = [
+    ".git",
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "Makefile",
+    "CMakeLists.txt",
+];
+
+/// The kind of multi-package workspace rooted at a directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceKind {
+    Cargo,
+    Pnpm,
+    NpmOrYarn,
+    Lerna,
+    Nx,
+    Go,
+}
+
+/// Detects whether `path` is the root of a workspace. Workspace members are never scanned on
+/// their own: the whole workspace is treated as one project rooted here.
+pub fn workspace_kind(path: &Path) -> Option<WorkspaceKind> {
+    let file_contains = |name: &str, needle: &str| {
+        fs::read_to_string(path.join(name)).is_ok_and(|content| content.contains(needle))
+    };
+
+    if file_contains("Cargo.toml", "[workspace]") {
+        Some(WorkspaceKind::Cargo)
+    } else if path.join("pnpm-workspace.yaml").is_file() {
+        Some(WorkspaceKind::Pnpm)
+    } else if file_contains("package.json", "\"workspaces\"") {
+        Some(WorkspaceKind::NpmOrYarn)
+    } else if path.join("lerna.json").is_file() {
+        Some(WorkspaceKind::Lerna)
+    } else if path.join("nx.json").is_file() {
+        Some(WorkspaceKind::Nx)
+    } else if path.join("go.work").is_file() {
+        Some(WorkspaceKind::Go)
+    } else {
+        None
+    }
+}
+
+/// Whether `path` is a project on its own: a workspace root, or a directory with a marker file.
+pub fn is_project_root(path: &Path) -> bool {
+    workspace_kind(path).is_some() || PROJECT_MARKERS.iter().any(|m| path.join(m).exists())
+}
+
+/// Whether `path` is only a grouping directory (e.g. `github.com/org`) to descend into while
+/// deep scanning: it is not a project root, contains nothing but directories, and some of them
+/// are project roots, or grouping directories themselves within `levels` levels below it. A
+/// project without a marker whose entries are all directories (e.g. `src/` and `docs/`) is not
+/// one.
+pub fn is_container(path: &Path, levels: usize) -> bool {
+    if levels == 0 || is_project_root(path) {
+        return false;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return false;
+    };
+    let mut dirs = Vec::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            return false;
+        }
+        dirs.push(entry.path());
+    }
+    dirs.iter()
+        .any(|dir| is_project_root(dir) || is_container(dir, levels - 1))
+}
+
+/// The ecosystem of a project, detected from its manifest files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectType {
+    Rust,
+    Go,
+    Java,
+    Python,
+    Ruby,
+    Php,
+    Node,
+}
+
+impl ProjectType {
+    /// Manifest files marking each type, checked in order: a Rust or Python project with a
+    /// `package.json` for its tooling is still a Rust or Python project.
+    const MARKERS: [(ProjectType, &'static [&'static str]); 7] = [
+        (Self::Rust, &["Cargo.toml"]),
+        (Self::Go, &["go.mod"]),
+        (Self::Java, &["pom.xml", "build.gradle", "build.gradle.kts"]),
+        (
+            Self::Python,
+            &["pyproject.toml", "setup.py", "requirements.txt"],
+        ),
+        (Self::Ruby, &["Gemfile"]),
+        (Self::Php, &["composer.json"]),
+        (Self::Node, &["package.json"]),
+    ];
+
+    /// Detects the type of the project at `path`, if it has a known manifest.
+    pub fn detect(path: &Path) -> Option<Self> {
+        Self::from_manifests(|file| path.join(file).is_file())
+    }
+
+    /// Detects the type of a project from whether it `has` each manifest file, e.g. when
+    /// the files are on another machine.
+    pub fn from_manifests(has: impl Fn(&str) -> bool) -> Option<Self> {
+        Self::MARKERS
+            .iter()
+            .find(|(_, files)| files.iter().any(|f| has(f)))
+            .map(|(kind, _)| *kind)
+    }
+
+    /// Every manifest file marking a type.
+    pub fn manifests() -> impl Iterator<Item = &'static str> {
+        Self::MARKERS
+            .iter()
+            .flat_map(|(_, files)| files.iter().copied())
+    }
+}
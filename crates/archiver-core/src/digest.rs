@@ -0,0 +1,52 @@
+//! Weekly or monthly digests of what the runs of a period did, built from the usage history.
+//!
+//! A digest covers the last complete period (the previous week, Monday to Sunday, or the
+//! previous calendar month), so a timer firing at the start of a period reports on the one
+//! that just ended.
+use crate::models::ArchivedRecord;
+use crate::trend::{Period, UsageSample};
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+/// What the runs of one period did.
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    /// First day of the period.
+    pub start: NaiveDate,
+    /// Last day of the period, inclusive.
+    pub end: NaiveDate,
+    pub runs: usize,
+    pub archived_projects: usize,
+    pub deleted_projects: usize,
+    pub freed_bytes: u64,
+    /// Disk usage at the last run of the period, if there was one.
+    pub last_sample: Option<UsageSample>,
+    /// Archived projects the deletion policy will delete within the next period.
+    pub upcoming_deletions: Vec<ArchivedRecord>,
+}
+
+/// Aggregates the samples of the last period complete on `today`. `upcoming_deletions` is
+/// passed through.
+pub fn build(
+    history: &[UsageSample],
+    period: Period,
+    today: NaiveDate,
+    upcoming_deletions: Vec<ArchivedRecord>,
+) -> Digest {
+    let start = period.previous_start(today);
+    let next = period.start_of(today);
+    let samples: Vec<_> = history
+        .iter()
+        .filter(|sample| (start..next).contains(&sample.at.date_naive()))
+        .collect();
+    Digest {
+        start,
+        end: next - Duration::days(1),
+        runs: samples.len(),
+        archived_projects: samples.iter().map(|s| s.archived_projects).sum(),
+        deleted_projects: samples.iter().map(|s| s.deleted_projects).sum(),
+        freed_bytes: samples.iter().map(|s| s.freed_bytes).sum(),
+        last_sample: samples.last().map(|sample| (*sample).clone()),
+        upcoming_deletions,
+    }
+}
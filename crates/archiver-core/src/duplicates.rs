@@ -0,0 +1,142 @@
+//! Detection of projects that are copies of each other across the projects directories.
+//!
+//! Git repositories are copies when their `origin` remotes point at the same repository, or,
+//! without a remote, when their checked-out trees are identical. Other directories are copies
+//! when their files are byte-for-byte identical.
+use crate::models::ScannedProject;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use tracing::debug;
+use walkdir::WalkDir;
+
+/// What the projects of a `DuplicateGroup` have in common.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "value")]
+pub enum DuplicateKey {
+    /// The same remote repository, normalized (e.g. `github.com/owner/repo`).
+    Remote(String),
+    /// The same content: a git tree id, or a digest of the files of a plain directory.
+    Content(String),
+}
+
+/// Projects that are copies of one another, most recently active first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub key: DuplicateKey,
+    pub projects: Vec<ScannedProject>,
+}
+
+/// Groups the projects that are copies of each other. Projects without a copy are left out.
+pub fn find(projects: &[ScannedProject]) -> Vec<DuplicateGroup> {
+    let mut groups: BTreeMap<DuplicateKey, Vec<ScannedProject>> = BTreeMap::new();
+    // Plain directories are only hashed when another one has the same number and size of files.
+    let mut by_shape: BTreeMap<(usize, u64), Vec<&ScannedProject>> = BTreeMap::new();
+
+    for project in projects {
+        match Repository::open(&project.path) {
+            Ok(repo) => {
+                if let Some(key) = repository_key(&repo) {
+                    groups.entry(key).or_default().push(project.clone());
+                }
+            }
+            Err(_) => {
+                let files = files_of(&project.path);
+                let size = files.iter().map(|(_, len)| len).sum();
+                by_shape
+                    .entry((files.len(), size))
+                    .or_default()
+                    .push(project);
+            }
+        }
+    }
+    for candidates in by_shape.into_values().filter(|c| c.len() > 1) {
+        for project in candidates {
+            debug!(path = %project.path.display(), "Hashing directory to compare its content.");
+            let key = DuplicateKey::Content(content_digest(&project.path));
+            groups.entry(key).or_default().push(project.clone());
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, projects)| projects.len() > 1)
+        .map(|(key, mut projects)| {
+            projects.sort_by_key(|project| Reverse(project.last_activity));
+            DuplicateGroup { key, projects }
+        })
+        .collect()
+}
+
+/// The normalized `origin` URL, or the id of the `HEAD` tree when there is no remote.
+fn repository_key(repo: &Repository) -> Option<DuplicateKey> {
+    if let Some(url) = repo
+        .find_remote("origin")
+        .ok()
+        .and_then(|remote| remote.url().map(normalize_remote_url))
+    {
+        return Some(DuplicateKey::Remote(url));
+    }
+    let tree = repo.head().ok()?.peel_to_tree().ok()?;
+    Some(DuplicateKey::Content(tree.id().to_string()))
+}
+
+/// Reduces the different spellings of a remote to `host/path`: scheme, user, port, `.git`
+/// suffix, trailing slash and case are dropped, and scp-like `host:path` becomes `host/path`.
+fn normalize_remote_url(url: &str) -> String {
+    let url = url.trim().to_ascii_lowercase();
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let host = authority.rsplit('@').next().unwrap_or(authority);
+            (host.split(':').next().unwrap_or(host), path)
+        }
+        // scp-like `user@host:path`, unless it is a local path.
+        None => match url.split_once(':') {
+            Some((authority, path)) if !authority.contains('/') => {
+                (authority.rsplit('@').next().unwrap_or(authority), path)
+            }
+            _ => ("", url.as_str()),
+        },
+    };
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", host, path.trim_start_matches('/'))
+    }
+}
+
+/// Relative paths and sizes of the files under `path`, sorted.
+fn files_of(path: &Path) -> Vec<(String, u64)> {
+    let mut files: Vec<(String, u64)> = WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let len = entry.metadata().ok()?.len();
+            let relative = entry.path().strip_prefix(path).ok()?;
+            Some((relative.to_string_lossy().into_owned(), len))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Digest of the relative paths and contents of the files under `path`.
+fn content_digest(path: &Path) -> String {
+    let mut hasher = DefaultHasher::new();
+    for (relative, _) in files_of(path) {
+        relative.hash(&mut hasher);
+        fs::read(path.join(&relative))
+            .unwrap_or_default()
+            .hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
@@ -1,3 +1,8 @@
+use crate::models::Manifest;
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::str::FromStr;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -14,14 +19,212 @@ pub enum Error {
     #[error("Serialization error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("Archive log '{}' could not be parsed: {source}", path.display())]
+    CorruptArchiveLog {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
     #[error("Home directory not found")]
     HomeDirNotFound,
 
+    #[error(
+        "Project '{key}' not found in archive log.{}",
+        did_you_mean(suggestions)
+    )]
+    ProjectNotFound {
+        key: String,
+        /// Archived names close to `key`, most similar first.
+        suggestions: Vec<String>,
+    },
+
+    #[error("Project name '{key}' is ambiguous, use its relative path instead: {}", candidates.join(", "))]
+    AmbiguousProject {
+        key: String,
+        candidates: Vec<String>,
+    },
+
+    #[error("Invalid project name '{0}'.")]
+    InvalidProjectName(String),
+
+    #[error("Archive path '{}' of project '{project}' is outside the archive directory.", path.display())]
+    OutsideArchive { project: String, path: PathBuf },
+
+    #[error("'{program}' failed: {message}")]
+    ForgeQuery { program: String, message: String },
+
+    #[error("Remote machine '{host}': {message}")]
+    Remote { host: String, message: String },
+
+    #[error("Restored project '{project}' does not match its archive manifest: expected {expected}, found {found}.")]
+    RestoreMismatch {
+        project: String,
+        expected: Manifest,
+        found: Manifest,
+    },
+
+    #[error("The plan is out of date: {}", changes.join("; "))]
+    StalePlan { changes: Vec<String> },
+
+    #[error("Run interrupted with {left} project(s) left; it can be resumed.")]
+    Interrupted { left: usize },
+
     #[error("{0}")]
     Custom(String),
-    
+
     #[error("Failed to walk directory: {0}")]
     WalkDir(#[from] walkdir::Error),
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, Error>;
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        _ => format!(
+            " Did you mean {}?",
+            suggestions
+                .iter()
+                .map(|name| format!("'{}'", name))
+                .collect::<Vec<_>>()
+                .join(" or ")
+        ),
+    }
+}
+
+/// Stable identifier of a class of errors, shown to users so they can look up its causes and
+/// fixes (`archiver explain A002`). Codes are never reused or renumbered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// A001: the filesystem refused access.
+    PermissionDenied,
+    /// A002: the archive log could not be parsed.
+    CorruptArchiveLog,
+    /// A003: the configuration is invalid.
+    InvalidSettings,
+    /// A004: a Git repository could not be read.
+    Git,
+    /// A005: the home directory could not be determined.
+    HomeDirNotFound,
+    /// A006: no archived project matches the given name.
+    ProjectNotFound,
+    /// A007: several archived projects match the given name.
+    AmbiguousProject,
+    /// A008: a name or archive path would escape its directory.
+    UnsafePath,
+    /// A009: the destination of a move already exists.
+    DestinationExists,
+    /// A010: a directory or file is missing.
+    PathNotFound,
+    /// A011: the `gh`/`glab` query failed.
+    ForgeQuery,
+    /// A012: a remote machine could not be reached, or a command on it failed.
+    Remote,
+    /// A013: a restored project has other files, or other content, than when it was archived.
+    RestoreMismatch,
+    /// A014: a saved plan no longer matches the projects and the archive.
+    StalePlan,
+    /// A015: the run was stopped by Ctrl-C before it was complete.
+    Interrupted,
+    /// A016: a state file other than the archive log (resume point, restore counts, plan,
+    /// cache, sidecar) could not be read or written as JSON.
+    CorruptStateFile,
+    /// A099: anything else.
+    Other,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 17] = [
+        Self::PermissionDenied,
+        Self::CorruptArchiveLog,
+        Self::InvalidSettings,
+        Self::Git,
+        Self::HomeDirNotFound,
+        Self::ProjectNotFound,
+        Self::AmbiguousProject,
+        Self::UnsafePath,
+        Self::DestinationExists,
+        Self::PathNotFound,
+        Self::ForgeQuery,
+        Self::Remote,
+        Self::RestoreMismatch,
+        Self::StalePlan,
+        Self::Interrupted,
+        Self::CorruptStateFile,
+        Self::Other,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PermissionDenied => "A001",
+            Self::CorruptArchiveLog => "A002",
+            Self::InvalidSettings => "A003",
+            Self::Git => "A004",
+            Self::HomeDirNotFound => "A005",
+            Self::ProjectNotFound => "A006",
+            Self::AmbiguousProject => "A007",
+            Self::UnsafePath => "A008",
+            Self::DestinationExists => "A009",
+            Self::PathNotFound => "A010",
+            Self::ForgeQuery => "A011",
+            Self::Remote => "A012",
+            Self::RestoreMismatch => "A013",
+            Self::StalePlan => "A014",
+            Self::Interrupted => "A015",
+            Self::CorruptStateFile => "A016",
+            Self::Other => "A099",
+        }
+    }
+
+    fn from_io_kind(kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem => Self::PermissionDenied,
+            ErrorKind::NotFound => Self::PathNotFound,
+            ErrorKind::AlreadyExists | ErrorKind::DirectoryNotEmpty => Self::DestinationExists,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|code| code.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::Custom(format!("Unknown error code '{}'.", s)))
+    }
+}
+
+impl Error {
+    /// The stable code of this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Io(e) => ErrorCode::from_io_kind(e.kind()),
+            Self::WalkDir(e) => e
+                .io_error()
+                .map_or(ErrorCode::Other, |io| ErrorCode::from_io_kind(io.kind())),
+            Self::Git(_) => ErrorCode::Git,
+            Self::Config(_) => ErrorCode::InvalidSettings,
+            Self::Json(_) => ErrorCode::CorruptStateFile,
+            Self::CorruptArchiveLog { .. } => ErrorCode::CorruptArchiveLog,
+            Self::HomeDirNotFound => ErrorCode::HomeDirNotFound,
+            Self::ProjectNotFound { .. } => ErrorCode::ProjectNotFound,
+            Self::AmbiguousProject { .. } => ErrorCode::AmbiguousProject,
+            Self::InvalidProjectName(_) | Self::OutsideArchive { .. } => ErrorCode::UnsafePath,
+            Self::ForgeQuery { .. } => ErrorCode::ForgeQuery,
+            Self::Remote { .. } => ErrorCode::Remote,
+            Self::RestoreMismatch { .. } => ErrorCode::RestoreMismatch,
+            Self::StalePlan { .. } => ErrorCode::StalePlan,
+            Self::Interrupted { .. } => ErrorCode::Interrupted,
+            Self::Custom(_) => ErrorCode::Other,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Integration with code forges (GitHub, GitLab) to find repositories with reviews in flight.
+//!
+//! Queries go through the official `gh` and `glab` command-line tools, so authentication is
+//! whatever the user already configured for them.
+use crate::error::{Error, Result};
+use git2::Repository;
+use std::path::Path;
+use std::process::Command;
+use tracing::debug;
+
+/// A code forge hosting a repository's `origin` remote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+}
+
+impl Forge {
+    /// Detects the forge of the `origin` remote. `gitlab_hosts` lists self-hosted GitLab
+    /// instances in addition to gitlab.com.
+    pub fn detect(path: &Path, gitlab_hosts: &[String]) -> Option<Self> {
+        let repo = Repository::open(path).ok()?;
+        let remote = repo.find_remote("origin").ok()?;
+        let url = remote.url()?.to_ascii_lowercase();
+
+        if url.contains("github.com") {
+            Some(Self::GitHub)
+        } else if url.contains("gitlab.com")
+            || gitlab_hosts
+                .iter()
+                .any(|host| url.contains(&host.to_ascii_lowercase()))
+        {
+            Some(Self::GitLab)
+        } else {
+            None
+        }
+    }
+
+    /// The CLI program and the argument lists listing open PRs/MRs authored by, or awaiting
+    /// review from, the authenticated user.
+    fn queries(self) -> (&'static str, [&'static [&'static str]; 2]) {
+        match self {
+            Self::GitHub => (
+                "gh",
+                [
+                    &[
+                        "pr", "list", "--state", "open", "--author", "@me", "--json", "number",
+                    ],
+                    &[
+                        "pr",
+                        "list",
+                        "--state",
+                        "open",
+                        "--search",
+                        "review-requested:@me",
+                        "--json",
+                        "number",
+                    ],
+                ],
+            ),
+            Self::GitLab => (
+                "glab",
+                [
+                    &["mr", "list", "--author=@me", "--output", "json"],
+                    &["mr", "list", "--reviewer=@me", "--output", "json"],
+                ],
+            ),
+        }
+    }
+}
+
+/// Counts the open pull/merge requests of the repository at `path` that the user authored or
+/// was asked to review.
+pub fn open_review_count(path: &Path, forge: Forge) -> Result<usize> {
+    let (program, queries) = forge.queries();
+    let mut count = 0;
+    for args in queries {
+        debug!(%program, ?args, path = %path.display(), "Querying open reviews.");
+        let output = Command::new(program)
+            .args(args)
+            .current_dir(path)
+            .output()
+            .map_err(|e| Error::ForgeQuery {
+                program: program.to_string(),
+                message: e.to_string(),
+            })?;
+        if !output.status.success() {
+            return Err(Error::ForgeQuery {
+                program: program.to_string(),
+                message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        let items: Vec<serde_json::Value> =
+            serde_json::from_slice(&output.stdout).map_err(|e| Error::ForgeQuery {
+                program: program.to_string(),
+                message: format!("unexpected output: {}", e),
+            })?;
+        count += items.len();
+    }
+    Ok(count)
+}
@@ -0,0 +1,122 @@
+//! Optional trimming of a repository's git history before it is archived.
+//!
+//! For large repositories the history often outweighs the working tree. Trimming marks the
+//! oldest commits to keep as shallow boundaries (as `git clone --depth` does) and prunes
+//! everything behind them, so `git fetch --unshallow` brings the history back after a
+//! restore. Only repositories whose branches are all pushed are trimmed, since the remote
+//! then still has the full history.
+use crate::config::HistoryTrim;
+use crate::error::{Error, Result};
+use chrono::Utc;
+use git2::{BranchType, Oid, Repository, Sort};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tracing::{debug, info};
+
+/// Trims the history of the repository at `path` to the commits `keep` selects, plus the tip
+/// of every ref. Returns how many commits were dropped; directories that are not repositories,
+/// and repositories with unpushed work, are left alone.
+pub fn trim(path: &Path, keep: &HistoryTrim) -> Result<usize> {
+    if keep.keep_commits.is_none() && keep.keep_days.is_none() {
+        return Ok(0);
+    }
+    let Ok(repo) = Repository::open(path) else {
+        return Ok(0);
+    };
+    if !is_fully_pushed(&repo)? {
+        info!(path = %path.display(), "Not trimming history: some commits exist only locally.");
+        return Ok(0);
+    }
+
+    let mut walk = repo.revwalk()?;
+    walk.push_glob("*")?;
+    walk.set_sorting(Sort::TIME)?;
+    let cutoff = keep
+        .keep_days
+        .map(|days| Utc::now().timestamp() - days as i64 * 86_400);
+    let mut kept = HashSet::new();
+    let mut total = 0;
+    for (index, oid) in walk.enumerate() {
+        let oid = oid?;
+        total += 1;
+        let recent = keep.keep_commits.is_some_and(|count| index < count)
+            || cutoff.is_some_and(|cutoff| {
+                repo.find_commit(oid)
+                    .is_ok_and(|c| c.time().seconds() >= cutoff)
+            });
+        if recent {
+            kept.insert(oid);
+        }
+    }
+    // Every ref must still point at a commit that exists.
+    for reference in repo.references()? {
+        if let Ok(commit) = reference?.peel_to_commit() {
+            kept.insert(commit.id());
+        }
+    }
+
+    let mut boundary: Vec<Oid> = kept
+        .iter()
+        .copied()
+        .filter(|oid| {
+            repo.find_commit(*oid)
+                .is_ok_and(|c| c.parent_ids().any(|parent| !kept.contains(&parent)))
+        })
+        .collect();
+    if boundary.is_empty() {
+        return Ok(0);
+    }
+    boundary.sort();
+
+    let shallow_path = repo.path().join("shallow");
+    let mut shallow: Vec<String> = fs::read_to_string(&shallow_path)
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .chain(boundary.iter().map(Oid::to_string))
+        .collect();
+    shallow.sort();
+    shallow.dedup();
+    debug!(path = %shallow_path.display(), boundaries = boundary.len(), "Writing shallow boundaries.");
+    fs::write(&shallow_path, shallow.join("\n") + "\n")?;
+
+    git(path, &["reflog", "expire", "--expire=now", "--all"])?;
+    git(path, &["gc", "--prune=now", "--quiet"])?;
+    Ok(total - kept.len())
+}
+
+/// Whether the repository has a remote and every local branch is contained in its upstream.
+fn is_fully_pushed(repo: &Repository) -> Result<bool> {
+    if repo.remotes()?.is_empty() {
+        return Ok(false);
+    }
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let Ok(upstream) = branch.upstream() else {
+            return Ok(false);
+        };
+        let (Some(local), Some(remote)) = (branch.get().target(), upstream.get().target()) else {
+            return Ok(false);
+        };
+        if repo.graph_ahead_behind(local, remote)?.0 > 0 {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Runs a `git` command in `path`, failing with its error output.
+fn git(path: &Path, args: &[&str]) -> Result<()> {
+    debug!(?args, path = %path.display(), "Running git.");
+    let output = Command::new("git").args(args).current_dir(path).output()?;
+    if !output.status.success() {
+        return Err(Error::Custom(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Stopping a run at a safe point when the user presses Ctrl-C.
+//!
+//! With the handler installed, SIGINT only raises a flag: the project being moved is finished,
+//! the archive log is updated with everything done so far, and the projects left are saved as
+//! a resume point in the index directory. A second Ctrl-C stops the process right away.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Name of the file holding the projects an interrupted run had left, in the index directory.
+pub const RESUME_FILE_NAME: &str = "resume.json";
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_sigint(_: libc::c_int) {
+    REQUESTED.store(true, Ordering::SeqCst);
+    // SAFETY: `signal` is async-signal-safe; the next SIGINT gets the default behavior.
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_DFL);
+    }
+}
+
+/// Makes SIGINT request a stop at the next safe point instead of killing the process.
+pub fn install_handler() {
+    let handler = on_sigint as extern "C" fn(libc::c_int);
+    // SAFETY: the handler only touches an atomic and calls `signal`, both async-signal-safe.
+    unsafe {
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
+
+/// Requests a stop at the next safe point, as SIGINT does once the handler is installed.
+pub fn request() {
+    REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Whether a stop was requested.
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Forgets a stop request, once the run it stopped has ended.
+pub fn clear() {
+    REQUESTED.store(false, Ordering::SeqCst);
+}
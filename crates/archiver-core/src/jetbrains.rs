@@ -0,0 +1,114 @@
+//! Integration with the JetBrains IDE family (IntelliJ IDEA, CLion, PyCharm, ...).
+//!
+//! Every IDE keeps a `recentProjects.xml` in its options directory. Once a project is moved
+//! into the archive, its entry points to a path that no longer exists, so we drop it.
+use crate::error::Result;
+use directories::BaseDirs;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+const RECENT_PROJECT_FILES: [&str; 2] = ["recentProjects.xml", "recentSolutions.xml"];
+const USER_HOME_MACRO: &str = "$USER_HOME$";
+
+/// Returns every recent-projects file found under the JetBrains configuration directory.
+pub fn recent_project_files() -> Vec<PathBuf> {
+    let Some(base_dirs) = BaseDirs::new() else {
+        return Vec::new();
+    };
+    let jetbrains_dir = base_dirs.config_dir().join("JetBrains");
+    let Ok(entries) = fs::read_dir(&jetbrains_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join("options"))
+        .flat_map(|options_dir| RECENT_PROJECT_FILES.map(|name| options_dir.join(name)))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Removes `project_path` from the recent projects of every installed JetBrains IDE.
+/// Failures are logged and never abort the caller, since this is purely cosmetic.
+pub fn forget_project(project_path: &Path) {
+    for file in recent_project_files() {
+        match remove_recent_project(&file, project_path) {
+            Ok(true) => {
+                debug!(file = %file.display(), "Removed project from JetBrains recent projects.")
+            }
+            Ok(false) => {}
+            Err(e) => {
+                warn!(file = %file.display(), error = %e, "Could not update JetBrains recent projects.")
+            }
+        }
+    }
+}
+
+/// Removes every reference to `project_path` from a single recent-projects XML file.
+/// Returns `true` if the file was modified.
+pub fn remove_recent_project(file: &Path, project_path: &Path) -> Result<bool> {
+    let content = fs::read_to_string(file)?;
+    let keys = xml_keys_for(project_path);
+
+    let mut output = Vec::new();
+    let mut skipping_depth = 0usize;
+    let mut changed = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if skipping_depth > 0 {
+            if trimmed.starts_with("<entry") && !trimmed.ends_with("/>") {
+                skipping_depth += 1;
+            } else if trimmed.starts_with("</entry>") {
+                skipping_depth -= 1;
+            }
+            continue;
+        }
+
+        let references_project = keys.iter().any(|key| {
+            trimmed.starts_with(&format!("<entry key=\"{}\"", key))
+                || trimmed.starts_with(&format!("<option value=\"{}\"", key))
+        });
+        if !references_project {
+            output.push(line);
+            continue;
+        }
+
+        changed = true;
+        if trimmed.starts_with("<entry") && !trimmed.ends_with("/>") {
+            skipping_depth = 1;
+        }
+    }
+
+    if changed {
+        let mut new_content = output.join("\n");
+        if content.ends_with('\n') {
+            new_content.push('\n');
+        }
+        fs::write(file, new_content)?;
+    }
+    Ok(changed)
+}
+
+/// The spellings a project path can take inside the XML: absolute, or relative to `$USER_HOME$`.
+fn xml_keys_for(project_path: &Path) -> Vec<String> {
+    let absolute = project_path.to_string_lossy().into_owned();
+    let mut keys = vec![escape_xml(&absolute)];
+
+    if let Some(base_dirs) = BaseDirs::new() {
+        if let Ok(relative) = project_path.strip_prefix(base_dirs.home_dir()) {
+            let macro_path = format!("{}/{}", USER_HOME_MACRO, relative.to_string_lossy());
+            keys.push(escape_xml(&macro_path));
+        }
+    }
+    keys
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,184 @@
+//! Append-only archive log (`index_format = "jsonl"`).
+//!
+//! Each line of `archive.jsonl` is one change: a record added, or a tombstone removing the
+//! record with the same name and archive date. Archiving appends lines instead of rewriting the
+//! whole log, so concurrent runs keep each other's records, a crash leaves at worst an
+//! incomplete last line (skipped when reading), and the file keeps the history of the archive.
+//! Writers only append what they changed themselves: the records they added and tombstones
+//! for the records they removed. `compact` rewrites it with only the current records, holding
+//! the lock appends take (`archive.jsonl.lock`) so no line appended meanwhile is dropped.
+use crate::error::{Error, Result};
+use crate::models::{ArchivedRecord, ProjectName};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// File name of the log in the index directory.
+pub const FILE_NAME: &str = "archive.jsonl";
+
+/// One line of the log.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Entry {
+    Add {
+        record: Box<ArchivedRecord>,
+    },
+    Remove {
+        name: ProjectName,
+        archived_at: DateTime<Utc>,
+        removed_at: DateTime<Utc>,
+    },
+}
+
+fn same_record(a: &ArchivedRecord, name: &ProjectName, archived_at: DateTime<Utc>) -> bool {
+    a.name == *name && a.archived_at == archived_at
+}
+
+/// Replays the log at `path` into the current records, in the order they were added.
+pub fn read(path: &Path) -> Result<Vec<ArchivedRecord>> {
+    let content = fs::read_to_string(path)?;
+    let mut records: Vec<ArchivedRecord> = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(Entry::Add { record }) => {
+                match records
+                    .iter_mut()
+                    .find(|r| same_record(r, &record.name, record.archived_at))
+                {
+                    Some(existing) => *existing = *record,
+                    None => records.push(*record),
+                }
+            }
+            Ok(Entry::Remove {
+                name, archived_at, ..
+            }) => records.retain(|r| !same_record(r, &name, archived_at)),
+            // A line cut short by a crash; the rest of the log is still valid.
+            Err(e) if e.is_eof() => {
+                warn!(path = %path.display(), line = number + 1, "Skipping incomplete archive log line.");
+            }
+            Err(source) => {
+                return Err(Error::CorruptArchiveLog {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Number of lines of the log, current records and history included.
+fn line_count(path: &Path) -> Result<usize> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count())
+}
+
+/// Appends `records` to the log at `path`, creating it if needed.
+pub fn append_records(path: &Path, records: &[ArchivedRecord]) -> Result<()> {
+    let entries: Vec<Entry> = records
+        .iter()
+        .map(|record| Entry::Add {
+            record: Box::new(record.clone()),
+        })
+        .collect();
+    append(path, &entries)
+}
+
+/// Appends a tombstone for each of `records`. Records other processes appended are kept,
+/// whether or not the caller has seen them.
+pub fn remove_records(path: &Path, records: &[ArchivedRecord]) -> Result<()> {
+    let removed_at = Utc::now();
+    let entries: Vec<Entry> = records
+        .iter()
+        .map(|record| Entry::Remove {
+            name: record.name.clone(),
+            archived_at: record.archived_at,
+            removed_at,
+        })
+        .collect();
+    append(path, &entries)
+}
+
+/// Replaces the log at `path` with one line per record, dropping the history.
+pub fn write(path: &Path, records: &[ArchivedRecord]) -> Result<()> {
+    let mut content = String::new();
+    for record in records {
+        content.push_str(&serde_json::to_string(&Entry::Add {
+            record: Box::new(record.clone()),
+        })?);
+        content.push('\n');
+    }
+    // Write next to the log and rename, so a crash never leaves it half written.
+    let temp_path = path.with_extension("jsonl.tmp");
+    fs::write(&temp_path, content)?;
+    File::open(&temp_path)?.sync_all()?;
+    fs::rename(&temp_path, path)?;
+    debug!(path = %path.display(), count = records.len(), "Wrote archive log.");
+    Ok(())
+}
+
+/// Rewrites the log at `path` with only its current records. Returns the number of lines
+/// dropped.
+pub fn compact(path: &Path) -> Result<usize> {
+    let _lock = lock(path)?;
+    let before = line_count(path)?;
+    let records = read(path)?;
+    write(path, &records)?;
+    Ok(before.saturating_sub(records.len()))
+}
+
+fn append(path: &Path, entries: &[Entry]) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut buffer = String::new();
+    for entry in entries {
+        buffer.push_str(&serde_json::to_string(entry)?);
+        buffer.push('\n');
+    }
+    let _lock = lock(path)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+    // Start on a new line after an incomplete one, so it stays the only line lost.
+    if !ends_with_newline(&mut file)? {
+        buffer.insert(0, '\n');
+    }
+    // A single write per call, so lines appended by concurrent runs do not interleave.
+    file.write_all(buffer.as_bytes())?;
+    file.sync_data()?;
+    debug!(path = %path.display(), count = entries.len(), "Appended to archive log.");
+    Ok(())
+}
+
+/// Takes the lock of the log at `path`, held until the returned file is dropped. It is a file
+/// of its own, as `compact` replaces the log.
+fn lock(path: &Path) -> Result<File> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("jsonl.lock"))?;
+    file.lock()?;
+    Ok(file)
+}
+
+fn ends_with_newline(file: &mut File) -> Result<bool> {
+    if file.metadata()?.len() == 0 {
+        return Ok(true);
+    }
+    file.seek(SeekFrom::End(-1))?;
+    let mut last = [0; 1];
+    file.read_exact(&mut last)?;
+    Ok(last[0] == b'\n')
+}
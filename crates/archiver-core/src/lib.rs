@@ -1,24 +1,81 @@
+pub mod artifacts;
+pub mod cleanup;
 pub mod config;
+pub mod containers;
+pub mod credentials;
+pub mod dashboard;
+pub mod dates;
+pub mod detect;
+pub mod digest;
+pub mod duplicates;
 pub mod error;
+pub mod forge;
+pub mod history;
+pub mod interrupt;
+pub mod jetbrains;
+pub mod jobs;
+pub mod journal;
+pub mod migrate;
 pub mod models;
+pub mod names;
+pub mod plan;
+pub mod policy;
+pub mod preflight;
+pub mod probe;
+pub mod prompt;
+pub mod remote;
+pub mod restores;
+pub mod sidecar;
+pub mod simulate;
+pub mod stats;
+pub mod summary;
+pub mod sync;
+pub mod team;
+#[cfg(feature = "test-utils")]
+pub mod testing;
+pub mod tree;
+pub mod trend;
 
 // Publicly re-export the main types for a clean external API.
-pub use config::Settings;
-pub use error::{Error, Result};
-pub use models::{ArchivedRecord, ScannedProject};
+pub use artifacts::ArtifactRecord;
+pub use config::{
+    ArchiveLayout, ArchiveRoute, Exclusion, HistoryTrim, IndexFormat, LogFormat, Settings,
+};
+pub use dates::DateFormatter;
+pub use detect::ProjectType;
+pub use digest::Digest;
+pub use duplicates::{DuplicateGroup, DuplicateKey};
+pub use error::{Error, ErrorCode, Result};
+pub use models::{
+    ArchivedRecord, Manifest, ProjectName, RestoreFilter, RestorePreview, ScanLimit,
+    ScannedProject, UpcomingCandidate,
+};
+pub use plan::SavedPlan;
+pub use policy::DeletionPolicy;
+pub use probe::ActivityProbe;
+pub use remote::{RemoteHost, RemoteProject};
+pub use restores::RestoreCount;
+pub use stats::CodeStats;
+pub use summary::{Outcome, ProjectOutcome, RunSummary};
+pub use team::TeamPolicy;
+pub use tree::TreeNode;
+pub use trend::UsageSample;
 
 use chrono::{DateTime, Duration, Utc};
+use cleanup::KeepList;
+use containers::RunningContainer;
 use git2::Repository;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::{debug, info, instrument, span, warn, Level};
+use trend::Period;
 use walkdir::WalkDir;
 
 /// Represents a planned action during a dry run.
 #[derive(Debug, PartialEq)]
 pub enum ActionPlan {
     Archive {
-        project_name: String,
+        project_name: ProjectName,
         path: std::path::PathBuf,
     },
     Nothing,
@@ -33,6 +90,7 @@ impl Archiver {
     const ARCHIVE_LOG_FILE: &'static str = "archive.json";
 
     pub fn new(settings: Settings) -> Self {
+        credentials::init_timeouts(settings.network_timeout());
         Self { settings }
     }
 
@@ -40,13 +98,187 @@ impl Archiver {
         &self.settings
     }
 
-    #[instrument(skip(self), name = "archive_process", fields(dry_run = %dry_run))]
     pub fn run_archive_process(&self, dry_run: bool) -> Result<Vec<ActionPlan>> {
+        self.run_tagged_archive_process(dry_run, &[])
+    }
+
+    /// Runs the archive process, labelling every record archived in this run with `tags`.
+    pub fn run_tagged_archive_process(
+        &self,
+        dry_run: bool,
+        tags: &[String],
+    ) -> Result<Vec<ActionPlan>> {
+        self.archive_inactive(dry_run, tags, &mut RunSummary::start(dry_run))
+    }
+
+    /// Runs a full pass, archiving inactive projects and then deleting expired archives when
+    /// automatic deletion is enabled. The summary is returned even when the run fails, with
+    /// the error recorded in it.
+    pub fn run_with_summary(&self, dry_run: bool, tags: &[String]) -> RunSummary {
+        let mut summary = RunSummary::start(dry_run);
+        let result = self
+            .archive_inactive(dry_run, tags, &mut summary)
+            .and_then(|_| self.delete_expired(dry_run, &mut summary));
+        if !dry_run && result.is_ok() {
+            // A complete run covers whatever an interrupted one had left.
+            self.clear_resume_point();
+        }
+        summary.finish(result.err().map(|e| e.to_string()));
+        summary
+    }
+
+    /// What an interrupted run had left to do, if a run was interrupted since the last
+    /// complete one.
+    pub fn resume_point(&self) -> Result<Option<SavedPlan>> {
+        let path = self.resume_point_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        SavedPlan::read(&path).map(Some)
+    }
+
+    /// Continues an interrupted run with the projects it had left, refusing like
+    /// [`Archiver::apply_plan`] if they changed since. `None` when no run was interrupted.
+    pub fn resume_run(&self) -> Result<Option<RunSummary>> {
+        let Some(plan) = self.resume_point()? else {
+            return Ok(None);
+        };
+        info!(
+            archive = plan.archive.len(),
+            delete = plan.delete.len(),
+            "Resuming interrupted run."
+        );
+        let summary = self.apply_plan(&plan)?;
+        if summary.error.is_none() {
+            self.clear_resume_point();
+        }
+        Ok(Some(summary))
+    }
+
+    fn resume_point_path(&self) -> PathBuf {
+        self.settings.index_dir().join(interrupt::RESUME_FILE_NAME)
+    }
+
+    /// Saves the projects an interrupted run had left, for [`Archiver::resume_run`].
+    fn save_resume_point(
+        &self,
+        archive: &[ScannedProject],
+        delete: &[ArchivedRecord],
+        tags: &[String],
+    ) -> Result<()> {
+        let path = self.resume_point_path();
+        warn!(path = %path.display(), left = archive.len() + delete.len(), "Run interrupted, saving a resume point.");
+        fs::create_dir_all(self.settings.index_dir())?;
+        SavedPlan {
+            created_at: Utc::now(),
+            archive: archive.to_vec(),
+            delete: delete.to_vec(),
+            tags: tags.to_vec(),
+        }
+        .write(&path)
+    }
+
+    fn clear_resume_point(&self) {
+        let path = self.resume_point_path();
+        if let Err(e) = fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!(path = %path.display(), error = %e, "Could not remove the resume point.");
+            }
+        }
+    }
+
+    /// Plans a full pass without changing anything, to be saved, reviewed and applied later
+    /// with [`Archiver::apply_plan`].
+    pub fn plan(&self) -> Result<SavedPlan> {
+        let projects = self.scan_projects()?;
+        let archive = self.archivable_projects(projects, &mut RunSummary::start(true));
+        Ok(SavedPlan {
+            created_at: Utc::now(),
+            archive,
+            delete: self.expired_records()?,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Counts the inactive projects a run would archive now, as `prompt-status` shows them.
+    pub fn pending_candidates(&self) -> Result<usize> {
+        let projects = self.scan_projects()?;
+        Ok(self
+            .archivable_projects(projects, &mut RunSummary::start(true))
+            .len())
+    }
+
+    /// What changed since `plan` was made that makes it unsafe to apply: projects gone, with
+    /// new activity or protected since, and archives no longer there or no longer due for
+    /// deletion. Empty when the plan can be applied as is.
+    pub fn plan_changes(&self, plan: &SavedPlan) -> Result<Vec<String>> {
+        let mut changes = Vec::new();
+        let containers = self.running_containers();
+        for project in &plan.archive {
+            if !project.path.is_dir() {
+                changes.push(format!("'{}' no longer exists", project.name));
+                continue;
+            }
+            match self.get_last_activity(&project.path) {
+                Ok((last_activity, _)) if last_activity == project.last_activity => {}
+                _ => {
+                    changes.push(format!("'{}' has new activity", project.name));
+                    continue;
+                }
+            }
+            if let Some(reason) = self.protection_reason(project, &containers) {
+                changes.push(format!("'{}' is now {}", project.name, reason));
+            }
+        }
+
+        let policy = self.deletion_policy();
+        let now = Utc::now();
+        let records = self.get_archive_records()?;
+        for planned in &plan.delete {
+            match records
+                .iter()
+                .find(|record| record.archive_path == planned.archive_path)
+            {
+                None => changes.push(format!("'{}' is no longer archived", planned.label())),
+                Some(record) if !policy.allows_deletion(record, now) => changes.push(format!(
+                    "'{}' is no longer due for deletion",
+                    planned.label()
+                )),
+                Some(_) => {}
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Applies a plan made by [`Archiver::plan`]: archives and deletes exactly the projects it
+    /// lists. Fails without changing anything if the plan is out of date.
+    pub fn apply_plan(&self, plan: &SavedPlan) -> Result<RunSummary> {
+        let changes = self.plan_changes(plan)?;
+        if !changes.is_empty() {
+            return Err(Error::StalePlan { changes });
+        }
+        let mut summary = RunSummary::start(false);
+        summary.scanned = plan.archive.len();
+        let result = self
+            .archive_projects(&plan.archive, false, &plan.tags, &mut summary)
+            .and_then(|_| self.delete_records(&plan.delete, false, &mut summary));
+        summary.finish(result.err().map(|e| e.to_string()));
+        Ok(summary)
+    }
+
+    #[instrument(skip(self, summary), name = "archive_process", fields(dry_run = %dry_run))]
+    fn archive_inactive(
+        &self,
+        dry_run: bool,
+        tags: &[String],
+        summary: &mut RunSummary,
+    ) -> Result<Vec<ActionPlan>> {
         info!("Starting archive process...");
         let projects = self.scan_projects()?;
         info!(project_count = projects.len(), "Scan complete.");
+        summary.scanned = projects.len();
 
-        let inactive_projects = self.filter_inactive_projects(projects);
+        let inactive_projects = self.archivable_projects(projects, summary);
         if inactive_projects.is_empty() {
             info!("No inactive projects to archive. Process finished.");
             return Ok(vec![ActionPlan::Nothing]);
@@ -55,27 +287,128 @@ impl Archiver {
             count = inactive_projects.len(),
             "Found inactive projects to archive."
         );
+        self.archive_projects(&inactive_projects, dry_run, tags, summary)
+    }
 
+    /// The inactive projects among `projects` that nothing protects. Protected ones are
+    /// recorded as skipped in `summary`.
+    fn archivable_projects(
+        &self,
+        projects: Vec<ScannedProject>,
+        summary: &mut RunSummary,
+    ) -> Vec<ScannedProject> {
+        let containers = self.running_containers();
+        self.filter_inactive_projects(projects)
+            .into_iter()
+            .filter(
+                |project| match self.protection_reason(project, &containers) {
+                    Some(reason) => {
+                        info!(project_name = %project.name, %reason, "Skipping inactive project.");
+                        summary.record(ProjectOutcome {
+                            name: project.name.to_string(),
+                            path: project.path.clone(),
+                            outcome: Outcome::Skipped,
+                            bytes: None,
+                            reason: Some(reason),
+                            cleaned: Vec::new(),
+                            cleaned_bytes: None,
+                        });
+                        false
+                    }
+                    None => true,
+                },
+            )
+            .collect()
+    }
+
+    /// Archives `projects`, or only records what would be done on a dry run.
+    fn archive_projects(
+        &self,
+        projects: &[ScannedProject],
+        dry_run: bool,
+        tags: &[String],
+        summary: &mut RunSummary,
+    ) -> Result<Vec<ActionPlan>> {
         let mut plan = vec![];
         let mut new_records = vec![];
+        let mut failure = None;
 
-        for project in &inactive_projects {
+        for (index, project) in projects.iter().enumerate() {
+            if !dry_run && interrupt::requested() {
+                let left = &projects[index..];
+                // Stop before the next project; what was moved is still logged below.
+                failure = Some(match self.save_resume_point(left, &[], tags) {
+                    Ok(()) => Error::Interrupted { left: left.len() },
+                    Err(e) => e,
+                });
+                break;
+            }
             plan.push(ActionPlan::Archive {
                 project_name: project.name.clone(),
                 path: project.path.clone(),
             });
+            let cleanup = self.cleanup_targets(&project.path);
+            let keep = self.keep_list(&project.path);
+            let cleaned_bytes: u64 = cleanup
+                .iter()
+                .map(|folder| {
+                    let kept: u64 = keep
+                        .protected_in(&project.path, folder)
+                        .iter()
+                        .map(|path| dir_size(path))
+                        .sum();
+                    dir_size(folder).saturating_sub(kept)
+                })
+                .sum();
+            let mut outcome = ProjectOutcome {
+                name: project.name.to_string(),
+                path: project.path.clone(),
+                outcome: Outcome::WouldArchive,
+                bytes: Some(dir_size(&project.path).saturating_sub(cleaned_bytes)),
+                reason: None,
+                cleaned: cleanup
+                    .iter()
+                    .filter_map(|folder| folder.strip_prefix(&project.path).ok())
+                    .map(Path::to_path_buf)
+                    .collect(),
+                cleaned_bytes: (!cleanup.is_empty()).then_some(cleaned_bytes),
+            };
             if !dry_run {
                 let project_span =
                     span!(Level::INFO, "archive_project", project_name = %project.name);
                 let _enter = project_span.enter();
                 info!("Archiving project...");
-                let record = self.archive_project(project)?;
-                new_records.push(record);
+                let archived = Self::clean_project(&project.path, &cleanup, &keep).and_then(|_| {
+                    let trimmed_commits = self.trim_history(&project.path);
+                    self.archive_project(project, tags, trimmed_commits)
+                });
+                match archived {
+                    Ok(record) => {
+                        if self.settings.clean_jetbrains_recent_projects {
+                            jetbrains::forget_project(&project.path);
+                        }
+                        new_records.push(record);
+                        outcome.outcome = Outcome::Archived;
+                    }
+                    Err(e) => {
+                        outcome.outcome = Outcome::Failed;
+                        outcome.bytes = None;
+                        outcome.reason = Some(e.to_string());
+                        summary.record(outcome);
+                        failure = Some(e);
+                        break;
+                    }
+                }
             }
+            summary.record(outcome);
         }
 
         if !dry_run {
+            // Log what was already moved, even if a later project failed.
             self.append_to_archive_log(&new_records)?;
+            if let Some(e) = failure {
+                return Err(e);
+            }
             info!("Archive process finished successfully.");
         } else {
             info!("Dry run complete. No files were changed.");
@@ -84,29 +417,137 @@ impl Archiver {
         Ok(plan)
     }
 
+    /// Finds a remote machine of the settings by name.
+    pub fn remote(&self, name: &str) -> Result<&RemoteHost> {
+        self.settings
+            .remotes
+            .iter()
+            .find(|remote| remote.name == name)
+            .ok_or_else(|| Error::Remote {
+                host: name.to_string(),
+                message: "not listed in the 'remotes' setting".to_string(),
+            })
+    }
+
+    /// Fetches `remote` of the project at `path`, authenticating as `git` would (SSH agent,
+    /// default SSH keys, credential helpers) and giving up after `network_timeout_secs`.
+    pub fn fetch(&self, path: &Path, remote: &str) -> Result<()> {
+        credentials::fetch(path, remote, self.settings.network_timeout())
+    }
+
+    /// Lists the projects of a remote machine with their last activity and type, leaving out
+    /// the excluded ones as the local scan does.
+    pub fn scan_remote(&self, remote: &RemoteHost) -> Result<Vec<RemoteProject>> {
+        let mut projects = remote.scan(&self.settings.ssh_program)?;
+        projects.retain(|remote_project| {
+            let name = remote_project.project.name.as_str();
+            let excluded = self.settings.is_excluded(name);
+            if excluded {
+                debug!(name, "Skipping excluded remote project.");
+            }
+            !excluded
+        });
+        Ok(projects)
+    }
+
+    /// Applies the archiving policy (the inactivity thresholds and exclusions) to the projects
+    /// of a remote machine, moving them into its own archive directory. The summary is returned
+    /// even when the run fails, with the error recorded in it.
+    pub fn run_remote(&self, remote: &RemoteHost, dry_run: bool, tags: &[String]) -> RunSummary {
+        let mut summary = RunSummary::start(dry_run);
+        let result = self.archive_remote(remote, dry_run, tags, &mut summary);
+        summary.finish(result.err().map(|e| e.to_string()));
+        summary
+    }
+
+    #[instrument(skip(self, remote, summary), fields(host = %remote.host))]
+    fn archive_remote(
+        &self,
+        remote: &RemoteHost,
+        dry_run: bool,
+        tags: &[String],
+        summary: &mut RunSummary,
+    ) -> Result<()> {
+        let projects = self.scan_remote(remote)?;
+        info!(project_count = projects.len(), "Remote scan complete.");
+        summary.scanned = projects.len();
+
+        let now = Utc::now();
+        let inactive = projects.iter().filter(|remote_project| {
+            let project = &remote_project.project;
+            let days = self.settings.inactivity_days_of_type(remote_project.kind);
+            now.signed_duration_since(project.last_activity) > Duration::days(days as i64)
+        });
+        for RemoteProject { project, .. } in inactive {
+            let mut outcome = ProjectOutcome {
+                name: project.name.to_string(),
+                path: project.path.clone(),
+                outcome: Outcome::WouldArchive,
+                bytes: None,
+                reason: None,
+                cleaned: Vec::new(),
+                cleaned_bytes: None,
+            };
+            if !dry_run {
+                info!(project_name = %project.name, "Archiving remote project...");
+                if let Err(e) = remote.archive(&self.settings.ssh_program, project, tags) {
+                    outcome.outcome = Outcome::Failed;
+                    outcome.reason = Some(e.to_string());
+                    summary.record(outcome);
+                    return Err(e);
+                }
+                outcome.outcome = Outcome::Archived;
+            }
+            summary.record(outcome);
+        }
+        Ok(())
+    }
+
+    /// Lists the active projects that will become archive candidates within `within`, soonest
+    /// first, so they can be pushed, excluded or finished before the archiver acts.
+    #[instrument(skip(self))]
+    pub fn forecast(&self, within: Duration) -> Result<Vec<UpcomingCandidate>> {
+        let now = Utc::now();
+        let mut upcoming: Vec<UpcomingCandidate> = self
+            .scan_projects()?
+            .into_iter()
+            .map(|project| UpcomingCandidate {
+                candidate_at: project.last_activity + self.inactivity_period(&project),
+                project,
+            })
+            .filter(|c| c.candidate_at >= now && c.candidate_at <= now + within)
+            .collect();
+        upcoming.sort_by_key(|c| c.candidate_at);
+        debug!(count = upcoming.len(), "Forecast complete.");
+        Ok(upcoming)
+    }
+
+    /// Describes what `restore_project` would move where, without changing anything.
+    #[instrument(skip(self))]
+    pub fn preview_restore(&self, project_name: &str) -> Result<RestorePreview> {
+        let mut all_records = self.get_archive_records()?;
+        let record = all_records.remove(self.find_record(&all_records, project_name)?);
+
+        Ok(RestorePreview {
+            destination_exists: record.original_path.exists(),
+            size_bytes: dir_size(&record.archive_path),
+            record,
+        })
+    }
+
+    /// Restores a single project to its original location and returns its former record.
     #[instrument(skip(self))]
-    pub fn restore_project(&self, project_name: &str) -> Result<()> {
+    pub fn restore_project(&self, project_name: &str) -> Result<ArchivedRecord> {
         info!(%project_name, "Attempting to restore project.");
         let mut all_records = self.get_archive_records()?;
-        let record_idx = all_records
-            .iter()
-            .position(|r| r.name == project_name)
-            .ok_or_else(|| {
-                Error::Custom(format!(
-                    "Project '{}' not found in archive log.",
-                    project_name
-                ))
-            })?;
-        let record = all_records.get(record_idx).unwrap();
-        debug!(from = %record.archive_path.display(), to = %record.original_path.display(), "Moving project directory.");
-        if let Some(parent) = record.original_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        fs::rename(&record.archive_path, &record.original_path)?;
-        all_records.remove(record_idx);
-        self.write_archive_log(&all_records)?;
+        let record_idx = self.find_record(&all_records, project_name)?;
+        let record = all_records.remove(record_idx);
+        self.ensure_in_archive(&record)?;
+        self.move_out_of_archive(&record)?;
+        self.remove_from_archive_log(std::slice::from_ref(&record))?;
+        self.record_restores(std::slice::from_ref(&record));
         info!(%project_name, "Project restored successfully.");
-        Ok(())
+        Ok(record)
     }
 
     /// Restores all projects from the archive to their original locations.
@@ -114,54 +555,147 @@ impl Archiver {
     #[instrument(skip(self))]
     pub fn restore_all(&self) -> Result<usize> {
         info!("Attempting to restore all projects.");
-        let all_records = self.get_archive_records()?;
-        let count = all_records.len();
+        self.restore_matching(&RestoreFilter::default())
+    }
 
-        if count == 0 {
-            info!("Archive is empty. Nothing to restore.");
+    /// Restores every archived project selected by `filter`, e.g. a cohort archived during a
+    /// misconfigured run. Returns the number of projects restored. The first project that
+    /// cannot be restored stops the batch, with the projects restored before it logged as such.
+    #[instrument(skip(self))]
+    pub fn restore_matching(&self, filter: &RestoreFilter) -> Result<usize> {
+        info!("Attempting to restore matching projects.");
+        let selected: Vec<_> = self
+            .get_archive_records()?
+            .into_iter()
+            .filter(|record| filter.matches(record))
+            .collect();
+        if selected.is_empty() {
+            info!("Nothing to restore.");
             return Ok(0);
         }
+        // A single record pointing outside the archive refuses the whole batch up front.
+        for record in &selected {
+            self.ensure_in_archive(record)?;
+        }
 
-        for record in &all_records {
-            let path = &record.archive_path;
-            let dest = &record.original_path;
-            debug!(from = %path.display(), to = %dest.display(), "Restoring project.");
-            if let Some(parent) = dest.parent() {
-                fs::create_dir_all(parent)?;
+        let mut restored = Vec::new();
+        let mut failure = None;
+        for record in selected {
+            match self.move_out_of_archive(&record) {
+                Ok(()) => restored.push(record),
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
             }
-            fs::rename(path, dest)?;
         }
 
-        // Clear the log file by writing an empty array
-        self.write_archive_log(&[])?;
-        info!("Successfully restored {} projects.", count);
-        Ok(count)
+        // Keep the log in sync with what was actually moved, even after a failure.
+        self.remove_from_archive_log(&restored)?;
+        self.record_restores(&restored);
+        if let Some(e) = failure {
+            return Err(e);
+        }
+        info!("Successfully restored {} projects.", restored.len());
+        Ok(restored.len())
+    }
+
+    /// Moves an archived project back to its original location and checks it against the
+    /// manifest taken when it was archived. A project that does not match is moved back into
+    /// the archive, so its record stays valid for another try.
+    fn move_out_of_archive(&self, record: &ArchivedRecord) -> Result<()> {
+        let (from, to) = (&record.archive_path, &record.original_path);
+        debug!(from = %from.display(), to = %to.display(), "Restoring project.");
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        move_dir(from, to)?;
+        if let Err(e) = Self::verify_restored(record) {
+            warn!(error = %e, "Restored project failed verification; moving it back into the archive.");
+            if let Err(undo) = move_dir(to, from) {
+                warn!(path = %to.display(), error = %undo, "Could not move the project back into the archive.");
+            }
+            return Err(e);
+        }
+        self.forget_archive_entry(from);
+        Ok(())
     }
 
     /// Deletes a single project permanently from the archive. This operation is irreversible!
     #[instrument(skip(self))]
     pub fn delete_project(&self, project_name: &str) -> Result<()> {
         info!(%project_name, "Attempting to delete project permanently.");
-        let mut all_records = self.get_archive_records()?;
+        let all_records = self.get_archive_records()?;
 
+        let record_idx = self.find_record(&all_records, project_name)?;
+        self.delete_at(all_records, record_idx)?;
+        info!("Project '{}' deleted successfully.", project_name);
+        Ok(())
+    }
+
+    /// Deletes the project archived at the archive path of `record`. Unlike its label, which
+    /// projects in different archive directories can share, the path identifies one entry.
+    fn delete_record(&self, record: &ArchivedRecord) -> Result<()> {
+        let all_records = self.get_archive_records()?;
         let record_idx = all_records
             .iter()
-            .position(|r| r.name == project_name)
-            .ok_or_else(|| {
-                Error::Custom(format!(
-                    "Project '{}' not found in archive log.",
-                    project_name
-                ))
+            .position(|r| r.archive_path == record.archive_path)
+            .ok_or_else(|| Error::ProjectNotFound {
+                key: record.label(),
+                suggestions: Vec::new(),
             })?;
+        self.delete_at(all_records, record_idx)
+    }
 
+    /// Deletes the project of `all_records[record_idx]` and logs the remaining records.
+    fn delete_at(&self, mut all_records: Vec<ArchivedRecord>, record_idx: usize) -> Result<()> {
         let record = all_records.remove(record_idx);
+        self.ensure_in_archive(&record)?;
 
         debug!(path = %record.archive_path.display(), "Deleting project directory.");
         fs::remove_dir_all(&record.archive_path)?;
+        self.forget_archive_entry(&record.archive_path);
 
-        self.write_archive_log(&all_records)?;
-        info!("Project '{}' deleted successfully.", project_name);
-        Ok(())
+        self.remove_from_archive_log(std::slice::from_ref(&record))
+    }
+
+    /// Deletes every archived project selected by `filter` permanently, e.g. a whole group.
+    /// Returns the number of projects deleted.
+    #[instrument(skip(self))]
+    pub fn delete_matching(&self, filter: &RestoreFilter) -> Result<usize> {
+        info!("Attempting to delete matching projects permanently.");
+        let selected: Vec<_> = self
+            .get_archive_records()?
+            .into_iter()
+            .filter(|record| filter.matches(record))
+            .collect();
+
+        let mut deleted = Vec::new();
+        let mut failure = None;
+        for record in selected {
+            if failure.is_some() {
+                break;
+            }
+            let removed = self
+                .ensure_in_archive(&record)
+                .and_then(|_| fs::remove_dir_all(&record.archive_path).map_err(Error::from));
+            match removed {
+                Ok(()) => {
+                    debug!(path = %record.archive_path.display(), "Deleted project directory.");
+                    self.forget_archive_entry(&record.archive_path);
+                    deleted.push(record);
+                }
+                Err(e) => failure = Some(e),
+            }
+        }
+
+        // Keep the log in sync with what was actually deleted, even after a failure.
+        self.remove_from_archive_log(&deleted)?;
+        if let Some(e) = failure {
+            return Err(e);
+        }
+        info!("Successfully deleted {} projects.", deleted.len());
+        Ok(deleted.len())
     }
 
     /// Deletes ALL projects permanently from the archive. This operation is irreversible, there is no undo!
@@ -177,38 +711,324 @@ impl Archiver {
             return Ok(0);
         }
 
+        // Archive routes may share their drive with other data: only their entries go.
+        for record in &all_records {
+            if record.archive_path.starts_with(&self.settings.archive_dir) {
+                continue;
+            }
+            self.ensure_in_archive(record)?;
+            match fs::remove_dir_all(&record.archive_path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => self.forget_archive_entry(&record.archive_path),
+            }
+        }
         debug!(path = %self.settings.archive_dir.display(), "Deleting all contents of archive directory.");
         // We can just remove the whole directory and recreate it. It's simpler.
         fs::remove_dir_all(&self.settings.archive_dir)?;
         fs::create_dir_all(&self.settings.archive_dir)?;
+        if self.settings.index_dir() != self.settings.archive_dir {
+            self.remove_from_archive_log(&all_records)?;
+            artifacts::write(&self.artifacts_log_path(), &[])?;
+        }
 
         info!("Successfully deleted {} projects.", count);
         Ok(count)
     }
 
+    /// Moves the build folders of the cleanup rules out of projects idle for `artifact_days`
+    /// but not inactive long enough to be archived, or only lists them on a dry run. The
+    /// sources stay in place. Returns the affected records.
+    #[instrument(skip(self), fields(dry_run = %dry_run))]
+    pub fn archive_artifacts(&self, dry_run: bool) -> Result<Vec<ArtifactRecord>> {
+        let Some(artifact_days) = self.settings.artifact_days else {
+            return Ok(Vec::new());
+        };
+        let log_path = self.artifacts_log_path();
+        let mut records = artifacts::read(&log_path)?;
+        let now = Utc::now();
+        let mut moved = Vec::new();
+        let containers = self.running_containers();
+
+        for project in self.scan_projects()? {
+            let idle = now.signed_duration_since(project.last_activity);
+            let inactivity_days = self.settings.inactivity_days_for(&project.path);
+            let semi_active = idle > Duration::days(artifact_days as i64)
+                && idle <= Duration::days(inactivity_days as i64);
+            if !semi_active || self.settings.is_excluded(project.name.as_str()) {
+                continue;
+            }
+            let folders = self.cleanup_targets(&project.path);
+            if folders.is_empty() {
+                continue;
+            }
+            if let Some(names) = mounting_containers(&containers, &project.path) {
+                info!(project_name = %project.name, containers = ?names, "Skipping artifacts of a mounted project.");
+                continue;
+            }
+            let archive_path = self
+                .settings
+                .archive_dir
+                .join(artifacts::DIR_NAME)
+                .join(project.name.as_str());
+            let taken = records
+                .iter()
+                .any(|r| r.archive_path == archive_path && r.project_path != project.path);
+            if taken {
+                warn!(project_name = %project.name, "Artifacts of another project with the same name are archived; skipping.");
+                continue;
+            }
+
+            let record = ArtifactRecord {
+                name: project.name.clone(),
+                project_path: project.path.clone(),
+                archive_path,
+                folders: folders
+                    .iter()
+                    .filter_map(|folder| folder.strip_prefix(&project.path).ok())
+                    .map(Path::to_path_buf)
+                    .collect(),
+                bytes: folders.iter().map(|folder| dir_size(folder)).sum(),
+                archived_at: now,
+            };
+            if !dry_run {
+                info!(project_name = %project.name, bytes = record.bytes, "Archiving build artifacts...");
+                // Artifacts moved before are superseded by the ones rebuilt since.
+                if record.archive_path.exists() {
+                    fs::remove_dir_all(&record.archive_path)?;
+                }
+                for relative in &record.folders {
+                    let dest = record.archive_path.join(relative);
+                    if let Some(parent) = dest.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::rename(project.path.join(relative), dest)?;
+                }
+                records.retain(|r| r.project_path != project.path);
+                records.push(record.clone());
+                artifacts::write(&log_path, &records)?;
+            }
+            moved.push(record);
+        }
+        Ok(moved)
+    }
+
+    /// Every project whose build artifacts are in the archive.
+    pub fn artifact_records(&self) -> Result<Vec<ArtifactRecord>> {
+        artifacts::read(&self.artifacts_log_path())
+    }
+
+    /// Moves the archived build artifacts of a project back into it. Fails without moving
+    /// anything if one of the folders was rebuilt in the meantime.
     #[instrument(skip(self))]
-    fn scan_projects(&self) -> Result<Vec<ScannedProject>> {
-        let mut projects = Vec::new();
+    pub fn restore_artifacts(&self, project_name: &str) -> Result<ArtifactRecord> {
+        let log_path = self.artifacts_log_path();
+        let mut records = artifacts::read(&log_path)?;
+        let matches: Vec<usize> = records
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.name == project_name)
+            .map(|(idx, _)| idx)
+            .collect();
+        let idx = match matches.as_slice() {
+            [idx] => *idx,
+            [] => {
+                return Err(Error::ProjectNotFound {
+                    key: project_name.to_string(),
+                    suggestions: names::suggestions(
+                        project_name,
+                        records.iter().map(|r| r.name.as_str()),
+                    ),
+                })
+            }
+            _ => {
+                return Err(Error::AmbiguousProject {
+                    key: project_name.to_string(),
+                    candidates: matches
+                        .iter()
+                        .map(|idx| records[*idx].project_path.display().to_string())
+                        .collect(),
+                })
+            }
+        };
+        let record = records.remove(idx);
+        let confined = record
+            .archive_path
+            .strip_prefix(self.settings.archive_dir.join(artifacts::DIR_NAME))
+            .is_ok_and(models::is_confined_relative_path);
+        if !confined {
+            return Err(Error::OutsideArchive {
+                project: record.name.to_string(),
+                path: record.archive_path,
+            });
+        }
+        if let Some(existing) = record
+            .folders
+            .iter()
+            .map(|relative| record.project_path.join(relative))
+            .find(|dest| dest.exists())
+        {
+            return Err(Error::Custom(format!(
+                "'{}' was rebuilt since its artifacts were archived; remove it to restore them.",
+                existing.display()
+            )));
+        }
+
+        for relative in &record.folders {
+            fs::rename(
+                record.archive_path.join(relative),
+                record.project_path.join(relative),
+            )?;
+        }
+        fs::remove_dir_all(&record.archive_path)?;
+        artifacts::write(&log_path, &records)?;
+        info!(%project_name, "Build artifacts restored.");
+        Ok(record)
+    }
+
+    fn artifacts_log_path(&self) -> PathBuf {
+        self.settings.index_dir().join(artifacts::FILE_NAME)
+    }
+
+    /// Returns the deletion policy in effect, as computed from the settings.
+    pub fn deletion_policy(&self) -> DeletionPolicy {
+        DeletionPolicy::from_settings(&self.settings)
+    }
+
+    /// Deletes the archived projects the deletion policy allows to delete, or only lists them
+    /// on a dry run. Returns the affected records.
+    pub fn run_auto_delete(&self, dry_run: bool) -> Result<Vec<ArchivedRecord>> {
+        self.delete_expired(dry_run, &mut RunSummary::start(dry_run))
+    }
+
+    #[instrument(skip(self, summary), fields(dry_run = %dry_run))]
+    fn delete_expired(
+        &self,
+        dry_run: bool,
+        summary: &mut RunSummary,
+    ) -> Result<Vec<ArchivedRecord>> {
+        let expired = self.expired_records()?;
+        self.delete_records(&expired, dry_run, summary)?;
+        Ok(expired)
+    }
+
+    /// The archived projects the deletion policy allows deleting now. Empty when automatic
+    /// deletion is disabled.
+    fn expired_records(&self) -> Result<Vec<ArchivedRecord>> {
+        let policy = self.deletion_policy();
+        if !policy.enabled {
+            debug!("Automatic deletion is disabled.");
+            return Ok(vec![]);
+        }
+
+        let now = Utc::now();
+        let expired: Vec<ArchivedRecord> = self
+            .get_archive_records()?
+            .into_iter()
+            .filter(|record| policy.allows_deletion(record, now))
+            .collect();
+        info!(
+            count = expired.len(),
+            min_archived_days = policy.min_archived_days,
+            "Found archived projects past the retention period."
+        );
+        Ok(expired)
+    }
+
+    /// Deletes the archived `records`, or only records what would be done on a dry run.
+    fn delete_records(
+        &self,
+        records: &[ArchivedRecord],
+        dry_run: bool,
+        summary: &mut RunSummary,
+    ) -> Result<()> {
+        for (index, record) in records.iter().enumerate() {
+            if !dry_run && interrupt::requested() {
+                let left = &records[index..];
+                self.save_resume_point(&[], left, &[])?;
+                return Err(Error::Interrupted { left: left.len() });
+            }
+            let mut outcome = ProjectOutcome {
+                name: record.label(),
+                path: record.archive_path.clone(),
+                outcome: Outcome::WouldDelete,
+                bytes: Some(dir_size(&record.archive_path)),
+                reason: None,
+                cleaned: Vec::new(),
+                cleaned_bytes: None,
+            };
+            if !dry_run {
+                if let Err(e) = self.delete_record(record) {
+                    outcome.outcome = Outcome::Failed;
+                    outcome.reason = Some(e.to_string());
+                    summary.record(outcome);
+                    return Err(e);
+                }
+                outcome.outcome = Outcome::Deleted;
+            }
+            summary.record(outcome);
+        }
+        Ok(())
+    }
+
+    /// Groups the scanned projects that are copies of each other (same remote repository or
+    /// same content), each group listing the most recently active copy first.
+    pub fn find_duplicates(&self) -> Result<Vec<DuplicateGroup>> {
+        Ok(duplicates::find(&self.scan_projects()?))
+    }
+
+    /// Scans the projects directories, returning every project with its last activity.
+    #[instrument(skip(self))]
+    pub fn scan_projects(&self) -> Result<Vec<ScannedProject>> {
+        let mut candidates = Vec::new();
+        for projects_dir in &self.settings.projects_dirs {
+            debug!(directory = %projects_dir.display(), depth = self.settings.scan_depth, "Scanning for projects.");
+            self.scan_directory(projects_dir, 1, &mut candidates)?;
+        }
+        // Finding the last activity walks histories and trees: it runs on `jobs` threads.
+        let activity = jobs::map(self.settings.jobs(), &candidates, |(_, path)| {
+            self.get_last_activity(path)
+        });
+        let projects = candidates
+            .into_iter()
+            .zip(activity)
+            .filter_map(|((name, path), activity)| match activity {
+                Ok((last_activity, scan_limit)) => Some(ScannedProject {
+                    name,
+                    path,
+                    last_activity,
+                    scan_limit,
+                }),
+                Err(e) => {
+                    warn!(path = %path.display(), error = %e, "Could not determine activity for directory, skipping.");
+                    None
+                }
+            })
+            .collect();
+        Ok(projects)
+    }
+
+    /// Collects the entries of `dir` that are projects. While deep scanning (`scan_depth > 1`),
+    /// grouping directories are descended into instead, and workspace roots are kept as a
+    /// single project.
+    fn scan_directory(
+        &self,
+        dir: &Path,
+        depth: usize,
+        candidates: &mut Vec<(ProjectName, PathBuf)>,
+    ) -> Result<()> {
         let archive_dir_name = self.settings.archive_dir.file_name();
-        debug!(directory = %self.settings.projects_dir.display(), "Scanning for projects.");
 
-        for entry_result in WalkDir::new(&self.settings.projects_dir)
-            .min_depth(1)
-            .max_depth(1)
-        {
+        for entry_result in WalkDir::new(dir).min_depth(1).max_depth(1) {
             let entry = entry_result?;
-            if Some(entry.file_name()) == archive_dir_name {
+            if Some(entry.file_name()) == archive_dir_name
+                || self.settings.archive_dirs().any(|dir| entry.path() == dir)
+            {
                 debug!(path = %entry.path().display(), "Skipping archive directory.");
                 continue;
             }
 
-            let project_name = entry.file_name().to_string_lossy();
-            if self
-                .settings
-                .exclude
-                .iter()
-                .any(|excluded| *excluded == project_name)
-            {
+            let project_name = entry.file_name().to_string_lossy().into_owned();
+            if self.settings.is_excluded(&project_name) {
                 debug!(name = %project_name, "Skipping excluded project.");
                 continue;
             }
@@ -218,27 +1038,49 @@ impl Archiver {
                 continue;
             }
 
-            match self.get_last_activity(path) {
-                Ok(last_activity) => {
-                    projects.push(ScannedProject {
-                        name: entry.file_name().to_string_lossy().into_owned(),
-                        path: path.to_path_buf(),
-                        last_activity,
-                    });
-                }
+            if depth < self.settings.scan_depth
+                && detect::is_container(path, self.settings.scan_depth - depth)
+            {
+                debug!(path = %path.display(), "Descending into grouping directory.");
+                self.scan_directory(path, depth + 1, candidates)?;
+                continue;
+            }
+            if let Some(kind) = detect::workspace_kind(path) {
+                debug!(path = %path.display(), ?kind, "Detected workspace root, scanning it as one project.");
+            }
+
+            let name = match ProjectName::new(project_name) {
+                Ok(name) => name,
                 Err(e) => {
-                    warn!(path = %path.display(), error = %e, "Could not determine activity for directory, skipping.");
+                    warn!(path = %path.display(), error = %e, "Skipping directory with an unusable name.");
+                    continue;
                 }
-            }
+            };
+            candidates.push((name, path.to_path_buf()));
         }
-        Ok(projects)
+        Ok(())
     }
 
-    /// Determines the last activity of a directory, trying Git first and falling back to file mtime.
-    fn get_last_activity(&self, path: &Path) -> Result<DateTime<Utc>> {
+    /// Determines the last activity of a directory: from the first activity probe matching it,
+    /// then Git, falling back to file mtime. Also returns the limit that stopped the file time
+    /// scan early, if one did.
+    fn get_last_activity(&self, path: &Path) -> Result<(DateTime<Utc>, Option<ScanLimit>)> {
+        if let Some(probe) = self
+            .settings
+            .activity_probes
+            .iter()
+            .find(|probe| probe.matches(path))
+        {
+            match probe.run(path) {
+                Ok(dt) => return Ok((dt, None)),
+                Err(e) => {
+                    warn!(path = %path.display(), error = %e, "Activity probe failed, using the usual activity checks.");
+                }
+            }
+        }
         if path.join(".git").is_dir() {
             match self.get_git_last_activity(path) {
-                Ok(dt) => return Ok(dt),
+                Ok(dt) => return Ok((dt, None)),
                 Err(e) => {
                     // If Git fails (e.g., empty repo), we don't give up.
                     // We log it and fall back to checking file modification times.
@@ -253,41 +1095,100 @@ impl Archiver {
     /// Gets the last activity time from a Git repository.
     fn get_git_last_activity(&self, path: &Path) -> Result<DateTime<Utc>> {
         let repo = Repository::open(path)?;
-        let last_commit = self.find_last_commit_across_branches(&repo)?;
+        let last_commit = if self.settings.activity_authors.is_empty() {
+            self.find_last_commit_across_branches(&repo)?
+        } else {
+            self.find_last_authored_commit(&repo)?
+        };
         let commit_time = last_commit.time();
 
-        DateTime::from_timestamp(commit_time.seconds(), 0)
+        let commit_activity = DateTime::from_timestamp(commit_time.seconds(), 0)
             .ok_or_else(|| Error::Custom("Invalid commit time".to_string()))
-            .map(|dt| dt.with_timezone(&Utc))
+            .map(|dt| dt.with_timezone(&Utc))?;
+
+        if !self.settings.count_untracked_files {
+            return Ok(commit_activity);
+        }
+        match self.find_latest_untracked_mtime(&repo, path)? {
+            Some(untracked) if untracked > commit_activity => {
+                debug!(path = %path.display(), %untracked, "Untracked files are newer than the last commit.");
+                Ok(untracked)
+            }
+            _ => Ok(commit_activity),
+        }
     }
 
-    /// Finds the latest modification time for a non-Git directory.
-    fn find_latest_mtime(&self, dir_path: &Path) -> Result<DateTime<Utc>> {
-        let latest_file_mtime = WalkDir::new(dir_path)
-            .into_iter()
-            .map(|entry_result| -> Result<Option<DateTime<Utc>>> {
-                let entry = entry_result?;
-                if entry.file_type().is_file() {
-                    let metadata = entry.metadata()?;
-                    let modified: DateTime<Utc> = metadata.modified()?.into();
-                    Ok(Some(modified))
-                } else {
-                    Ok(None)
-                }
-            })
-            .collect::<Result<Vec<Option<DateTime<Utc>>>>>()?
-            .into_iter()
-            .flatten()
-            .max();
+    /// Finds the newest modification time among untracked, non-ignored files of a repository.
+    fn find_latest_untracked_mtime(
+        &self,
+        repo: &Repository,
+        path: &Path,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let mut options = git2::StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false);
+
+        let mut latest = None;
+        for entry in repo.statuses(Some(&mut options))?.iter() {
+            if !entry.status().contains(git2::Status::WT_NEW) {
+                continue;
+            }
+            let Some(relative) = entry.path() else {
+                continue;
+            };
+            let Ok(metadata) = fs::metadata(path.join(relative)) else {
+                continue;
+            };
+            let modified: DateTime<Utc> = metadata.modified()?.into();
+            latest = latest.max(Some(modified));
+        }
+        Ok(latest)
+    }
+
+    /// Finds the latest modification time for a non-Git directory, stopping at the
+    /// `mtime_scan_max_files` and `mtime_scan_timeout_secs` limits.
+    fn find_latest_mtime(&self, dir_path: &Path) -> Result<(DateTime<Utc>, Option<ScanLimit>)> {
+        let started = std::time::Instant::now();
+        let mut files = 0;
+        let mut limit = None;
+        let mut latest_file_mtime = None;
+        for entry_result in WalkDir::new(dir_path) {
+            let entry = entry_result?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            if self
+                .settings
+                .mtime_scan_max_files
+                .is_some_and(|max| files >= max)
+            {
+                limit = Some(ScanLimit::MaxFiles(files));
+            } else if let Some(secs) = self
+                .settings
+                .mtime_scan_timeout_secs
+                .filter(|secs| started.elapsed().as_secs() >= *secs)
+            {
+                limit = Some(ScanLimit::Timeout(secs));
+            }
+            if let Some(limit) = limit {
+                warn!(path = %dir_path.display(), %limit, "Stopped scanning the project early.");
+                break;
+            }
+            files += 1;
+            let modified: DateTime<Utc> = entry.metadata()?.modified()?.into();
+            latest_file_mtime = latest_file_mtime.max(Some(modified));
+        }
 
         // If the latest file time was found, return it.
         // Otherwise, fall back to the directory's own modification time.
         if let Some(latest) = latest_file_mtime {
-            Ok(latest)
+            Ok((latest, limit))
         } else {
             let dir_meta = fs::metadata(dir_path)?;
             let dir_mtime: DateTime<Utc> = dir_meta.modified()?.into();
-            Ok(dir_mtime)
+            Ok((dir_mtime, limit))
         }
     }
     /// Finds the most recent commit across all local branches in a repository.
@@ -309,30 +1210,467 @@ impl Archiver {
             })
     }
 
+    /// Finds the most recent commit on any local branch authored by one of `activity_authors`,
+    /// so that merged upstream work does not count as the user's own activity.
+    fn find_last_authored_commit<'repo>(
+        &self,
+        repo: &'repo Repository,
+    ) -> Result<git2::Commit<'repo>> {
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        for (branch, _) in repo
+            .branches(Some(git2::BranchType::Local))?
+            .filter_map(|res| res.ok())
+        {
+            if let Some(oid) = branch.get().target() {
+                revwalk.push(oid)?;
+            }
+        }
+
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            let is_own = commit.author().email().is_some_and(|email| {
+                self.settings
+                    .activity_authors
+                    .iter()
+                    .any(|author| author.eq_ignore_ascii_case(email))
+            });
+            if is_own {
+                return Ok(commit);
+            }
+        }
+        Err(Error::Git(git2::Error::new(
+            git2::ErrorCode::NotFound,
+            git2::ErrorClass::Reference,
+            "No commits by the configured activity authors",
+        )))
+    }
+
     fn filter_inactive_projects(&self, projects: Vec<ScannedProject>) -> Vec<ScannedProject> {
         let now = Utc::now();
-        let inactivity_period = Duration::days(self.settings.inactivity_days as i64);
+        let stale_copies = self.stale_copies(&projects);
         projects
             .into_iter()
-            .filter(|p| now.signed_duration_since(p.last_activity) > inactivity_period)
+            .filter(|p| {
+                now.signed_duration_since(p.last_activity) > self.inactivity_period(p)
+                    || stale_copies.contains(&p.path)
+            })
             .collect()
     }
 
+    /// With `archive_duplicates`, the paths of every copy of a duplicated project but the most
+    /// recently active one.
+    fn stale_copies(&self, projects: &[ScannedProject]) -> Vec<PathBuf> {
+        if !self.settings.archive_duplicates {
+            return Vec::new();
+        }
+        duplicates::find(projects)
+            .into_iter()
+            .flat_map(|group| group.projects.into_iter().skip(1))
+            .map(|copy| {
+                info!(project_name = %copy.name, path = %copy.path.display(), "Older copy of a duplicated project.");
+                copy.path
+            })
+            .collect()
+    }
+
+    /// How long the project must be inactive before it is archived, depending on its type.
+    fn inactivity_period(&self, project: &ScannedProject) -> Duration {
+        let days = self.settings.inactivity_days_for(&project.path);
+        if days != self.settings.inactivity_days {
+            debug!(project_name = %project.name, days, "Using the inactivity threshold of its project type.");
+        }
+        Duration::days(days as i64)
+    }
+
+    /// Returns why an inactive project must not be archived, if something protects it, its
+    /// activity scan stopped early or the current user lacks the permissions to move it.
+    fn protection_reason(
+        &self,
+        project: &ScannedProject,
+        containers: &[RunningContainer],
+    ) -> Option<String> {
+        if let Some(limit) = project.scan_limit {
+            return Some(limit.to_string());
+        }
+        if let Some(branch) = self.protected_branch(&project.path) {
+            return Some(format!("checked out on protected branch '{}'", branch));
+        }
+        if let Some(count) = self.open_reviews(&project.path) {
+            return Some(format!("{} open pull/merge request(s)", count));
+        }
+        if let Some(names) = mounting_containers(containers, &project.path) {
+            return Some(format!(
+                "mounted by running container(s) {}",
+                names.join(", ")
+            ));
+        }
+        self.preflight_failure(project)
+    }
+
+    /// Returns why the current user cannot archive a project, listing the paths that fail
+    /// the permission preflight.
+    fn preflight_failure(&self, project: &ScannedProject) -> Option<String> {
+        let blocked = preflight::blocked_paths(&project.path, &self.cleanup_targets(&project.path));
+        if blocked.is_empty() {
+            return None;
+        }
+        const SHOWN: usize = 5;
+        let mut paths: Vec<String> = blocked
+            .iter()
+            .take(SHOWN)
+            .map(|path| path.display().to_string())
+            .collect();
+        if blocked.len() > SHOWN {
+            paths.push(format!("and {} more", blocked.len() - SHOWN));
+        }
+        Some(format!("insufficient permissions on {}", paths.join(", ")))
+    }
+
+    /// Returns the number of open PRs/MRs involving the user, if there are any. A failed query
+    /// is logged and does not protect the project.
+    fn open_reviews(&self, path: &Path) -> Option<usize> {
+        if !self.settings.check_open_reviews {
+            return None;
+        }
+        let forge = forge::Forge::detect(path, &self.settings.gitlab_hosts)?;
+        match forge::open_review_count(path, forge) {
+            Ok(0) => None,
+            Ok(count) => Some(count),
+            Err(e) => {
+                warn!(path = %path.display(), ?forge, error = %e, "Could not check for open reviews.");
+                None
+            }
+        }
+    }
+
+    /// Lists the running containers once for a whole run, or none when `check_containers` is
+    /// off. A failed query is logged and protects no project.
+    fn running_containers(&self) -> Vec<RunningContainer> {
+        if !self.settings.check_containers {
+            return Vec::new();
+        }
+        let socket = containers::socket_path(self.settings.docker_socket.as_deref());
+        containers::running(&socket).unwrap_or_else(|e| {
+            warn!(socket = %socket.display(), error = %e, "Could not list running containers.");
+            Vec::new()
+        })
+    }
+
+    /// Returns the current branch of the repository at `path` if it matches `skip_branches`.
+    fn protected_branch(&self, path: &Path) -> Option<String> {
+        if self.settings.skip_branches.is_empty() || !path.join(".git").exists() {
+            return None;
+        }
+        let repo = Repository::open(path).ok()?;
+        let head = repo.head().ok()?;
+        let branch = head.shorthand()?;
+        self.settings
+            .skip_branches
+            .iter()
+            .any(|pattern| glob_matches(pattern, branch))
+            .then(|| branch.to_string())
+    }
+
+    /// Folders the cleanup rules remove from a project before it is archived (e.g.
+    /// `node_modules` when there is a `package.json`), among those that exist.
+    fn cleanup_targets(&self, project_path: &Path) -> Vec<PathBuf> {
+        let mut targets: Vec<PathBuf> = self
+            .settings
+            .cleanup_rules
+            .iter()
+            .filter(|rule| project_path.join(&rule.detection_file).exists())
+            .flat_map(|rule| &rule.folders_to_delete)
+            .filter(|folder| models::is_confined_relative_path(Path::new(folder)))
+            .map(|folder| project_path.join(folder))
+            // Symlinked folders are left alone: their target may live outside the project.
+            .filter(|path| fs::symlink_metadata(path).is_ok_and(|m| m.is_dir()))
+            .collect();
+        targets.sort();
+        targets.dedup();
+        targets
+    }
+
+    /// The keep-list protecting a project's files from cleanup: `cleanup_keep` and the `keep`
+    /// globs of the rules that apply to it.
+    fn keep_list(&self, project_path: &Path) -> KeepList {
+        let rules = self
+            .settings
+            .cleanup_rules
+            .iter()
+            .filter(|rule| project_path.join(&rule.detection_file).exists());
+        KeepList::new(
+            self.settings
+                .cleanup_keep
+                .iter()
+                .chain(rules.flat_map(|rule| &rule.keep)),
+        )
+    }
+
+    fn clean_project(project_path: &Path, targets: &[PathBuf], keep: &KeepList) -> Result<()> {
+        for target in targets {
+            let kept = cleanup::remove_folder(project_path, target, keep)?;
+            for path in kept {
+                info!(path = %path.display(), "Kept a protected path while cleaning up.");
+            }
+        }
+        Ok(())
+    }
+
+    /// Trims the project's git history when `trim_history` is set, returning how many commits
+    /// were dropped. A failure is only logged: the history is then archived as it is.
+    fn trim_history(&self, project_path: &Path) -> Option<usize> {
+        let keep = self.settings.trim_history.as_ref()?;
+        match history::trim(project_path, keep) {
+            Ok(0) => None,
+            Ok(dropped) => {
+                info!(dropped, "Trimmed git history before archiving.");
+                Some(dropped)
+            }
+            Err(e) => {
+                warn!(error = %e, "Could not trim git history; archiving it in full.");
+                None
+            }
+        }
+    }
+
     #[instrument(skip(self, project))]
-    fn archive_project(&self, project: &ScannedProject) -> Result<ArchivedRecord> {
+    fn archive_project(
+        &self,
+        project: &ScannedProject,
+        tags: &[String],
+        trimmed_commits: Option<usize>,
+    ) -> Result<ArchivedRecord> {
         let project_name = &project.name;
-        let dest_path = self.settings.archive_dir.join(project_name);
+        let relative_path = match self.settings.layout {
+            ArchiveLayout::Flat => None,
+            ArchiveLayout::Nested => self
+                .settings
+                .projects_dirs
+                .iter()
+                .find_map(|dir| project.path.strip_prefix(dir).ok())
+                .map(Path::to_path_buf),
+        };
+        let group = self.group_for(&project.path)?;
+        let route = self.route_for(&project.path);
+        let mut dest_path = route
+            .map_or(&self.settings.archive_dir, |route| &route.archive_dir)
+            .clone();
+        if let Some(group) = &group {
+            dest_path.push(group);
+        }
+        dest_path.push(relative_path.as_deref().unwrap_or(project_name.as_ref()));
+        let code_stats = self.settings.collect_code_stats.then(|| {
+            debug!(path = %project.path.display(), "Counting lines of code.");
+            CodeStats::collect(&project.path)
+        });
         debug!(from = %project.path.display(), to = %dest_path.display(), "Moving project directory.");
         if let Some(parent) = dest_path.parent() {
             fs::create_dir_all(parent)?;
         }
-        fs::rename(&project.path, &dest_path)?;
-        Ok(ArchivedRecord {
+        move_dir(&project.path, &dest_path)?;
+        let manifest = Manifest::of(&dest_path);
+        let record = ArchivedRecord {
             name: project_name.clone(),
             original_path: project.path.clone(),
             archive_path: dest_path,
+            relative_path,
             archived_at: Utc::now(),
-        })
+            machine: self.settings.sync_index.then(|| self.settings.machine_id()),
+            tags: tags.to_vec(),
+            code_stats,
+            trimmed_commits,
+            manifest: Some(manifest),
+            group,
+            destination: route.map(|route| route.name.clone()),
+        };
+        // The project is already moved: a missing sidecar must not lose its log record.
+        if let Err(e) = sidecar::write(&record) {
+            warn!(error = %e, "Could not write metadata sidecar.");
+        }
+        Ok(record)
+    }
+
+    /// How often each project was restored, from the counts kept next to the archive log.
+    pub fn restore_counts(&self) -> Result<Vec<RestoreCount>> {
+        restores::read(&self.settings.index_dir().join(restores::FILE_NAME))
+    }
+
+    /// Projects of `summary` that are archived (or would be) again after being restored at
+    /// least `suggest_exclusion_after` times, and would rather be excluded.
+    pub fn exclusion_suggestions(&self, summary: &RunSummary) -> Vec<RestoreCount> {
+        let threshold = self.settings.suggest_exclusion_after;
+        if threshold == 0 {
+            return Vec::new();
+        }
+        let counts = match self.restore_counts() {
+            Ok(counts) => counts,
+            Err(e) => {
+                warn!(error = %e, "Could not read the restore counts.");
+                return Vec::new();
+            }
+        };
+        let archived: Vec<_> = summary
+            .with_outcome(Outcome::Archived)
+            .chain(summary.with_outcome(Outcome::WouldArchive))
+            .map(|project| &project.path)
+            .collect();
+        counts
+            .into_iter()
+            .filter(|count| count.count >= threshold && archived.contains(&&count.path))
+            .collect()
+    }
+
+    /// Counts a restore of each of `restored`. A failure is only logged.
+    fn record_restores(&self, restored: &[ArchivedRecord]) {
+        let path = self.settings.index_dir().join(restores::FILE_NAME);
+        if let Err(e) = restores::record(&path, restored) {
+            warn!(path = %path.display(), error = %e, "Could not record restores.");
+        }
+    }
+
+    /// Checks a restored project against the manifest taken when it was archived. Records
+    /// written before manifests existed are not checked.
+    fn verify_restored(record: &ArchivedRecord) -> Result<()> {
+        let Some(expected) = &record.manifest else {
+            debug!(project = %record.label(), "No manifest recorded; skipping verification.");
+            return Ok(());
+        };
+        let found = Manifest::of(&record.original_path);
+        if !expected.matches(&found) {
+            return Err(Error::RestoreMismatch {
+                project: record.label(),
+                expected: expected.clone(),
+                found,
+            });
+        }
+        Ok(())
+    }
+
+    /// The group a project is archived in: `archive_group`, or the name of its projects
+    /// directory with `group_by_root`. Groups name a directory, so they must be a single path
+    /// component.
+    fn group_for(&self, project_path: &Path) -> Result<Option<String>> {
+        let group = self.settings.archive_group.clone().or_else(|| {
+            if !self.settings.group_by_root {
+                return None;
+            }
+            let root = self
+                .settings
+                .projects_dirs
+                .iter()
+                .find(|dir| project_path.starts_with(dir))?;
+            Some(root.file_name()?.to_string_lossy().into_owned())
+        });
+        match group {
+            Some(group) => Ok(Some(ProjectName::new(group)?.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// The first archive route the project at `path` matches, if any.
+    fn route_for(&self, path: &Path) -> Option<&ArchiveRoute> {
+        if self.settings.archive_routes.is_empty() {
+            return None;
+        }
+        let bytes = dir_size(path);
+        let route = self
+            .settings
+            .archive_routes
+            .iter()
+            .find(|route| route.matches(path, bytes));
+        if let Some(route) = route {
+            debug!(route = %route.name, path = %path.display(), "Routing project.");
+        }
+        route
+    }
+
+    /// Finds a record by project name, or by its relative path for nested layouts. With
+    /// `loose_name_matching`, a key that matches nothing exactly may differ in case and Unicode
+    /// normalization. Fails if the name is ambiguous, which can happen when nested projects
+    /// share a name, and suggests close names if nothing matches.
+    fn find_record(&self, records: &[ArchivedRecord], key: &str) -> Result<usize> {
+        if !models::is_confined_relative_path(Path::new(key)) {
+            return Err(Error::InvalidProjectName(key.to_string()));
+        }
+        let find = |same: &dyn Fn(&str) -> bool| -> Vec<usize> {
+            records
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| {
+                    same(r.name.as_str())
+                        || r.relative_path
+                            .as_deref()
+                            .and_then(Path::to_str)
+                            .is_some_and(same)
+                })
+                .map(|(idx, _)| idx)
+                .collect()
+        };
+        let mut matches = find(&|name| name == key);
+        if matches.is_empty() && self.settings.loose_name_matching {
+            matches = find(&|name| names::loosely_equal(name, key));
+        }
+        match matches.as_slice() {
+            [idx] => Ok(*idx),
+            [] => Err(Error::ProjectNotFound {
+                key: key.to_string(),
+                suggestions: names::suggestions(key, records.iter().map(|r| r.name.as_str())),
+            }),
+            _ => Err(Error::AmbiguousProject {
+                key: key.to_string(),
+                candidates: matches.iter().map(|idx| records[*idx].label()).collect(),
+            }),
+        }
+    }
+
+    /// Refuses to act on a record whose archive path lies outside the archive directories, as a
+    /// hand-edited or corrupted log could otherwise move or delete arbitrary directories.
+    fn ensure_in_archive(&self, record: &ArchivedRecord) -> Result<()> {
+        let confined = self.settings.archive_dirs().any(|dir| {
+            record
+                .archive_path
+                .strip_prefix(dir)
+                .is_ok_and(models::is_confined_relative_path)
+        });
+        if confined {
+            Ok(())
+        } else {
+            Err(Error::OutsideArchive {
+                project: record.label(),
+                path: record.archive_path.clone(),
+            })
+        }
+    }
+
+    /// Cleans up after a project left the archive: its metadata sidecar, then the grouping
+    /// directories (e.g. `github.com/org`) left empty.
+    fn forget_archive_entry(&self, archive_path: &Path) {
+        if let Err(e) = sidecar::remove(archive_path) {
+            warn!(path = %archive_path.display(), error = %e, "Could not remove metadata sidecar.");
+        }
+        self.remove_empty_archive_parents(archive_path);
+    }
+
+    /// Removes the grouping directories (e.g. `github.com/org`) left empty in the archive.
+    fn remove_empty_archive_parents(&self, archive_path: &Path) {
+        let Some(archive_dir) = self
+            .settings
+            .archive_dirs()
+            .find(|dir| archive_path.starts_with(dir))
+        else {
+            return;
+        };
+        let mut current = archive_path.parent();
+        while let Some(dir) = current {
+            if dir == archive_dir || !dir.starts_with(archive_dir) {
+                break;
+            }
+            if fs::remove_dir(dir).is_err() {
+                break;
+            }
+            current = dir.parent();
+        }
     }
 
     #[instrument(skip(self, new_records))]
@@ -341,28 +1679,332 @@ impl Archiver {
             return Ok(());
         }
         info!(count = new_records.len(), "Appending to archive log file.");
+        if let Some(log_path) = self.journal_path()? {
+            return journal::append_records(&log_path, new_records);
+        }
+        if self.settings.sync_index {
+            let index_dir = self.settings.index_dir();
+            fs::create_dir_all(index_dir)?;
+            return sync::append_records(index_dir, new_records, &self.settings.machine_id());
+        }
         let mut all_records = self.get_archive_records()?;
         all_records.extend_from_slice(new_records);
         self.write_archive_log(&all_records)
     }
 
+    /// Removes `removed` from the archive log, keeping every other record, including those
+    /// another process logged since the caller read the log.
+    #[instrument(skip(self, removed))]
+    fn remove_from_archive_log(&self, removed: &[ArchivedRecord]) -> Result<()> {
+        if removed.is_empty() {
+            return Ok(());
+        }
+        if let Some(log_path) = self.journal_path()? {
+            return journal::remove_records(&log_path, removed);
+        }
+        if self.settings.sync_index {
+            return sync::remove_records(self.settings.index_dir(), removed);
+        }
+        let mut all_records = self.get_archive_records()?;
+        all_records.retain(|record| {
+            !removed
+                .iter()
+                .any(|r| r.name == record.name && r.archived_at == record.archived_at)
+        });
+        self.write_archive_log(&all_records)
+    }
+
+    /// Replaces the records of the JSON archive log.
     #[instrument(skip(self, records))]
     fn write_archive_log(&self, records: &[ArchivedRecord]) -> Result<()> {
-        let log_path = self.settings.archive_dir.join(Self::ARCHIVE_LOG_FILE);
+        self.relocate_archive_log()?;
+        let index_dir = self.settings.index_dir();
+        fs::create_dir_all(index_dir)?;
+        let log_path = index_dir.join(Self::ARCHIVE_LOG_FILE);
         debug!(path = %log_path.display(), "Writing archive log.");
         let json_data = serde_json::to_string_pretty(records)?;
         fs::write(log_path, json_data)?;
         Ok(())
     }
 
+    /// The archive log this machine writes: the shared `archive.json` (or `archive.jsonl`), or
+    /// its own `archive.<machine>.json` when the index is shared between machines.
+    pub fn archive_log_path(&self) -> PathBuf {
+        let file_name = if self.settings.sync_index {
+            sync::log_file_name(Some(&self.settings.machine_id()))
+        } else {
+            Self::log_file_name(self.settings.index_format).to_string()
+        };
+        self.settings.index_dir().join(file_name)
+    }
+
+    fn log_file_name(format: IndexFormat) -> &'static str {
+        match format {
+            IndexFormat::Json => Self::ARCHIVE_LOG_FILE,
+            IndexFormat::Jsonl => journal::FILE_NAME,
+        }
+    }
+
+    /// The append-only log, when it is the configured format. Fails when the log on disk is
+    /// in the other format, instead of starting an empty one next to it.
+    fn journal_path(&self) -> Result<Option<PathBuf>> {
+        self.relocate_archive_log()?;
+        self.check_index_format()?;
+        Ok(
+            (!self.settings.sync_index && self.settings.index_format == IndexFormat::Jsonl)
+                .then(|| self.archive_log_path()),
+        )
+    }
+
+    fn check_index_format(&self) -> Result<()> {
+        if self.settings.sync_index {
+            return Ok(());
+        }
+        let other = match self.settings.index_format {
+            IndexFormat::Json => IndexFormat::Jsonl,
+            IndexFormat::Jsonl => IndexFormat::Json,
+        };
+        let other_path = self.settings.index_dir().join(Self::log_file_name(other));
+        if other_path.exists() && !self.archive_log_path().exists() {
+            return Err(Error::Custom(format!(
+                "The archive log '{}' is not in the configured 'index_format'; convert it with `archiver index convert`.",
+                other_path.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Converts the archive log to the configured `index_format`, removing the old file.
+    /// Returns the number of records converted, or `None` when there was nothing to convert.
+    #[instrument(skip(self))]
+    pub fn convert_archive_log(&self) -> Result<Option<usize>> {
+        if self.settings.sync_index {
+            return Err(Error::Custom(
+                "A shared archive index ('sync_index') is always stored as JSON.".to_string(),
+            ));
+        }
+        self.relocate_archive_log()?;
+        let index_dir = self.settings.index_dir();
+        let (from, to) = match self.settings.index_format {
+            IndexFormat::Json => (journal::FILE_NAME, Self::ARCHIVE_LOG_FILE),
+            IndexFormat::Jsonl => (Self::ARCHIVE_LOG_FILE, journal::FILE_NAME),
+        };
+        let (from, to) = (index_dir.join(from), index_dir.join(to));
+        if !from.exists() {
+            return Ok(None);
+        }
+        if to.exists() {
+            return Err(Error::Custom(format!(
+                "Both '{}' and '{}' exist; remove the one that is out of date first.",
+                from.display(),
+                to.display()
+            )));
+        }
+        let records = match self.settings.index_format {
+            IndexFormat::Json => {
+                let records = journal::read(&from)?;
+                fs::write(&to, serde_json::to_string_pretty(&records)?)?;
+                records
+            }
+            IndexFormat::Jsonl => {
+                let records: Vec<ArchivedRecord> =
+                    serde_json::from_str(&fs::read_to_string(&from)?).map_err(|source| {
+                        Error::CorruptArchiveLog {
+                            path: from.clone(),
+                            source,
+                        }
+                    })?;
+                journal::write(&to, &records)?;
+                records
+            }
+        };
+        fs::remove_file(&from)?;
+        info!(from = %from.display(), to = %to.display(), count = records.len(), "Converted archive log.");
+        Ok(Some(records.len()))
+    }
+
+    /// Rewrites the append-only archive log with only its current records, dropping the
+    /// history of removed and replaced ones. Returns the number of lines dropped; nothing is
+    /// done with the JSON format.
+    #[instrument(skip(self))]
+    pub fn compact_archive_log(&self) -> Result<usize> {
+        match self.journal_path()? {
+            Some(log_path) if log_path.exists() => journal::compact(&log_path),
+            _ => Ok(0),
+        }
+    }
+
+    /// Moves the archive logs left in the archive directory into `index_dir` the first time
+    /// a separate index directory is used, so no record is lost by the change.
+    fn relocate_archive_log(&self) -> Result<()> {
+        let index_dir = self.settings.index_dir();
+        let log_files = |dir: &Path| -> Result<Vec<PathBuf>> {
+            let mut files = sync::log_files(dir)?;
+            files.extend(Some(dir.join(journal::FILE_NAME)).filter(|path| path.is_file()));
+            Ok(files)
+        };
+        if index_dir == self.settings.archive_dir || !log_files(index_dir)?.is_empty() {
+            return Ok(());
+        }
+        let old_logs = log_files(&self.settings.archive_dir)?;
+        if old_logs.is_empty() {
+            return Ok(());
+        }
+        fs::create_dir_all(index_dir)?;
+        for old_log in old_logs {
+            let new_log = index_dir.join(old_log.file_name().unwrap_or_default());
+            info!(from = %old_log.display(), to = %new_log.display(), "Moving archive log to the index directory.");
+            // The index directory is often on another filesystem than the archive.
+            if fs::rename(&old_log, &new_log).is_err() {
+                fs::copy(&old_log, &new_log)?;
+                fs::remove_file(&old_log)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Summarizes the runs of the last complete `period` recorded in `history`, with the
+    /// archived projects the deletion policy will delete within the next one.
+    pub fn digest(&self, history: &[UsageSample], period: Period) -> Result<Digest> {
+        let policy = self.deletion_policy();
+        let now = Utc::now();
+        let upcoming = if policy.enabled {
+            self.get_archive_records()?
+                .into_iter()
+                .filter(|record| {
+                    !policy.allows_deletion(record, now)
+                        && policy.allows_deletion(record, now + period.length())
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Ok(digest::build(history, period, now.date_naive(), upcoming))
+    }
+
+    /// Measures the current disk usage of the projects directories and the archive, for the
+    /// usage history. `freed_bytes` is what the run that just ended freed.
+    pub fn usage_sample(&self, freed_bytes: u64) -> UsageSample {
+        let active_bytes = self
+            .settings
+            .projects_dirs
+            .iter()
+            .flat_map(|dir| {
+                // The archive may live inside a projects directory.
+                WalkDir::new(dir).into_iter().filter_entry(|entry| {
+                    !self
+                        .settings
+                        .archive_dirs()
+                        .any(|archive_dir| entry.path() == archive_dir)
+                })
+            })
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| entry.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        UsageSample {
+            at: Utc::now(),
+            active_bytes,
+            archive_bytes: self.settings.archive_dirs().map(dir_size).sum(),
+            freed_bytes,
+            archived_projects: 0,
+            deleted_projects: 0,
+        }
+    }
+
     pub fn get_archive_records(&self) -> Result<Vec<ArchivedRecord>> {
-        let log_path = self.settings.archive_dir.join(Self::ARCHIVE_LOG_FILE);
+        self.relocate_archive_log()?;
+        let index_dir = self.settings.index_dir();
+        if self.settings.sync_index {
+            return sync::read_merged(index_dir);
+        }
+        if let Some(log_path) = self.journal_path()? {
+            debug!(path = %log_path.display(), "Reading archive records.");
+            if !log_path.exists() {
+                warn!("Archive log file not found. Returning empty list.");
+                return Ok(Vec::new());
+            }
+            return journal::read(&log_path);
+        }
+        let log_path = index_dir.join(Self::ARCHIVE_LOG_FILE);
         debug!(path = %log_path.display(), "Reading archive records.");
         if !log_path.exists() {
             warn!("Archive log file not found. Returning empty list.");
             return Ok(Vec::new());
         }
-        let file_content = fs::read_to_string(log_path)?;
-        Ok(serde_json::from_str(&file_content)?)
+        let file_content = fs::read_to_string(&log_path)?;
+        serde_json::from_str(&file_content).map_err(|source| Error::CorruptArchiveLog {
+            path: log_path,
+            source,
+        })
     }
 }
+
+/// Returns the names of the `containers` with a bind mount into `path`, if there are any.
+fn mounting_containers(containers: &[RunningContainer], path: &Path) -> Option<Vec<String>> {
+    let names: Vec<_> = containers
+        .iter()
+        .filter(|container| container.mounts_from(path))
+        .map(|container| container.name.clone())
+        .collect();
+    (!names.is_empty()).then_some(names)
+}
+
+/// Total size in bytes of the files under `path`, skipping anything unreadable.
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Moves a directory, copying it when `to` is on another filesystem (e.g. an archive route
+/// on an external drive). A failed copy is removed, leaving `from` untouched.
+fn move_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            debug!(from = %from.display(), to = %to.display(), "Copying across filesystems.");
+            if to.exists() {
+                return Err(std::io::ErrorKind::AlreadyExists.into());
+            }
+            if let Err(e) = copy_tree(from, to) {
+                let _ = fs::remove_dir_all(to);
+                return Err(e);
+            }
+            fs::remove_dir_all(from)
+        }
+        result => result,
+    }
+}
+
+/// Copies a directory tree, keeping permissions and symbolic links.
+fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+    let mut dirs = Vec::new();
+    for entry in WalkDir::new(from) {
+        let entry = entry?;
+        let dest = to.join(entry.path().strip_prefix(from).unwrap_or(entry.path()));
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &dest)?;
+        } else if file_type.is_dir() {
+            fs::create_dir_all(&dest)?;
+            dirs.push((dest, entry.metadata()?.permissions()));
+        } else {
+            fs::copy(entry.path(), &dest)?;
+        }
+    }
+    // Directory permissions last, as read-only directories could not be filled.
+    for (dir, permissions) in dirs.into_iter().rev() {
+        fs::set_permissions(dir, permissions)?;
+    }
+    Ok(())
+}
+
+/// Matches `text` against a glob pattern, comparing literally if the pattern is invalid.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    glob::Pattern::new(pattern).map_or(pattern == text, |p| p.matches(text))
+}
@@ -0,0 +1,143 @@
+//! Migration of settings files written for older versions of the schema.
+//!
+//! Every change that renames, restructures or drops a key bumps `CURRENT_VERSION` and adds a
+//! step to `MIGRATIONS`. Files without a `version` key are version 1. A file is only rewritten
+//! when a step changed something, and the original is kept next to it as
+//! `settings.toml.v<N>.bak`.
+//!
+//! The only change of the schema so far is version 2, where `projects_dir` became the
+//! `projects_dirs` list. No key has been renamed, and the rule tables (`cleanup_rules`,
+//! `archive_routes`, `exclude`) have only gained optional keys or forms that older files
+//! still parse as, so they need no step. A future rename or rule format change adds one.
+use crate::config::Settings;
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+use tracing::{debug, info, warn};
+
+/// Version of the settings schema written by this build.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// A step from version `from` to `from + 1`. `apply` returns whether it changed the table.
+struct Migration {
+    from: u32,
+    description: &'static str,
+    apply: fn(&mut Table) -> bool,
+}
+
+const MIGRATIONS: [Migration; 1] = [Migration {
+    from: 1,
+    description: "'projects_dir' became the 'projects_dirs' list",
+    apply: projects_dir_to_list,
+}];
+
+fn projects_dir_to_list(table: &mut Table) -> bool {
+    let Some(dir) = table.remove("projects_dir") else {
+        return false;
+    };
+    let mut dirs = match table.remove("projects_dirs") {
+        Some(Value::Array(dirs)) => dirs,
+        _ => Vec::new(),
+    };
+    if !dirs.contains(&dir) {
+        dirs.insert(0, dir);
+    }
+    table.insert("projects_dirs".to_string(), Value::Array(dirs));
+    true
+}
+
+/// What loading a settings file changed in it.
+#[derive(Debug, Clone, Default)]
+pub struct MigrationReport {
+    /// Schema version the file was written for.
+    pub from_version: u32,
+    /// Descriptions of the steps that changed the file.
+    pub applied: Vec<String>,
+    /// Where the original file was saved, when it was rewritten.
+    pub backup: Option<PathBuf>,
+    /// Top-level keys unknown to the current schema. They are left in the file but ignored.
+    pub unknown_keys: Vec<String>,
+}
+
+/// Upgrades a parsed settings file to the current schema in place.
+pub fn migrate_table(table: &mut Table) -> MigrationReport {
+    let from_version = table
+        .get("version")
+        .and_then(Value::as_integer)
+        .map_or(1, |version| version as u32);
+    let mut report = MigrationReport {
+        from_version,
+        ..Default::default()
+    };
+
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= from_version) {
+        if (migration.apply)(table) {
+            debug!(
+                from = migration.from,
+                step = migration.description,
+                "Applied settings migration."
+            );
+            report.applied.push(migration.description.to_string());
+        }
+    }
+    if !report.applied.is_empty() {
+        table.insert(
+            "version".to_string(),
+            Value::Integer(CURRENT_VERSION.into()),
+        );
+    }
+
+    // `serde_json` keeps `None` fields as `null`, so every key of the schema is listed.
+    let known = serde_json::to_value(Settings::default()).unwrap_or_default();
+    report.unknown_keys = table
+        .keys()
+        .filter(|key| known.get(key.as_str()).is_none())
+        .cloned()
+        .collect();
+    report
+}
+
+/// Upgrades the settings file at `path` to the current schema, backing up the original when
+/// it has to be rewritten.
+pub fn migrate_file(path: &Path) -> Result<MigrationReport> {
+    let content = fs::read_to_string(path)?;
+    let mut table: Table = content.parse().map_err(|e| {
+        Error::Config(config::ConfigError::Message(format!(
+            "invalid settings file '{}': {}",
+            path.display(),
+            e
+        )))
+    })?;
+    let mut report = migrate_table(&mut table);
+
+    if report.from_version > CURRENT_VERSION {
+        warn!(
+            path = %path.display(),
+            version = report.from_version,
+            "Settings file is for a newer version of archiver; unknown keys are ignored."
+        );
+    }
+    for key in &report.unknown_keys {
+        warn!(path = %path.display(), %key, "Ignoring unknown settings key.");
+    }
+    if report.applied.is_empty() {
+        return Ok(report);
+    }
+
+    let mut backup_name = path.file_name().unwrap_or_default().to_os_string();
+    backup_name.push(format!(".v{}.bak", report.from_version));
+    let backup = path.with_file_name(backup_name);
+    fs::copy(path, &backup)?;
+    let migrated = toml::to_string_pretty(&table).map_err(|e| Error::Custom(e.to_string()))?;
+    fs::write(path, migrated)?;
+    info!(
+        path = %path.display(),
+        backup = %backup.display(),
+        from = report.from_version,
+        to = CURRENT_VERSION,
+        "Migrated settings file."
+    );
+    report.backup = Some(backup);
+    Ok(report)
+}
@@ -1,18 +1,293 @@
-use std::path::PathBuf;
+use crate::error::{Error, Result};
+use crate::stats::CodeStats;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use walkdir::WalkDir;
+
+/// Name of a project: a single, non-empty path component. Names end up joined onto the
+/// projects and archive directories, so separators, `.` and `..` are rejected, whether the
+/// name comes from a scan, the command line or the archive log.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ProjectName(String);
+
+impl ProjectName {
+    pub fn new(name: impl Into<String>) -> Result<Self> {
+        let name = name.into();
+        let mut components = Path::new(&name).components();
+        let single_component =
+            matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none();
+        if !single_component || name.contains(['/', '\\', '\0']) {
+            return Err(Error::InvalidProjectName(name));
+        }
+        Ok(Self(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Whether `path` is a non-empty relative path made only of plain components, i.e. it cannot
+/// escape the directory it is joined onto.
+pub fn is_confined_relative_path(path: &Path) -> bool {
+    path.components().next().is_some()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+impl TryFrom<String> for ProjectName {
+    type Error = Error;
+
+    fn try_from(name: String) -> Result<Self> {
+        Self::new(name)
+    }
+}
+
+impl FromStr for ProjectName {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        Self::new(name)
+    }
+}
+
+impl From<ProjectName> for String {
+    fn from(name: ProjectName) -> Self {
+        name.0
+    }
+}
+
+impl AsRef<Path> for ProjectName {
+    fn as_ref(&self) -> &Path {
+        Path::new(&self.0)
+    }
+}
+
+impl fmt::Display for ProjectName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialEq<str> for ProjectName {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for ProjectName {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScannedProject {
-    pub name: String,
+    pub name: ProjectName,
     pub path: PathBuf,
-    pub last_activity: DateTime<Utc>
+    pub last_activity: DateTime<Utc>,
+    /// Set when the file time scan stopped early, so `last_activity` may be too old.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scan_limit: Option<ScanLimit>,
+}
+
+/// The limit that stopped the file time scan of a project (`mtime_scan_max_files`,
+/// `mtime_scan_timeout_secs`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanLimit {
+    /// The number of files looked at.
+    MaxFiles(u64),
+    /// The seconds spent.
+    Timeout(u64),
+}
+
+impl fmt::Display for ScanLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MaxFiles(files) => write!(f, "activity scan stopped after {} files", files),
+            Self::Timeout(secs) => write!(f, "activity scan stopped after {} seconds", secs),
+        }
+    }
+}
+
+/// A project that is still active but will cross the inactivity threshold soon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpcomingCandidate {
+    pub project: ScannedProject,
+    /// When the project becomes a candidate for archiving, unless there is new activity.
+    pub candidate_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchivedRecord {
-    pub name: String,
+    pub name: ProjectName,
     pub original_path: PathBuf,
     pub archive_path: PathBuf,
+    /// Location relative to the projects directory, kept for nested (org-style) layouts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_path: Option<PathBuf>,
     pub archived_at: DateTime<Utc>,
-}
\ No newline at end of file
+    /// Machine that archived the project, set when the archive index is shared between machines.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine: Option<String>,
+    /// Free-form labels given when archiving (e.g. `client-x`), used to restore cohorts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Lines of code per language at archive time, when `collect_code_stats` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_stats: Option<CodeStats>,
+    /// Commits dropped from the git history before archiving, when `trim_history` removed any.
+    /// `git fetch --unshallow` restores them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trimmed_commits: Option<usize>,
+    /// Group of the archive the project belongs to (e.g. `work`), which is also the
+    /// subdirectory of the archive it is stored in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Name of the archive route holding the project, or none for `archive_dir`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    /// Files and bytes of the project as archived, checked again when it is restored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<Manifest>,
+}
+
+impl ArchivedRecord {
+    /// Name shown to users: the relative path for nested layouts, the project name otherwise.
+    pub fn label(&self) -> String {
+        self.relative_path.as_ref().map_or_else(
+            || self.name.to_string(),
+            |p| p.to_string_lossy().into_owned(),
+        )
+    }
+}
+
+/// Number of files (symlinks included), total size and content digest of a project tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub files: u64,
+    pub bytes: u64,
+    /// SHA-256 of the relative path, size and SHA-256 of the content of every file (the target
+    /// of every symlink), in path order. Missing from manifests written before digests were.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+}
+
+impl Manifest {
+    /// Counts and hashes the files under `path`. Entries that cannot be read are left out,
+    /// so they show up as a mismatch.
+    pub fn of(path: &Path) -> Self {
+        let mut entries: Vec<(PathBuf, u64, [u8; 32])> = Vec::new();
+        for entry in WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+        {
+            let content = if entry.file_type().is_file() {
+                fs::File::open(entry.path()).and_then(|mut file| {
+                    let mut hasher = Sha256::new();
+                    io::copy(&mut file, &mut hasher)?;
+                    Ok(hasher.finalize().into())
+                })
+            } else if entry.file_type().is_symlink() {
+                fs::read_link(entry.path())
+                    .map(|target| Sha256::digest(target.as_os_str().as_encoded_bytes()).into())
+            } else {
+                continue;
+            };
+            let (Ok(content), Ok(relative)) = (content, entry.path().strip_prefix(path)) else {
+                continue;
+            };
+            let size = if entry.file_type().is_file() {
+                entry.metadata().map_or(0, |metadata| metadata.len())
+            } else {
+                0
+            };
+            entries.push((relative.to_path_buf(), size, content));
+        }
+
+        let mut digest = Sha256::new();
+        for (relative, size, content) in &entries {
+            digest.update(relative.as_os_str().as_encoded_bytes());
+            digest.update([0]);
+            digest.update(size.to_le_bytes());
+            digest.update(content);
+        }
+        Self {
+            files: entries.len() as u64,
+            bytes: entries.iter().map(|(_, size, _)| size).sum(),
+            digest: Some(format!("{:x}", digest.finalize())),
+        }
+    }
+
+    /// Whether `found` has the files of this manifest: the same digest, or the same counts
+    /// for manifests without one.
+    pub fn matches(&self, found: &Manifest) -> bool {
+        self.files == found.files
+            && self.bytes == found.bytes
+            && self
+                .digest
+                .as_ref()
+                .is_none_or(|_| self.digest == found.digest)
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} files, {} bytes", self.files, self.bytes)?;
+        if let Some(digest) = &self.digest {
+            write!(f, ", content {}", &digest[..digest.len().min(12)])?;
+        }
+        Ok(())
+    }
+}
+
+/// What restoring an archived project would do, computed without touching the filesystem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorePreview {
+    pub record: ArchivedRecord,
+    /// Whether something already exists at the original path, which would make the restore fail.
+    pub destination_exists: bool,
+    /// Total size in bytes of the files in the archived project.
+    pub size_bytes: u64,
+}
+
+/// Selects archived records for bulk restores. Every criterion that is set must match.
+#[derive(Debug, Clone, Default)]
+pub struct RestoreFilter {
+    /// Only records archived at or after this instant.
+    pub archived_after: Option<DateTime<Utc>>,
+    /// Only records archived strictly before this instant.
+    pub archived_before: Option<DateTime<Utc>>,
+    /// Only records carrying this tag.
+    pub tag: Option<String>,
+    /// Only records of this group.
+    pub group: Option<String>,
+}
+
+impl RestoreFilter {
+    pub fn matches(&self, record: &ArchivedRecord) -> bool {
+        self.archived_after
+            .is_none_or(|after| record.archived_at >= after)
+            && self
+                .archived_before
+                .is_none_or(|before| record.archived_at < before)
+            && self
+                .tag
+                .as_ref()
+                .is_none_or(|tag| record.tags.contains(tag))
+            && self
+                .group
+                .as_ref()
+                .is_none_or(|group| record.group.as_ref() == Some(group))
+    }
+}
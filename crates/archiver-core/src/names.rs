@@ -0,0 +1,49 @@
+//! Matching the project names the user types against the archived ones: loosely, ignoring
+//! case and Unicode normalization (a name typed as `e` + combining accent matches a stored
+//! `é`), and by similarity, to suggest the name meant after a typo.
+use unicode_normalization::UnicodeNormalization;
+
+/// Similarity (0 to 1) from which an archived name is suggested.
+const MIN_SIMILARITY: f64 = 0.7;
+
+/// Most names suggested at once.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// `name` in NFKC form and lowercase, the form loose matching compares.
+pub fn normalize(name: &str) -> String {
+    name.nfkc().flat_map(char::to_lowercase).collect()
+}
+
+/// Whether `a` and `b` differ at most in case and Unicode normalization.
+pub fn loosely_equal(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// The `names` closest to `key`, most similar first, for "did you mean" hints.
+pub fn suggestions<'a>(key: &str, names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let key = normalize(key);
+    let mut scored: Vec<(f64, &str)> = names
+        .into_iter()
+        .map(|name| {
+            let normalized = normalize(name);
+            let mut score = strsim::normalized_damerau_levenshtein(&key, &normalized);
+            // A name typed partly, e.g. `archiver` for `archiver-core`, is also a near match.
+            if key.chars().count() >= 3 && normalized.contains(&key) {
+                score = score.max(MIN_SIMILARITY);
+            }
+            (score, name)
+        })
+        .filter(|(score, _)| *score >= MIN_SIMILARITY)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+    let mut suggested: Vec<String> = Vec::new();
+    for (_, name) in scored {
+        if !suggested.iter().any(|s| s == name) {
+            suggested.push(name.to_string());
+        }
+        if suggested.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+    suggested
+}
@@ -0,0 +1,32 @@
+//! Plans saved to a file, so a run can be reviewed (or approved by someone else) before it is
+//! applied, possibly days later (`archiver plan --out`, `archiver apply`).
+use crate::error::Result;
+use crate::models::{ArchivedRecord, ScannedProject};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Everything a run would do, as it was planned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedPlan {
+    pub created_at: DateTime<Utc>,
+    /// Projects to archive, with the last activity they had when planned.
+    pub archive: Vec<ScannedProject>,
+    /// Archived projects to delete, as they were recorded when planned.
+    pub delete: Vec<ArchivedRecord>,
+    /// Tags given to the projects archived.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl SavedPlan {
+    pub fn read(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! Retention policy deciding when archived projects may be deleted automatically.
+//!
+//! The configured numbers are not trusted as is: `days_before_delete` is raised to at least
+//! `delete_safety_multiplier` times `inactivity_days`, so a project can never be deleted
+//! shortly after being archived because of a typo in the configuration.
+use crate::config::Settings;
+use crate::models::ArchivedRecord;
+use chrono::{DateTime, Duration, Utc};
+use tracing::warn;
+
+/// Effective deletion policy computed from the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeletionPolicy {
+    /// Whether automatic deletion is enabled at all.
+    pub enabled: bool,
+    /// Minimum number of days a project must stay in the archive before it may be deleted.
+    pub min_archived_days: u64,
+}
+
+impl DeletionPolicy {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let floor = settings
+            .inactivity_days
+            .saturating_mul(settings.delete_safety_multiplier);
+        if settings.days_before_delete < floor {
+            warn!(
+                days_before_delete = settings.days_before_delete,
+                enforced = floor,
+                "days_before_delete is below the safety floor; using the floor instead."
+            );
+        }
+
+        Self {
+            enabled: settings.enable_auto_delete,
+            min_archived_days: settings.days_before_delete.max(floor),
+        }
+    }
+
+    /// Whether `record` may be deleted at `now`. The project must have stayed archived for the
+    /// full period: restoring it drops the record and re-archiving starts the clock again, and a
+    /// project whose original path exists again (e.g. copied back by hand) is never deleted.
+    /// A period too long to represent (e.g. `days_before_delete` set to mean "never") is never
+    /// over.
+    pub fn allows_deletion(&self, record: &ArchivedRecord, now: DateTime<Utc>) -> bool {
+        let Some(min_age) = i64::try_from(self.min_archived_days)
+            .ok()
+            .and_then(Duration::try_days)
+        else {
+            return false;
+        };
+        self.enabled
+            && now.signed_duration_since(record.archived_at) >= min_age
+            && record.archive_path.exists()
+            && !record.original_path.exists()
+    }
+}
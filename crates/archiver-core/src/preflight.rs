@@ -0,0 +1,59 @@
+//! Permission checks run before a project is archived, so that a project the current user
+//! cannot fully move (e.g. with root-owned build outputs on a shared machine) is skipped up
+//! front instead of failing halfway through the move.
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Whether the current user has `mode` access to `path`, as the kernel decides it (ACLs and
+/// capabilities included).
+fn accessible(path: &Path, mode: libc::c_int) -> bool {
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: `c_path` is a valid NUL-terminated string that outlives the call.
+    unsafe { libc::access(c_path.as_ptr(), mode) == 0 }
+}
+
+/// Paths that keep the current user from archiving `project`: a parent it cannot rename the
+/// project in, files it cannot read, directories it cannot list, and, for the `cleanup`
+/// folders removed beforehand, directories it cannot empty. Empty when the project can be
+/// archived.
+pub fn blocked_paths(project: &Path, cleanup: &[PathBuf]) -> Vec<PathBuf> {
+    const LIST: libc::c_int = libc::R_OK | libc::X_OK;
+    const MODIFY: libc::c_int = libc::R_OK | libc::W_OK | libc::X_OK;
+
+    let mut blocked = Vec::new();
+    let parents = project
+        .parent()
+        .into_iter()
+        .chain(cleanup.iter().filter_map(|target| target.parent()));
+    for parent in parents {
+        if !accessible(parent, libc::W_OK | libc::X_OK) && !blocked.iter().any(|p| p == parent) {
+            blocked.push(parent.to_path_buf());
+        }
+    }
+
+    for entry in WalkDir::new(project).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let removed = cleanup.iter().any(|target| path.starts_with(target));
+        let mode = if entry.file_type().is_dir() {
+            // Moving a directory to another parent rewrites its `..` entry.
+            if removed || entry.depth() == 0 {
+                MODIFY
+            } else {
+                LIST
+            }
+        } else if entry.file_type().is_file() && !removed {
+            libc::R_OK
+        } else {
+            // Symlinks are moved as they are; removed files only need their directory.
+            continue;
+        };
+        if !accessible(path, mode) {
+            blocked.push(path.to_path_buf());
+        }
+    }
+    blocked
+}
@@ -0,0 +1,121 @@
+//! Activity probes: commands that report the last activity of a kind of project, for
+//! workflows that neither the git history nor file times describe (e.g. a LaTeX thesis
+//! rebuilt by a cron job, or a data project whose inputs are refreshed by a pipeline).
+//!
+//! ```toml
+//! [[activity_probes]]
+//! detection_file = "*.tex"
+//! # Newest PDF built, in Unix seconds.
+//! command = "stat -c %Y build/*.pdf | sort -n | tail -n 1"
+//! ```
+use crate::error::{Error, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+/// A command giving the last activity of the projects it applies to.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ActivityProbe {
+    /// File at the root of the projects the probe applies to, or a glob of one (e.g. `*.tex`).
+    pub detection_file: String,
+    /// Shell command run in the project directory (`sh -c`). Its last line of output is the
+    /// last activity: an RFC 3339 timestamp, a `YYYY-MM-DD` date or Unix seconds.
+    pub command: String,
+    /// Seconds after which the command is stopped and the usual activity checks are used.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl ActivityProbe {
+    /// Whether the project at `path` has a file matching `detection_file` at its root.
+    pub fn matches(&self, path: &Path) -> bool {
+        if path.join(&self.detection_file).exists() {
+            return true;
+        }
+        let Ok(pattern) = glob::Pattern::new(&self.detection_file) else {
+            return false;
+        };
+        fs::read_dir(path).is_ok_and(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .any(|entry| pattern.matches(&entry.file_name().to_string_lossy()))
+        })
+    }
+
+    /// Runs the command in `path` and parses the activity it prints.
+    pub fn run(&self, path: &Path) -> Result<DateTime<Utc>> {
+        let failed = |message: String| {
+            Error::Custom(format!(
+                "Activity probe '{}' failed: {}",
+                self.command, message
+            ))
+        };
+        debug!(command = %self.command, path = %path.display(), "Running activity probe.");
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .current_dir(path)
+            .env("ARCHIVER_PROJECT", path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        // Read the output while waiting, so a command printing a lot cannot block on a full pipe.
+        let Some(mut stdout) = child.stdout.take() else {
+            return Err(failed("could not read its output".to_string()));
+        };
+        let reader = thread::spawn(move || {
+            let mut output = String::new();
+            stdout.read_to_string(&mut output).map(|_| output)
+        });
+        let deadline = Instant::now() + Duration::from_secs(self.timeout_secs);
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(failed(format!(
+                    "still running after {} seconds",
+                    self.timeout_secs
+                )));
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        let output = reader
+            .join()
+            .map_err(|_| failed("could not read its output".to_string()))??;
+        if !status.success() {
+            return Err(failed(status.to_string()));
+        }
+        let line = output
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .ok_or_else(|| failed("it printed nothing".to_string()))?;
+        parse_activity(line).ok_or_else(|| failed(format!("unexpected output '{}'", line)))
+    }
+}
+
+/// Parses an RFC 3339 timestamp, a `YYYY-MM-DD` date (midnight UTC) or Unix seconds.
+fn parse_activity(text: &str) -> Option<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(text) {
+        return Some(at.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+    let seconds: f64 = text.parse().ok()?;
+    DateTime::from_timestamp(seconds as i64, 0)
+}
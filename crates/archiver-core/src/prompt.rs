@@ -0,0 +1,88 @@
+//! A cached count of the projects waiting to be archived, cheap enough to read from a shell
+//! prompt on every command (`archiver prompt-status`).
+//!
+//! While a background refresh counts, it holds a marker file with its start time, so the
+//! prompts drawn meanwhile do not start more of them.
+use crate::error::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+
+/// Minutes after which a refresh marker is taken to be left over from a refresh that died.
+const REFRESH_TIMEOUT_MINUTES: i64 = 15;
+
+/// Archive candidates as counted at some point.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptStatus {
+    pub computed_at: DateTime<Utc>,
+    /// Inactive projects a run would archive.
+    pub candidates: usize,
+}
+
+impl PromptStatus {
+    pub fn new(candidates: usize) -> Self {
+        Self {
+            computed_at: Utc::now(),
+            candidates,
+        }
+    }
+
+    /// Reads the status cached at `path`, if there is one.
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Whether the count is recent enough to be shown without counting again.
+    pub fn is_fresh(&self, max_age: Duration, now: DateTime<Utc>) -> bool {
+        now.signed_duration_since(self.computed_at) < max_age
+    }
+}
+
+/// Claims the refresh marker at `path` for a new refresh at `now`. Returns `false` while
+/// another refresh holds it.
+pub fn claim_refresh(path: &Path, now: DateTime<Utc>) -> Result<bool> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(mut file) => {
+            file.write_all(now.to_rfc3339().as_bytes())?;
+            return Ok(true);
+        }
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(e.into()),
+    }
+    let started = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| DateTime::parse_from_rfc3339(content.trim()).ok());
+    let abandoned = started.is_none_or(|started| {
+        now.signed_duration_since(started) >= Duration::minutes(REFRESH_TIMEOUT_MINUTES)
+    });
+    if abandoned {
+        fs::write(path, now.to_rfc3339())?;
+    }
+    Ok(abandoned)
+}
+
+/// Releases the refresh marker at `path` once the refresh is over, whether it succeeded.
+pub fn release_refresh(path: &Path) {
+    if let Err(e) = fs::remove_file(path) {
+        if e.kind() != ErrorKind::NotFound {
+            tracing::warn!(path = %path.display(), error = %e, "Could not remove the prompt refresh marker.");
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Scanning and archiving projects on other machines over SSH.
+//!
+//! Nothing needs to be installed on the remote machine besides a POSIX shell, `git` and GNU
+//! `find`: a small script enumerates its projects and their last activity, and archiving is a
+//! `mv` into its archive directory followed by the usual metadata sidecar. The connection is
+//! whatever `ssh <host>` does, so keys, agents and `~/.ssh/config` aliases all apply.
+use crate::detect::ProjectType;
+use crate::error::{Error, Result};
+use crate::models::{ArchivedRecord, ProjectName, ScannedProject};
+use crate::sidecar;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tracing::{debug, warn};
+
+/// A machine whose projects are scanned and archived over SSH.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RemoteHost {
+    /// Name used on the command line (`archiver remote run <name>`).
+    pub name: String,
+    /// SSH destination: `user@host`, `host` or an alias from `~/.ssh/config`.
+    pub host: String,
+    /// Projects directories on the remote machine. A leading `~/` is its home directory.
+    pub projects_dirs: Vec<PathBuf>,
+    /// Archive directory on the remote machine.
+    pub archive_dir: PathBuf,
+}
+
+/// A project found on a remote machine.
+#[derive(Debug, Clone)]
+pub struct RemoteProject {
+    pub project: ScannedProject,
+    /// Type of the project, from the manifest files found in it.
+    pub kind: Option<ProjectType>,
+}
+
+/// Quotes a remote path for the shell, keeping a leading `~/` expandable.
+fn quote_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("\"$HOME\"/{}", quote(rest)),
+        None => quote(&path),
+    }
+}
+
+/// Single-quotes a string for a POSIX shell.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+impl RemoteHost {
+    /// Runs `script` on the remote machine with `program` (usually `ssh`), feeding it `input`,
+    /// and returns its standard output.
+    fn run(&self, program: &str, script: &str, input: Option<&str>) -> Result<String> {
+        debug!(host = %self.host, %script, "Running remote command.");
+        let remote_error = |message: String| Error::Remote {
+            host: self.host.clone(),
+            message,
+        };
+        // `--` keeps a host starting with `-` from being read as an option of ssh.
+        let mut child = Command::new(program)
+            .arg("--")
+            .arg(&self.host)
+            .arg(script)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| remote_error(format!("could not run '{}': {}", program, e)))?;
+        if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+            // A command that fails early closes its input: its exit status tells why.
+            let _ = stdin.write_all(input.as_bytes());
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(remote_error(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Lists the projects of the remote machine with their last activity (the newest commit
+    /// across branches for repositories, the newest file otherwise) and type. Like the local
+    /// scan, it skips the archive directory, by path and by name, when it is inside a projects
+    /// directory.
+    pub fn scan(&self, program: &str) -> Result<Vec<RemoteProject>> {
+        let roots: Vec<String> = self.projects_dirs.iter().map(|d| quote_path(d)).collect();
+        let manifests: Vec<String> = ProjectType::manifests().map(quote).collect();
+        let archive_name = self
+            .archive_dir
+            .file_name()
+            .map(|name| quote(&name.to_string_lossy()))
+            .unwrap_or_else(|| "''".to_string());
+        let script = format!(
+            r#"archive={}
+archive_name={}
+for root in {}; do
+  for dir in "$root"/*/; do
+    [ -d "$dir" ] || continue
+    dir=${{dir%/}}
+    [ "$dir" -ef "$archive" ] && continue
+    [ "${{dir##*/}}" = "$archive_name" ] && continue
+    time=
+    [ -d "$dir/.git" ] && time=$(git -C "$dir" log -1 --all --format=%ct 2>/dev/null)
+    [ -n "$time" ] || time=$(find "$dir" -type f -not -path '*/.git/*' -printf '%T@\n' 2>/dev/null | sort -n | tail -n 1)
+    [ -n "$time" ] || time=$(stat -c %Y "$dir")
+    found=
+    for manifest in {}; do
+      [ -f "$dir/$manifest" ] && found="$found $manifest"
+    done
+    printf '%s\t%s\t%s\n' "${{time%.*}}" "$found" "$dir"
+  done
+done"#,
+            quote_path(&self.archive_dir),
+            archive_name,
+            roots.join(" "),
+            manifests.join(" ")
+        );
+        let output = self.run(program, &script, None)?;
+        Ok(output.lines().filter_map(parse_scan_line).collect())
+    }
+
+    /// Moves a project into the archive directory of the remote machine and writes its
+    /// metadata sidecar there. Fails without moving anything if the destination exists.
+    pub fn archive(
+        &self,
+        program: &str,
+        project: &ScannedProject,
+        tags: &[String],
+    ) -> Result<ArchivedRecord> {
+        let archive_path = self.archive_dir.join(project.name.as_str());
+        let record = ArchivedRecord {
+            name: project.name.clone(),
+            original_path: project.path.clone(),
+            archive_path: archive_path.clone(),
+            relative_path: None,
+            archived_at: Utc::now(),
+            machine: Some(self.name.clone()),
+            tags: tags.to_vec(),
+            code_stats: None,
+            trimmed_commits: None,
+            manifest: None,
+            group: None,
+            destination: None,
+        };
+        let script = format!(
+            "mkdir -p {archive_dir} && if [ -e {dest} ]; then echo 'destination already exists' >&2; exit 1; fi && mv {src} {dest} && cat > {sidecar}",
+            archive_dir = quote_path(&self.archive_dir),
+            src = quote_path(&project.path),
+            dest = quote_path(&archive_path),
+            sidecar = quote_path(&sidecar::path_for(&archive_path)),
+        );
+        self.run(
+            program,
+            &script,
+            Some(&serde_json::to_string_pretty(&record)?),
+        )?;
+        Ok(record)
+    }
+}
+
+/// Parses a `<unix time>\t<manifest files>\t<path>` line of the scan script.
+fn parse_scan_line(line: &str) -> Option<RemoteProject> {
+    let mut fields = line.splitn(3, '\t');
+    let (time, manifests, path) = (fields.next()?, fields.next()?, fields.next()?);
+    let path = PathBuf::from(path);
+    let name = path.file_name()?.to_string_lossy().into_owned();
+    let parsed = time
+        .parse()
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0));
+    match (ProjectName::new(name), parsed) {
+        (Ok(name), Some(last_activity)) => Some(RemoteProject {
+            project: ScannedProject {
+                name,
+                path,
+                last_activity,
+                scan_limit: None,
+            },
+            kind: ProjectType::from_manifests(|file| {
+                manifests.split_whitespace().any(|found| found == file)
+            }),
+        }),
+        _ => {
+            warn!(%line, "Skipping unreadable line from the remote scan.");
+            None
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! How often each project was restored from the archive.
+//!
+//! A project archived and restored again and again is one the archiving policy keeps fighting
+//! the user on, and a candidate for an exclusion. Counts are kept per original path in
+//! `restores.json`, next to the archive log.
+use crate::error::Result;
+use crate::models::{ArchivedRecord, ProjectName};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Name of the file holding the counts, in the index directory.
+pub const FILE_NAME: &str = "restores.json";
+
+/// Number of times a project was restored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestoreCount {
+    pub name: ProjectName,
+    /// Where the project lives when it is not archived.
+    pub path: PathBuf,
+    pub count: u32,
+    pub last_restored_at: DateTime<Utc>,
+}
+
+/// Reads the counts at `path`. A missing file means nothing was restored yet.
+pub fn read(path: &Path) -> Result<Vec<RestoreCount>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Counts one more restore of each of `restored` in the file at `path`.
+pub fn record(path: &Path, restored: &[ArchivedRecord]) -> Result<()> {
+    if restored.is_empty() {
+        return Ok(());
+    }
+    let mut counts = read(path)?;
+    let now = Utc::now();
+    for record in restored {
+        match counts
+            .iter_mut()
+            .find(|count| count.path == record.original_path)
+        {
+            Some(count) => {
+                count.name = record.name.clone();
+                count.count += 1;
+                count.last_restored_at = now;
+            }
+            None => counts.push(RestoreCount {
+                name: record.name.clone(),
+                path: record.original_path.clone(),
+                count: 1,
+                last_restored_at: now,
+            }),
+        }
+    }
+    fs::write(path, serde_json::to_string_pretty(&counts)?)?;
+    Ok(())
+}
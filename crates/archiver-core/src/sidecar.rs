@@ -0,0 +1,64 @@
+//! Per-entry metadata files kept next to each archived project.
+//!
+//! Every archived project `<name>` gets a `<name>.meta.json` sibling holding its record, so
+//! the archive stays self-describing and the index can be rebuilt if the archive log is lost.
+use crate::error::Result;
+use crate::models::ArchivedRecord;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+/// Suffix of metadata files, appended to the project's directory name.
+pub const EXTENSION: &str = ".meta.json";
+
+/// Path of the metadata file of the project archived at `archive_path`.
+pub fn path_for(archive_path: &Path) -> PathBuf {
+    let mut name = archive_path.file_name().unwrap_or_default().to_os_string();
+    name.push(EXTENSION);
+    archive_path.with_file_name(name)
+}
+
+/// Writes (or replaces) the metadata file of `record`.
+pub fn write(record: &ArchivedRecord) -> Result<()> {
+    let path = path_for(&record.archive_path);
+    debug!(path = %path.display(), "Writing metadata sidecar.");
+    fs::write(path, serde_json::to_string_pretty(record)?)?;
+    Ok(())
+}
+
+/// Removes the metadata file of the project archived at `archive_path`, if there is one.
+pub fn remove(archive_path: &Path) -> Result<()> {
+    match fs::remove_file(path_for(archive_path)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Reads every metadata file under `archive_dir`, at any depth. Files that cannot be parsed
+/// are logged and skipped.
+pub fn read_all(archive_dir: &Path) -> Result<Vec<ArchivedRecord>> {
+    let mut records = Vec::new();
+    // Archived projects themselves (directories with a sidecar) are not descended into.
+    let walker = WalkDir::new(archive_dir)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| !entry.file_type().is_dir() || !path_for(entry.path()).is_file());
+    for entry in walker {
+        let entry = entry?;
+        let is_sidecar =
+            entry.file_type().is_file() && entry.file_name().to_string_lossy().ends_with(EXTENSION);
+        if !is_sidecar {
+            continue;
+        }
+        let content = fs::read_to_string(entry.path())?;
+        match serde_json::from_str(&content) {
+            Ok(record) => records.push(record),
+            Err(e) => {
+                warn!(path = %entry.path().display(), error = %e, "Skipping unreadable metadata sidecar.")
+            }
+        }
+    }
+    Ok(records)
+}
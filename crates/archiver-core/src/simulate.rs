@@ -0,0 +1,193 @@
+//! Generation of synthetic project trees, to benchmark scanning and archiving at scale and to
+//! reproduce scaling bugs without access to someone's real projects directory.
+//!
+//! Trees are deterministic for a given seed: the same spec always produces the same projects,
+//! file sizes and Git histories (commit dates are relative to the generation time).
+use crate::error::Result;
+use chrono::{Duration, Utc};
+use git2::{Repository, Signature, Time};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, instrument};
+
+/// What to generate.
+#[derive(Debug, Clone, Copy)]
+pub struct SimulationSpec {
+    /// Number of projects.
+    pub projects: usize,
+    /// Upper bound for the total size of the generated files, in bytes.
+    pub max_size: u64,
+    /// Seed of the pseudo-random generator.
+    pub seed: u64,
+}
+
+impl Default for SimulationSpec {
+    fn default() -> Self {
+        Self {
+            projects: 100,
+            max_size: 64 * 1024 * 1024,
+            seed: 42,
+        }
+    }
+}
+
+/// Kinds of generated projects, each with the files typical of its ecosystem.
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Rust,
+    Node,
+    Python,
+    /// A directory that is not a Git repository, judged by file modification times.
+    Plain,
+}
+
+/// Small xorshift generator: the simulation only needs reproducible variety, not quality.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..bound`.
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound.max(1)
+    }
+}
+
+/// Generates `spec.projects` projects directly under `projects_dir` and returns their paths.
+#[instrument(skip(projects_dir), fields(dir = %projects_dir.display()))]
+pub fn generate(projects_dir: &Path, spec: &SimulationSpec) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(projects_dir)?;
+    let mut rng = Rng::new(spec.seed);
+    let budget_per_project = spec.max_size / spec.projects.max(1) as u64;
+    let now = Utc::now();
+    let mut size_left = spec.max_size;
+
+    let mut paths = Vec::with_capacity(spec.projects);
+    for index in 0..spec.projects {
+        let kind = match rng.below(10) {
+            0..=3 => Kind::Rust,
+            4..=6 => Kind::Node,
+            7..=8 => Kind::Python,
+            _ => Kind::Plain,
+        };
+        let path = projects_dir.join(format!("project-{:05}", index));
+        // From half to one and a half times the average, never beyond the total budget.
+        let size = (budget_per_project / 2 + rng.below(budget_per_project)).min(size_left);
+        size_left -= size;
+        debug!(path = %path.display(), ?kind, size, "Generating project.");
+        write_sources(&path, kind, size)?;
+
+        if !matches!(kind, Kind::Plain) {
+            // Last activity spread over the past three years, a third of it recent.
+            let age_days = if rng.below(3) == 0 {
+                rng.below(30)
+            } else {
+                30 + rng.below(3 * 365)
+            };
+            let commits = 1 + rng.below(20) as usize;
+            let last_commit = now - Duration::days(age_days as i64);
+            commit_history(&path, commits, last_commit.timestamp(), &mut rng)?;
+        }
+        paths.push(path);
+    }
+    info!(count = paths.len(), "Generated synthetic projects.");
+    Ok(paths)
+}
+
+fn write_sources(path: &Path, kind: Kind, size: u64) -> Result<()> {
+    let (manifest, source, bulk_dir) = match kind {
+        Kind::Rust => (
+            (
+                "Cargo.toml",
+                "[package]\nname = \"sim\"\nversion = \"0.1.0\"\n",
+            ),
+            ("src/main.rs", "fn main() {\n    println!(\"hello\");\n}\n"),
+            "target/debug",
+        ),
+        Kind::Node => (
+            (
+                "package.json",
+                "{ \"name\": \"sim\", \"version\": \"1.0.0\" }\n",
+            ),
+            ("index.js", "console.log('hello');\n"),
+            "node_modules/dep",
+        ),
+        Kind::Python => (
+            ("pyproject.toml", "[project]\nname = \"sim\"\n"),
+            ("sim/__init__.py", "print('hello')\n"),
+            ".venv/lib",
+        ),
+        Kind::Plain => (("README.md", "# notes\n"), ("notes.txt", "todo\n"), "data"),
+    };
+
+    for (file, content) in [manifest, source] {
+        let file_path = path.join(file);
+        fs::create_dir_all(file_path.parent().unwrap_or(path))?;
+        fs::write(file_path, content)?;
+    }
+
+    // Most of the size lives in build artifacts and dependencies, as in real projects.
+    let bulk_dir = path.join(bulk_dir);
+    fs::create_dir_all(&bulk_dir)?;
+    const CHUNK: u64 = 256 * 1024;
+    let mut remaining = size;
+    let mut index = 0;
+    while remaining > 0 {
+        let len = remaining.min(CHUNK);
+        fs::write(
+            bulk_dir.join(format!("blob-{}.bin", index)),
+            vec![b'x'; len as usize],
+        )?;
+        remaining -= len;
+        index += 1;
+    }
+    if !matches!(kind, Kind::Plain) {
+        fs::write(
+            path.join(".gitignore"),
+            format!(
+                "{}\n",
+                bulk_dir.strip_prefix(path).unwrap_or(&bulk_dir).display()
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+/// Creates `commits` commits, one day apart, the last one at `last_commit` (Unix seconds).
+fn commit_history(path: &Path, commits: usize, last_commit: i64, rng: &mut Rng) -> Result<()> {
+    let repo = Repository::init(path)?;
+    let mut parent = None;
+    for n in 0..commits {
+        let time = last_commit - (commits - 1 - n) as i64 * 86_400;
+        fs::write(
+            path.join("CHANGELOG.md"),
+            format!("change {}\n", rng.next()),
+        )?;
+
+        let mut index = repo.index()?;
+        index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let signature = Signature::new("Sim", "sim@example.com", &Time::new(time, 0))?;
+        let parents: Vec<_> = parent.iter().collect();
+        let oid = repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &format!("change {}", n),
+            &tree,
+            &parents,
+        )?;
+        parent = Some(repo.find_commit(oid)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! Code statistics recorded at archive time, so an entry still tells what kind of project it
+//! was once the sources themselves are compressed away.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tokei::{Config, Languages};
+
+/// Line counts of a single language.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageStats {
+    pub files: usize,
+    pub code: usize,
+    pub comments: usize,
+    pub blanks: usize,
+}
+
+/// Line counts per language, keyed by language name (e.g. `Rust`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeStats {
+    pub languages: BTreeMap<String, LanguageStats>,
+}
+
+impl CodeStats {
+    /// Counts the lines of every recognized source file under `path`, honoring `.gitignore`.
+    pub fn collect(path: &Path) -> Self {
+        let mut languages = Languages::new();
+        languages.get_statistics(&[path], &[], &Config::default());
+
+        let languages = languages
+            .iter()
+            .map(|(kind, language)| (kind, language.summarise()))
+            .filter(|(_, language)| !language.is_empty())
+            .map(|(kind, language)| {
+                let stats = LanguageStats {
+                    files: language.reports.len(),
+                    code: language.code,
+                    comments: language.comments,
+                    blanks: language.blanks,
+                };
+                (kind.to_string(), stats)
+            })
+            .collect();
+        Self { languages }
+    }
+
+    /// Total lines of code across all languages.
+    pub fn total_code(&self) -> usize {
+        self.languages.values().map(|stats| stats.code).sum()
+    }
+
+    /// The language with the most lines of code, if any.
+    pub fn primary_language(&self) -> Option<&str> {
+        self.languages
+            .iter()
+            .max_by_key(|(_, stats)| stats.code)
+            .map(|(name, _)| name.as_str())
+    }
+}
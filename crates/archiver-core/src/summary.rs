@@ -0,0 +1,135 @@
+//! Machine-readable summary of a run, written after every run for external dashboards.
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// What happened to a single project during a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Archived,
+    WouldArchive,
+    /// Inactive, but protected (e.g. by a branch or an open review).
+    Skipped,
+    Deleted,
+    WouldDelete,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectOutcome {
+    pub name: String,
+    pub path: PathBuf,
+    pub outcome: Outcome,
+    /// Size of the project in bytes, when it was moved or deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    /// Why the project was skipped, or the error it failed with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Folders removed by the cleanup rules before archiving, relative to the project.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cleaned: Vec<PathBuf>,
+    /// Size of the cleaned folders in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cleaned_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub dry_run: bool,
+    /// Number of projects found by the scan.
+    pub scanned: usize,
+    pub archived: usize,
+    pub skipped: usize,
+    pub deleted: usize,
+    pub failed: usize,
+    /// Bytes moved into the archive (or that would be, on a dry run).
+    pub bytes_moved: u64,
+    /// Bytes freed by deleting expired archives (or that would be, on a dry run).
+    pub bytes_freed: u64,
+    /// Bytes freed by the cleanup rules before archiving (or that would be, on a dry run).
+    #[serde(default)]
+    pub bytes_cleaned: u64,
+    pub projects: Vec<ProjectOutcome>,
+    /// The error that aborted the run, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RunSummary {
+    pub fn start(dry_run: bool) -> Self {
+        let now = Utc::now();
+        Self {
+            started_at: now,
+            finished_at: now,
+            duration_ms: 0,
+            dry_run,
+            scanned: 0,
+            archived: 0,
+            skipped: 0,
+            deleted: 0,
+            failed: 0,
+            bytes_moved: 0,
+            bytes_freed: 0,
+            bytes_cleaned: 0,
+            projects: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Records the outcome of a project and updates the totals.
+    pub fn record(&mut self, outcome: ProjectOutcome) {
+        let bytes = outcome.bytes.unwrap_or(0);
+        match outcome.outcome {
+            Outcome::Archived | Outcome::WouldArchive => {
+                self.archived += 1;
+                self.bytes_moved += bytes;
+                self.bytes_cleaned += outcome.cleaned_bytes.unwrap_or(0);
+            }
+            Outcome::Deleted | Outcome::WouldDelete => {
+                self.deleted += 1;
+                self.bytes_freed += bytes;
+            }
+            Outcome::Skipped => self.skipped += 1,
+            Outcome::Failed => self.failed += 1,
+        }
+        self.projects.push(outcome);
+    }
+
+    /// Projects with the given outcome.
+    pub fn with_outcome(&self, outcome: Outcome) -> impl Iterator<Item = &ProjectOutcome> {
+        self.projects.iter().filter(move |p| p.outcome == outcome)
+    }
+
+    /// Stamps the end of the run, along with the error that aborted it, if any.
+    pub fn finish(&mut self, error: Option<String>) {
+        self.finished_at = Utc::now();
+        self.duration_ms = (self.finished_at - self.started_at).num_milliseconds();
+        self.error = error;
+    }
+
+    /// Reads the summary written at `path`, if a run wrote one.
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the summary as pretty-printed JSON, replacing the previous one.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+//! Multi-machine archive index.
+//!
+//! When several machines share the same archive directory, each one writes its own
+//! `archive.<machine>.json` and the index is the union of all of them (merge-on-read).
+//! A machine adds records to its own file only, and touches another machine's file only to
+//! remove one of its records that was restored or deleted, reading the file again first, so
+//! concurrent runs never clobber each other's new records.
+use crate::error::{Error, Result};
+use crate::models::ArchivedRecord;
+use crate::sidecar;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+const LOG_PREFIX: &str = "archive.";
+const LOG_EXTENSION: &str = ".json";
+const LEGACY_LOG_FILE: &str = "archive.json";
+
+/// Returns the name of the current machine, used when no `machine_id` is configured.
+pub fn hostname() -> String {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|name| name.trim().to_string())
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+/// File name of the log owned by `machine`, or the legacy shared log for records without one.
+/// It never ends like a metadata sidecar: a machine called `meta` would otherwise share
+/// `archive.meta.json` with the sidecar of a project called `archive`.
+pub fn log_file_name(machine: Option<&str>) -> String {
+    match machine {
+        Some(machine) => {
+            let mut sanitized: String = machine
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            if format!(".{}{}", sanitized, LOG_EXTENSION) == sidecar::EXTENSION {
+                sanitized.push('_');
+            }
+            format!("{}{}{}", LOG_PREFIX, sanitized, LOG_EXTENSION)
+        }
+        None => LEGACY_LOG_FILE.to_string(),
+    }
+}
+
+/// Lists the legacy log and every per-machine log present in `dir`.
+pub(crate) fn log_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|name| {
+                        name.starts_with(LOG_PREFIX)
+                            && name.ends_with(LOG_EXTENSION)
+                            && !name.ends_with(sidecar::EXTENSION)
+                    })
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Reads and merges every log file in `dir`, dropping records present in more than one file.
+pub fn read_merged(dir: &Path) -> Result<Vec<ArchivedRecord>> {
+    let mut records: Vec<ArchivedRecord> = Vec::new();
+    for file in log_files(dir)? {
+        debug!(path = %file.display(), "Reading machine archive log.");
+        let file_records = read_file(&file)?;
+        for record in file_records {
+            let duplicate = records
+                .iter()
+                .any(|r| r.name == record.name && r.archived_at == record.archived_at);
+            if !duplicate {
+                records.push(record);
+            }
+        }
+    }
+    records.sort_by_key(|r| r.archived_at);
+    Ok(records)
+}
+
+/// Adds `records` to the log of `own_machine`, the only one a machine writes new records to.
+pub fn append_records(dir: &Path, records: &[ArchivedRecord], own_machine: &str) -> Result<()> {
+    let file = dir.join(log_file_name(Some(own_machine)));
+    let mut file_records = read_file(&file)?;
+    file_records.extend_from_slice(records);
+    write_file(&file, &file_records)
+}
+
+/// Removes `records` from the logs holding them, whichever machine they belong to. Each log
+/// is read again first, so records its machine added meanwhile are kept.
+pub fn remove_records(dir: &Path, records: &[ArchivedRecord]) -> Result<()> {
+    for file in log_files(dir)? {
+        let mut file_records = read_file(&file)?;
+        let before = file_records.len();
+        file_records.retain(|record| {
+            !records
+                .iter()
+                .any(|r| r.name == record.name && r.archived_at == record.archived_at)
+        });
+        if file_records.len() != before {
+            write_file(&file, &file_records)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_file(file: &Path) -> Result<Vec<ArchivedRecord>> {
+    match fs::read_to_string(file) {
+        Ok(content) => serde_json::from_str(&content).map_err(|source| Error::CorruptArchiveLog {
+            path: file.to_path_buf(),
+            source,
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_file(file: &Path, records: &[ArchivedRecord]) -> Result<()> {
+    debug!(path = %file.display(), count = records.len(), "Writing machine archive log.");
+    fs::write(file, serde_json::to_string_pretty(records)?)?;
+    Ok(())
+}
@@ -0,0 +1,328 @@
+//! Team policy: settings a team maintains in one place (e.g. the exclusions and retention of
+//! shared build machines), fetched from a URL and layered under the local settings, which
+//! override them.
+//!
+//! The policy is a TOML settings file, of which only the settings in [`KEYS`] are used:
+//! settings that pick directories or run commands stay local. It is downloaded with `curl`,
+//! cached in the state directory and checked again after `refresh_minutes`, sending the ETag
+//! of the cached copy so an unchanged file is not downloaded again. With `allowed_signers`,
+//! the file must come with an SSH signature at `<url>.sig` (`ssh-keygen -Y sign -n archiver`)
+//! by one of those signers. While the URL cannot be reached, the cached copy is used, as long
+//! as it was checked against the same signers.
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Settings a team policy may set.
+pub const KEYS: &[&str] = &[
+    "inactivity_days",
+    "inactivity_days_by_type",
+    "exclude",
+    "exclude_on_restore",
+    "skip_branches",
+    "cleanup_keep",
+    "count_untracked_files",
+    "check_open_reviews",
+    "check_containers",
+    "enable_auto_delete",
+    "days_before_delete",
+    "delete_safety_multiplier",
+    "suggest_exclusion_after",
+];
+
+/// Namespace of the SSH signatures of policy files.
+const SIGNATURE_NAMESPACE: &str = "archiver";
+
+fn default_refresh_minutes() -> u64 {
+    60
+}
+
+/// Where the team policy comes from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TeamPolicy {
+    /// HTTPS (or `file://`) URL of the policy file. Plain HTTP is only accepted on localhost.
+    pub url: String,
+    /// Minutes the cached policy is used before checking the URL again.
+    #[serde(default = "default_refresh_minutes")]
+    pub refresh_minutes: u64,
+    /// `allowed_signers` file (see `ssh-keygen(1)`) of the people who may sign the policy.
+    /// When set, unsigned or badly signed policies are refused.
+    #[serde(default)]
+    pub allowed_signers: Option<PathBuf>,
+}
+
+/// The policy as last downloaded.
+#[derive(Debug, Serialize, Deserialize)]
+struct Cache {
+    url: String,
+    /// Digest of the `allowed_signers` file the policy was verified with, if it was signed.
+    #[serde(default)]
+    signers: Option<String>,
+    etag: Option<String>,
+    fetched_at: DateTime<Utc>,
+    content: String,
+}
+
+enum Download {
+    Fetched {
+        content: String,
+        etag: Option<String>,
+    },
+    NotModified,
+}
+
+/// Returns the policy settings as TOML, from the cache at `cache_path` while it is fresh,
+/// otherwise from the URL, falling back to the cache when the URL cannot be used.
+pub fn load(policy: &TeamPolicy, cache_path: &Path, timeout: Duration) -> Result<String> {
+    let signers = signers_digest(policy);
+    let cached = fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Cache>(&content).ok())
+        .filter(|cache| cache.url == policy.url && cache.signers == signers);
+    let max_age = chrono::Duration::minutes(policy.refresh_minutes as i64);
+    if let Some(cache) = cached
+        .as_ref()
+        .filter(|c| Utc::now() - c.fetched_at < max_age)
+    {
+        debug!(url = %policy.url, "Using the cached team policy.");
+        return filter(&cache.content);
+    }
+
+    let etag = cached.as_ref().and_then(|cache| cache.etag.as_deref());
+    let cache = match download(policy, cache_path, etag, timeout) {
+        Ok(Download::Fetched { content, etag }) => {
+            info!(url = %policy.url, "Downloaded the team policy.");
+            Cache {
+                url: policy.url.clone(),
+                signers,
+                etag,
+                fetched_at: Utc::now(),
+                content,
+            }
+        }
+        Ok(Download::NotModified) => match cached {
+            Some(cache) => Cache {
+                fetched_at: Utc::now(),
+                ..cache
+            },
+            None => return Err(policy_error(policy, "not modified, but nothing is cached")),
+        },
+        Err(e) => match cached {
+            Some(cache) => {
+                warn!(url = %policy.url, error = %e, "Could not update the team policy, using the cached one.");
+                return filter(&cache.content);
+            }
+            None => return Err(e),
+        },
+    };
+    let settings = filter(&cache.content)?;
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(cache_path, serde_json::to_string_pretty(&cache)?)?;
+    Ok(settings)
+}
+
+/// Digest of the `allowed_signers` file of `policy`, so a policy verified with other signers
+/// is not taken from the cache. Unreadable files get a digest of their own.
+fn signers_digest(policy: &TeamPolicy) -> Option<String> {
+    let path = policy.allowed_signers.as_ref()?;
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    fs::read(path).ok().hash(&mut hasher);
+    Some(format!("{:016x}", hasher.finish()))
+}
+
+fn policy_error(policy: &TeamPolicy, message: impl std::fmt::Display) -> Error {
+    Error::Custom(format!("Team policy '{}': {}", policy.url, message))
+}
+
+/// Keeps the settings of `content` a policy may set.
+fn filter(content: &str) -> Result<String> {
+    let mut table: toml::Table = toml::from_str(content)
+        .map_err(|e| Error::Custom(format!("Invalid team policy: {}", e)))?;
+    table.retain(|key, _| {
+        let allowed = KEYS.contains(&key);
+        if !allowed {
+            warn!(key, "Ignoring a setting the team policy may not set.");
+        }
+        allowed
+    });
+    toml::to_string(&table).map_err(|e| Error::Custom(format!("Invalid team policy: {}", e)))
+}
+
+fn download(
+    policy: &TeamPolicy,
+    cache_path: &Path,
+    etag: Option<&str>,
+    timeout: Duration,
+) -> Result<Download> {
+    let url = policy.url.as_str();
+    let loopback = ["http://localhost", "http://127.0.0.1", "http://[::1]"]
+        .iter()
+        .any(|prefix| {
+            url.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with([':', '/']))
+        });
+    let protocol = if url.starts_with("https://") {
+        "https"
+    } else if url.starts_with("file://") {
+        "file"
+    } else if loopback {
+        "http"
+    } else {
+        return Err(policy_error(policy, "only HTTPS URLs are allowed"));
+    };
+
+    let body_path = cache_path.with_extension("download");
+    let (status, etag) = curl(policy, url, protocol, etag, &body_path, timeout)?;
+    if status == 304 {
+        return Ok(Download::NotModified);
+    }
+    let content = fs::read_to_string(&body_path);
+    let _ = fs::remove_file(&body_path);
+    let content = content?;
+    if let Some(allowed_signers) = &policy.allowed_signers {
+        let signature_path = cache_path.with_extension("sig");
+        let signature = curl(
+            policy,
+            &format!("{url}.sig"),
+            protocol,
+            None,
+            &signature_path,
+            timeout,
+        )
+        .and_then(|_| verify(policy, &content, &signature_path, allowed_signers));
+        let _ = fs::remove_file(&signature_path);
+        signature?;
+    }
+    Ok(Download::Fetched { content, etag })
+}
+
+/// Downloads `url`, which uses `protocol`, into `output`, returning the HTTP status (`0` for
+/// `file://` URLs) and the ETag of the response. Redirects may only lead to HTTPS URLs.
+fn curl(
+    policy: &TeamPolicy,
+    url: &str,
+    protocol: &str,
+    etag: Option<&str>,
+    output: &Path,
+    timeout: Duration,
+) -> Result<(u16, Option<String>)> {
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--location", "--proto"])
+        .arg(format!("={protocol}"))
+        .args(["--proto-redir", "=https", "--max-time"])
+        .arg(timeout.as_secs().max(1).to_string())
+        .arg("--output")
+        .arg(output)
+        .args(["--write-out", "%{http_code}\n%header{etag}"]);
+    if let Some(etag) = etag {
+        command
+            .arg("--header")
+            .arg(format!("If-None-Match: {etag}"));
+    }
+    debug!(url, "Downloading team policy.");
+    let result = command
+        .arg(url)
+        .output()
+        .map_err(|e| policy_error(policy, format!("could not run 'curl': {e}")))?;
+    if !result.status.success() {
+        return Err(policy_error(
+            policy,
+            String::from_utf8_lossy(&result.stderr).trim(),
+        ));
+    }
+    let out = String::from_utf8_lossy(&result.stdout);
+    let mut lines = out.lines();
+    let status: u16 = lines
+        .next()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0);
+    if status != 0 && status != 200 && status != 304 {
+        let _ = fs::remove_file(output);
+        return Err(policy_error(policy, format!("HTTP status {status}")));
+    }
+    let etag = lines
+        .next()
+        .map(str::trim)
+        .filter(|etag| !etag.is_empty())
+        .map(str::to_string);
+    Ok((status, etag))
+}
+
+/// Checks that `signature` signs `content` by one of `allowed_signers`, with `ssh-keygen`.
+fn verify(
+    policy: &TeamPolicy,
+    content: &str,
+    signature: &Path,
+    allowed_signers: &Path,
+) -> Result<()> {
+    let ssh_keygen = |args: &[&std::ffi::OsStr], input: &str| -> Result<std::process::Output> {
+        let mut child = Command::new("ssh-keygen")
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| policy_error(policy, format!("could not run 'ssh-keygen': {e}")))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A command that fails early closes its input: its exit status tells why.
+            let _ = stdin.write_all(input.as_bytes());
+        }
+        Ok(child.wait_with_output()?)
+    };
+    let found = ssh_keygen(
+        &[
+            "-Y".as_ref(),
+            "find-principals".as_ref(),
+            "-f".as_ref(),
+            allowed_signers.as_os_str(),
+            "-s".as_ref(),
+            signature.as_os_str(),
+        ],
+        "",
+    )?;
+    let stdout = String::from_utf8_lossy(&found.stdout);
+    let Some(principal) = stdout.lines().next().filter(|_| found.status.success()) else {
+        return Err(policy_error(policy, "not signed by an allowed signer"));
+    };
+    let verified = ssh_keygen(
+        &[
+            "-Y".as_ref(),
+            "verify".as_ref(),
+            "-f".as_ref(),
+            allowed_signers.as_os_str(),
+            "-I".as_ref(),
+            principal.as_ref(),
+            "-n".as_ref(),
+            SIGNATURE_NAMESPACE.as_ref(),
+            "-s".as_ref(),
+            signature.as_os_str(),
+        ],
+        content,
+    )?;
+    if !verified.status.success() {
+        return Err(policy_error(
+            policy,
+            format!(
+                "bad signature: {}",
+                String::from_utf8_lossy(&verified.stderr).trim()
+            ),
+        ));
+    }
+    debug!(url = %policy.url, principal, "Verified the team policy signature.");
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! Builders of realistic environments for integration tests, behind the `test-utils` feature:
+//! a temporary projects directory and archive, projects with files of any age, and Git
+//! repositories with back-dated commits.
+//!
+//! ```no_run
+//! use archiver_core::testing::TestEnv;
+//! use archiver_core::Archiver;
+//!
+//! let env = TestEnv::new().configure(|settings| settings.inactivity_days = 60);
+//! env.project("thesis").git_commit("draft", "2023-01-01T12:00:00Z");
+//! Archiver::new(env.settings().clone()).run_archive_process(false).unwrap();
+//! assert!(env.archive_dir().join("thesis").exists());
+//! ```
+//!
+//! Failures panic, as tests expect. Git commits need the `git` program.
+use crate::config::Settings;
+use chrono::{DateTime, Utc};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+/// A temporary directory with a projects directory and an archive directory, and settings
+/// using them. Everything is removed when it is dropped.
+pub struct TestEnv {
+    dir: TempDir,
+    settings: Settings,
+}
+
+impl TestEnv {
+    /// An empty projects directory and archive, with projects inactive after 30 days.
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let projects_dir = dir.path().join("projects");
+        let archive_dir = dir.path().join("archive");
+        fs::create_dir_all(&projects_dir).unwrap();
+        fs::create_dir_all(&archive_dir).unwrap();
+        let settings = Settings {
+            projects_dirs: vec![projects_dir],
+            archive_dir,
+            inactivity_days: 30,
+            ..Default::default()
+        };
+        Self { dir, settings }
+    }
+
+    /// The environment of the archiver's own tests: `old_project`, last committed to in 2023,
+    /// `new_project`, committed to now, and `empty_project`, a repository without commits.
+    pub fn standard() -> Self {
+        let env = Self::new();
+        env.project("old_project")
+            .git_commit("old commit", "2023-01-01T12:00:00Z");
+        env.project("new_project")
+            .git_commit("new commit", &Utc::now().to_rfc3339());
+        env.project("empty_project").git_init();
+        env
+    }
+
+    /// Changes the settings, e.g. to enable a feature under test.
+    pub fn configure(mut self, change: impl FnOnce(&mut Settings)) -> Self {
+        change(&mut self.settings);
+        self
+    }
+
+    /// The temporary directory holding everything.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// The (first) projects directory.
+    pub fn projects_dir(&self) -> &Path {
+        &self.settings.projects_dirs[0]
+    }
+
+    pub fn archive_dir(&self) -> &Path {
+        &self.settings.archive_dir
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Creates the directory of project `name` in the projects directory, if it does not exist.
+    pub fn project(&self, name: &str) -> ProjectBuilder {
+        ProjectBuilder::at(self.projects_dir().join(name))
+    }
+
+    /// The temporary directory, which must be kept alive, and the settings.
+    pub fn into_parts(self) -> (TempDir, Settings) {
+        (self.dir, self.settings)
+    }
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Adds files and commits to a project directory.
+pub struct ProjectBuilder {
+    path: PathBuf,
+}
+
+impl ProjectBuilder {
+    /// Creates the directory at `path`, if it does not exist.
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes the file at `relative`, creating its parent directories.
+    pub fn file(self, relative: impl AsRef<Path>, contents: &str) -> Self {
+        let path = self.path.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, contents).unwrap();
+        self
+    }
+
+    /// Writes the file at `relative` and sets its modification time, e.g. to make a project
+    /// without Git look inactive.
+    pub fn file_modified_at(
+        self,
+        relative: impl AsRef<Path>,
+        contents: &str,
+        modified: DateTime<Utc>,
+    ) -> Self {
+        let path = self.path.join(relative.as_ref());
+        let builder = self.file(relative, contents);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified.into())
+            .unwrap();
+        builder
+    }
+
+    /// Initializes a Git repository, if there is none.
+    pub fn git_init(self) -> Self {
+        if !self.path.join(".git").exists() {
+            git(&self.path, &["init", "--quiet"], None);
+        }
+        self
+    }
+
+    /// Commits every file, with author and committer dates set to `date` (RFC 3339). Writes
+    /// `file.txt` with the message first, so there is always something to commit.
+    pub fn git_commit(self, message: &str, date: &str) -> Self {
+        let builder = self.git_init().file("file.txt", message);
+        git(&builder.path, &["add", "."], None);
+        git(
+            &builder.path,
+            &["commit", "--quiet", "-m", message],
+            Some(date),
+        );
+        builder
+    }
+}
+
+/// Runs `git` in `dir`, with a fallback identity so commits work on machines without one.
+fn git(dir: &Path, args: &[&str], date: Option<&str>) {
+    let mut command = Command::new("git");
+    command
+        .args([
+            "-c",
+            "user.name=Archiver Test",
+            "-c",
+            "user.email=test@example.com",
+        ])
+        .args(args)
+        .current_dir(dir);
+    if let Some(date) = date {
+        command
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date);
+    }
+    let output = command.output().expect("Failed to run git");
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Initializes a Git repository at `path` and commits a file with `msg`, dated `date`.
+pub fn init_git_repo_with_date(path: &Path, msg: &str, date: &str) {
+    ProjectBuilder::at(path).git_commit(msg, date);
+}
+
+/// Sends the logs of the code under test to the test output. Can be called from every test.
+pub fn setup_tracing() {
+    let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+}
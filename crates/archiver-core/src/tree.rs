@@ -0,0 +1,81 @@
+//! The archive as a tree for `list --tree`: archive directories, then groups (or the year of
+//! archiving for projects without one), then projects, each with its size on disk.
+use crate::config::Settings;
+use crate::models::ArchivedRecord;
+use chrono::{DateTime, Datelike, Utc};
+use std::collections::BTreeMap;
+
+/// A node of the archive tree. Children are sorted by size, largest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    /// Archive directory path, group, year or project label.
+    pub name: String,
+    /// Size on disk of everything below the node.
+    pub bytes: u64,
+    /// When the project was archived; only set on projects.
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Whether the project is in the archive log but not on disk.
+    pub missing: bool,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    fn branch(name: String, mut children: Vec<TreeNode>) -> Self {
+        children.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+        Self {
+            name,
+            bytes: children.iter().map(|child| child.bytes).sum(),
+            archived_at: None,
+            missing: false,
+            children,
+        }
+    }
+}
+
+/// Builds the tree of `records`, measuring each archived project on disk.
+pub fn build(settings: &Settings, records: Vec<ArchivedRecord>) -> Vec<TreeNode> {
+    let mut dirs: BTreeMap<String, BTreeMap<String, Vec<TreeNode>>> = BTreeMap::new();
+    for record in records {
+        // A route removed from the settings is shown by its name.
+        let dir = match &record.destination {
+            None => settings.archive_dir.display().to_string(),
+            Some(name) => settings
+                .archive_routes
+                .iter()
+                .find(|route| &route.name == name)
+                .map_or_else(
+                    || name.clone(),
+                    |route| route.archive_dir.display().to_string(),
+                ),
+        };
+        let branch = record
+            .group
+            .clone()
+            .unwrap_or_else(|| record.archived_at.year().to_string());
+        let missing = !record.archive_path.exists();
+        let project = TreeNode {
+            name: record.label(),
+            bytes: crate::dir_size(&record.archive_path),
+            archived_at: Some(record.archived_at),
+            missing,
+            children: Vec::new(),
+        };
+        dirs.entry(dir)
+            .or_default()
+            .entry(branch)
+            .or_default()
+            .push(project);
+    }
+    let mut tree: Vec<TreeNode> = dirs
+        .into_iter()
+        .map(|(dir, branches)| {
+            let branches = branches
+                .into_iter()
+                .map(|(name, projects)| TreeNode::branch(name, projects))
+                .collect();
+            TreeNode::branch(dir, branches)
+        })
+        .collect();
+    tree.sort_by_key(|node| std::cmp::Reverse(node.bytes));
+    tree
+}
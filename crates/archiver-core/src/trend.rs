@@ -0,0 +1,133 @@
+//! History of disk usage, one sample per real run, to follow whether the archiving policy keeps
+//! the projects directories from growing.
+//!
+//! Samples are appended as JSON lines to a file in the state directory
+//! (`Settings::usage_history_path`) and grouped by week or month when read back.
+use crate::error::Result;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use tracing::warn;
+
+/// Disk usage measured at the end of a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageSample {
+    pub at: DateTime<Utc>,
+    /// Size of the projects directories, the archive excluded.
+    pub active_bytes: u64,
+    /// Size of the archive directory.
+    pub archive_bytes: u64,
+    /// Bytes freed by the run, by deleting expired archives and by the cleanup rules.
+    pub freed_bytes: u64,
+    /// Projects the run archived.
+    #[serde(default)]
+    pub archived_projects: usize,
+    /// Projects the run deleted from the archive.
+    #[serde(default)]
+    pub deleted_projects: usize,
+}
+
+/// Length of the periods samples are grouped by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// Weeks starting on Monday.
+    Week,
+    Month,
+}
+
+impl Period {
+    /// First day of the period containing `date`.
+    pub fn start_of(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Week => date - Duration::days(date.weekday().num_days_from_monday().into()),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// Length of the period, a month counting as 30 days.
+    pub fn length(self) -> Duration {
+        match self {
+            Self::Week => Duration::days(7),
+            Self::Month => Duration::days(30),
+        }
+    }
+
+    /// First day of the period before the one containing `date`.
+    pub fn previous_start(self, date: NaiveDate) -> NaiveDate {
+        let start = self.start_of(date);
+        match self {
+            Self::Week => start - Duration::days(7),
+            Self::Month => self.start_of(start - Duration::days(1)),
+        }
+    }
+}
+
+/// Disk usage over one period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub period_start: NaiveDate,
+    /// Size of the projects directories at the last run of the period.
+    pub active_bytes: u64,
+    /// Size of the archive at the last run of the period.
+    pub archive_bytes: u64,
+    /// Bytes freed by all the runs of the period.
+    pub freed_bytes: u64,
+    pub runs: usize,
+}
+
+/// Appends a sample to the history at `path`, creating it if needed.
+pub fn append(path: &Path, sample: &UsageSample) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(sample)?)?;
+    Ok(())
+}
+
+/// Reads the history at `path`, oldest first. A missing file is an empty history and lines
+/// that cannot be parsed are logged and skipped.
+pub fn read(path: &Path) -> Result<Vec<UsageSample>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut samples: Vec<UsageSample> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            serde_json::from_str(line)
+                .inspect_err(|e| warn!(path = %path.display(), error = %e, "Skipping unreadable usage sample."))
+                .ok()
+        })
+        .collect();
+    samples.sort_by_key(|sample| sample.at);
+    Ok(samples)
+}
+
+/// Groups samples by period, oldest first.
+pub fn trend(samples: &[UsageSample], period: Period) -> Vec<TrendPoint> {
+    let mut points: Vec<TrendPoint> = Vec::new();
+    for sample in samples {
+        let period_start = period.start_of(sample.at.date_naive());
+        match points.last_mut() {
+            Some(point) if point.period_start == period_start => {
+                point.active_bytes = sample.active_bytes;
+                point.archive_bytes = sample.archive_bytes;
+                point.freed_bytes += sample.freed_bytes;
+                point.runs += 1;
+            }
+            _ => points.push(TrendPoint {
+                period_start,
+                active_bytes: sample.active_bytes,
+                archive_bytes: sample.archive_bytes,
+                freed_bytes: sample.freed_bytes,
+                runs: 1,
+            }),
+        }
+    }
+    points
+}
@@ -1,4 +1,8 @@
-use archiver_core::Archiver;
+use archiver_core::config::CleanupRule;
+use archiver_core::{
+    ActionPlan, Archiver, ErrorCode, Exclusion, Outcome, ProjectType, RestoreFilter,
+};
+use std::process::Command;
 
 mod helpers;
 use helpers::{setup_test_env, setup_tracing};
@@ -12,12 +16,12 @@ fn it_archives_only_inactive_projects_on_real_run() {
     let result = archiver.run_archive_process(false);
     assert!(result.is_ok());
 
-    let old_project_original_path = settings.projects_dir.join("old_project");
+    let old_project_original_path = settings.projects_dirs[0].join("old_project");
     let old_project_archived_path = settings.archive_dir.join("old_project");
     assert!(!old_project_original_path.exists());
     assert!(old_project_archived_path.exists());
 
-    let new_project_path = settings.projects_dir.join("new_project");
+    let new_project_path = settings.projects_dirs[0].join("new_project");
     assert!(new_project_path.exists());
 
     let log_content = archiver.get_archive_records().unwrap();
@@ -34,7 +38,7 @@ fn it_ignores_empty_repositories_without_commits() {
     let result = archiver.run_archive_process(false);
     assert!(result.is_ok()); // Should not error out
 
-    let empty_project_path = settings.projects_dir.join("empty_project");
+    let empty_project_path = settings.projects_dirs[0].join("empty_project");
     assert!(
         empty_project_path.exists(),
         "Empty project should be ignored and remain"
@@ -51,7 +55,7 @@ fn it_restores_an_archived_project() {
     let archiver = Archiver::new(settings.clone());
     archiver.run_archive_process(false).unwrap();
 
-    let old_project_original_path = settings.projects_dir.join("old_project");
+    let old_project_original_path = settings.projects_dirs[0].join("old_project");
     let old_project_archived_path = settings.archive_dir.join("old_project");
     assert!(!old_project_original_path.exists());
     assert!(old_project_archived_path.exists());
@@ -66,19 +70,114 @@ fn it_restores_an_archived_project() {
     assert!(log_content.is_empty());
 }
 
+#[test]
+fn it_verifies_restored_projects_against_their_manifest() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    let other_path = settings.projects_dirs[0].join("other_project");
+    std::fs::create_dir(&other_path).unwrap();
+    helpers::init_git_repo_with_date(&other_path, "old commit", "2023-01-01T12:00:00Z");
+    archiver.run_archive_process(false).unwrap();
+
+    let records = archiver.get_archive_records().unwrap();
+    let manifest = records[0].manifest.clone().unwrap();
+    assert!(manifest.files > 1);
+    assert!(manifest.bytes >= "old commit".len() as u64);
+    assert!(manifest.digest.is_some());
+
+    archiver.restore_project("old_project").unwrap();
+
+    // Same size, other content.
+    let archived_file = settings.archive_dir.join("other_project/file.txt");
+    std::fs::write(&archived_file, "odd commit").unwrap();
+    let error = archiver.restore_project("other_project").unwrap_err();
+    assert_eq!(error.code(), ErrorCode::RestoreMismatch);
+    assert!(error.to_string().contains("other_project"));
+    // The project goes back into the archive and keeps its record, to be restored again.
+    assert!(!other_path.exists());
+    assert!(archived_file.exists());
+    assert_eq!(archiver.get_archive_records().unwrap().len(), 1);
+
+    std::fs::write(&archived_file, "old commit").unwrap();
+    archiver.restore_project("other_project").unwrap();
+    assert!(other_path.join("file.txt").exists());
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+}
+
+#[test]
+fn it_previews_a_restore_without_moving_anything() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+
+    let preview = archiver.preview_restore("old_project").unwrap();
+
+    assert_eq!(
+        preview.record.original_path,
+        settings.projects_dirs[0].join("old_project")
+    );
+    assert!(!preview.destination_exists);
+    assert!(preview.size_bytes >= "old commit".len() as u64);
+    assert!(settings.archive_dir.join("old_project").exists());
+    assert_eq!(archiver.get_archive_records().unwrap().len(), 1);
+
+    std::fs::create_dir(settings.projects_dirs[0].join("old_project")).unwrap();
+    assert!(
+        archiver
+            .preview_restore("old_project")
+            .unwrap()
+            .destination_exists
+    );
+}
+
+#[test]
+fn it_restores_a_tagged_cohort() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    archiver
+        .run_tagged_archive_process(false, &["client-x".to_string()])
+        .unwrap();
+
+    let other_path = settings.projects_dirs[0].join("other_project");
+    std::fs::create_dir(&other_path).unwrap();
+    helpers::init_git_repo_with_date(&other_path, "old commit", "2023-01-01T12:00:00Z");
+    archiver.run_archive_process(false).unwrap();
+
+    let before_yesterday = RestoreFilter {
+        archived_before: Some(chrono::Utc::now() - chrono::Duration::days(1)),
+        ..Default::default()
+    };
+    assert_eq!(archiver.restore_matching(&before_yesterday).unwrap(), 0);
+
+    let tagged = RestoreFilter {
+        tag: Some("client-x".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(archiver.restore_matching(&tagged).unwrap(), 1);
+
+    assert!(settings.projects_dirs[0].join("old_project").exists());
+    assert!(!other_path.exists());
+    let records = archiver.get_archive_records().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].name, "other_project");
+}
+
 #[test]
 fn it_ignores_excluded_projects() {
     setup_tracing();
     let (_temp_dir, mut settings) = setup_test_env();
 
     let excluded_project_name = "old_project";
-    settings.exclude.push(excluded_project_name.to_string());
+    settings.exclude.push(excluded_project_name.into());
 
     let archiver = Archiver::new(settings.clone());
 
     archiver.run_archive_process(false).unwrap();
 
-    let excluded_project_path = settings.projects_dir.join(excluded_project_name);
+    let excluded_project_path = settings.projects_dirs[0].join(excluded_project_name);
     assert!(
         excluded_project_path.exists(),
         "Excluded project should not have been moved."
@@ -91,3 +190,281 @@ fn it_ignores_excluded_projects() {
         "The archive log should be empty when the only inactive project is excluded"
     );
 }
+
+#[test]
+fn it_applies_glob_exclusions_until_they_expire() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    let today = chrono::Local::now().date_naive();
+
+    settings.exclude = vec![Exclusion::new("old_*", Some(today))];
+    let plan = Archiver::new(settings.clone())
+        .run_archive_process(true)
+        .unwrap();
+    assert!(
+        matches!(plan.as_slice(), [ActionPlan::Nothing]),
+        "A glob exclusion applies through its last day"
+    );
+
+    settings.exclude = vec![Exclusion::new("old_*", today.pred_opt())];
+    let plan = Archiver::new(settings).run_archive_process(true).unwrap();
+    assert!(
+        matches!(plan.as_slice(), [ActionPlan::Archive { project_name, .. }] if project_name == "old_project"),
+        "An expired exclusion no longer applies"
+    );
+}
+
+#[test]
+fn it_skips_repositories_checked_out_on_protected_branches() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.skip_branches = vec!["wip/*".to_string()];
+
+    let old_project_path = settings.projects_dirs[0].join("old_project");
+    let output = Command::new("git")
+        .args(["checkout", "-b", "wip/refactor"])
+        .current_dir(&old_project_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+
+    assert!(old_project_path.exists());
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+}
+
+#[test]
+fn it_only_counts_commits_by_activity_authors() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.activity_authors = vec!["me@example.com".to_string()];
+
+    // An old commit of mine followed by a fresh upstream commit merged into the repo.
+    let forked_path = settings.projects_dirs[0].join("forked_project");
+    std::fs::create_dir(&forked_path).unwrap();
+    let commit_as = |email: &str, date: &str, file: &str| {
+        std::fs::write(forked_path.join(file), email).unwrap();
+        for args in [vec!["add", "."], vec!["commit", "-m", file]] {
+            let output = Command::new("git")
+                .args(&args)
+                .env("GIT_AUTHOR_NAME", email)
+                .env("GIT_AUTHOR_EMAIL", email)
+                .env("GIT_COMMITTER_NAME", email)
+                .env("GIT_COMMITTER_EMAIL", email)
+                .env("GIT_AUTHOR_DATE", date)
+                .env("GIT_COMMITTER_DATE", date)
+                .current_dir(&forked_path)
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+        }
+    };
+    Command::new("git")
+        .arg("init")
+        .current_dir(&forked_path)
+        .output()
+        .unwrap();
+    commit_as("me@example.com", "2023-01-01T12:00:00Z", "mine.txt");
+    commit_as(
+        "upstream@example.com",
+        &chrono::Utc::now().to_rfc3339(),
+        "upstream.txt",
+    );
+
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+
+    assert!(settings.archive_dir.join("forked_project").exists());
+}
+
+#[test]
+fn it_counts_untracked_files_as_activity_when_enabled() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.count_untracked_files = true;
+
+    let old_project_path = settings.projects_dirs[0].join("old_project");
+    std::fs::write(old_project_path.join("notes.md"), "new idea").unwrap();
+
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+
+    assert!(
+        old_project_path.exists(),
+        "Fresh untracked work keeps the project active"
+    );
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+}
+
+#[test]
+fn it_records_code_statistics_when_enabled() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.collect_code_stats = true;
+    let old_project_path = settings.projects_dirs[0].join("old_project");
+    std::fs::write(
+        old_project_path.join("main.rs"),
+        "// entry point\nfn main() {\n    println!(\"hi\");\n}\n",
+    )
+    .unwrap();
+
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+
+    let records = archiver.get_archive_records().unwrap();
+    let stats = records[0].code_stats.as_ref().unwrap();
+    assert_eq!(stats.primary_language(), Some("Rust"));
+    assert_eq!(stats.languages["Rust"].code, 3);
+    assert_eq!(stats.languages["Rust"].comments, 1);
+}
+
+#[test]
+fn it_applies_inactivity_thresholds_by_project_type() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    let ten_days_ago = (chrono::Utc::now() - chrono::Duration::days(10)).to_rfc3339();
+    for (name, manifest) in [("web_app", "package.json"), ("cli_tool", "Cargo.toml")] {
+        let path = settings.projects_dirs[0].join(name);
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join(manifest), "").unwrap();
+        helpers::init_git_repo_with_date(&path, "commit", &ten_days_ago);
+    }
+    settings.inactivity_days_by_type = [(ProjectType::Node, 7), (ProjectType::Rust, 90)].into();
+
+    let plan = Archiver::new(settings).run_archive_process(true).unwrap();
+    let mut planned: Vec<String> = plan
+        .into_iter()
+        .filter_map(|action| match action {
+            ActionPlan::Archive { project_name, .. } => Some(project_name.to_string()),
+            ActionPlan::Nothing => None,
+        })
+        .collect();
+    planned.sort();
+    assert_eq!(planned, ["old_project", "web_app"]);
+}
+
+#[test]
+fn it_applies_cleanup_rules_before_archiving() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    let project_path = settings.projects_dirs[0].join("old_project");
+    std::fs::write(project_path.join("package.json"), "{}").unwrap();
+    std::fs::create_dir(project_path.join("node_modules")).unwrap();
+    std::fs::write(project_path.join("node_modules/dep.js"), vec![b'x'; 4096]).unwrap();
+    settings.cleanup_rules = vec![CleanupRule {
+        detection_file: "package.json".to_string(),
+        folders_to_delete: vec!["node_modules".to_string(), "../escape".to_string()],
+        keep: vec![],
+    }];
+    let archiver = Archiver::new(settings.clone());
+
+    let plan = archiver.run_with_summary(true, &[]);
+    assert_eq!(plan.bytes_cleaned, 4096);
+    let outcome = plan.with_outcome(Outcome::WouldArchive).next().unwrap();
+    assert_eq!(outcome.cleaned, [std::path::PathBuf::from("node_modules")]);
+    assert!(project_path.join("node_modules").exists());
+
+    let run = archiver.run_with_summary(false, &[]);
+    assert!(run.error.is_none());
+    assert_eq!(run.bytes_cleaned, 4096);
+    let archived = settings.archive_dir.join("old_project");
+    assert!(archived.join("package.json").exists());
+    assert!(!archived.join("node_modules").exists());
+}
+
+#[test]
+fn it_keeps_protected_paths_inside_cleaned_folders() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    let project_path = settings.projects_dirs[0].join("old_project");
+    let build = project_path.join("build");
+    std::fs::write(project_path.join("Makefile"), "all:").unwrap();
+    std::fs::create_dir_all(build.join("cache/deep")).unwrap();
+    std::fs::create_dir_all(build.join("public/uploads/2024")).unwrap();
+    std::fs::write(build.join("cache/deep/obj.o"), vec![b'x'; 4096]).unwrap();
+    std::fs::write(build.join("cache/deep/.env"), "SECRET=1").unwrap();
+    std::fs::write(build.join("app.sqlite"), "db").unwrap();
+    std::fs::write(build.join("public/uploads/2024/photo.jpg"), "jpg").unwrap();
+    settings.cleanup_rules = vec![CleanupRule {
+        detection_file: "Makefile".to_string(),
+        folders_to_delete: vec!["build".to_string()],
+        keep: vec!["**/uploads/**".to_string()],
+    }];
+    settings.cleanup_keep = vec!["**/.env".to_string(), "**/*.sqlite".to_string()];
+    let archiver = Archiver::new(settings.clone());
+
+    let plan = archiver.run_with_summary(true, &[]);
+    assert_eq!(plan.bytes_cleaned, 4096);
+
+    let run = archiver.run_with_summary(false, &[]);
+    assert!(run.error.is_none());
+    let archived = settings.archive_dir.join("old_project/build");
+    assert!(archived.join("cache/deep/.env").exists());
+    assert!(archived.join("app.sqlite").exists());
+    assert!(archived.join("public/uploads/2024/photo.jpg").exists());
+    assert!(!archived.join("cache/deep/obj.o").exists());
+}
+
+#[test]
+fn it_forecasts_projects_about_to_become_candidates() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let soon_path = settings.projects_dirs[0].join("soon_project");
+    std::fs::create_dir(&soon_path).unwrap();
+    let last_commit = chrono::Utc::now() - chrono::Duration::days(25);
+    helpers::init_git_repo_with_date(&soon_path, "commit", &last_commit.to_rfc3339());
+
+    let archiver = Archiver::new(settings);
+
+    let upcoming = archiver.forecast(chrono::Duration::days(10)).unwrap();
+    assert_eq!(upcoming.len(), 1);
+    assert_eq!(upcoming[0].project.name, "soon_project");
+    assert_eq!(
+        (upcoming[0].candidate_at - chrono::Utc::now()).num_days(),
+        4
+    );
+
+    assert!(archiver
+        .forecast(chrono::Duration::days(2))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn it_summarizes_a_run() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+
+    let summary = archiver.run_with_summary(false, &[]);
+
+    assert!(summary.error.is_none());
+    assert!(summary.scanned >= 2);
+    assert_eq!(summary.archived, 1);
+    assert_eq!(summary.failed, 0);
+    assert_eq!(
+        summary.bytes_moved,
+        "old commit".len() as u64 + git_dir_size(&settings)
+    );
+    let archived: Vec<_> = summary.with_outcome(Outcome::Archived).collect();
+    assert_eq!(archived[0].name, "old_project");
+
+    let path = temp_dir.path().join("state/last-run.json");
+    summary.write(&path).unwrap();
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(written["archived"], 1);
+    assert_eq!(written["projects"][0]["outcome"], "archived");
+}
+
+/// Size of the `.git` directory of the archived `old_project`.
+fn git_dir_size(settings: &archiver_core::Settings) -> u64 {
+    walkdir::WalkDir::new(settings.archive_dir.join("old_project/.git"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.metadata().unwrap().len())
+        .sum()
+}
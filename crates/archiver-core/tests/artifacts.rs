@@ -0,0 +1,99 @@
+use archiver_core::config::CleanupRule;
+use archiver_core::{Archiver, Settings};
+use chrono::{Duration, Utc};
+use std::fs;
+use std::path::Path;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+fn add_node_modules(project: &Path, size: usize) {
+    fs::write(project.join("package.json"), "{}").unwrap();
+    fs::create_dir_all(project.join("node_modules/dep")).unwrap();
+    fs::write(project.join("node_modules/dep/index.js"), vec![b'x'; size]).unwrap();
+}
+
+fn artifact_settings(settings: Settings) -> Settings {
+    Settings {
+        artifact_days: Some(7),
+        cleanup_rules: vec![CleanupRule {
+            detection_file: "package.json".to_string(),
+            folders_to_delete: vec!["node_modules".to_string()],
+            keep: vec![],
+        }],
+        ..settings
+    }
+}
+
+#[test]
+fn it_moves_only_the_artifacts_of_idle_projects() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let projects_dir = settings.projects_dirs[0].clone();
+    let idle = projects_dir.join("idle_project");
+    fs::create_dir(&idle).unwrap();
+    let ten_days_ago = (Utc::now() - Duration::days(10)).to_rfc3339();
+    init_git_repo_with_date(&idle, "commit", &ten_days_ago);
+    for project in [&idle, &projects_dir.join("new_project")] {
+        add_node_modules(project, 2048);
+    }
+    add_node_modules(&projects_dir.join("old_project"), 2048);
+    let archiver = Archiver::new(artifact_settings(settings.clone()));
+
+    let plan = archiver.archive_artifacts(true).unwrap();
+    assert_eq!(plan.len(), 1);
+    assert_eq!(plan[0].name, "idle_project");
+    assert_eq!(plan[0].bytes, 2048);
+    assert!(idle.join("node_modules").exists());
+    assert!(archiver.artifact_records().unwrap().is_empty());
+
+    let moved = archiver.archive_artifacts(false).unwrap();
+    assert_eq!(moved.len(), 1);
+    assert!(!idle.join("node_modules").exists());
+    assert!(idle.join("package.json").exists());
+    assert!(moved[0]
+        .archive_path
+        .join("node_modules/dep/index.js")
+        .exists());
+    assert!(projects_dir.join("new_project/node_modules").exists());
+    assert!(projects_dir.join("old_project/node_modules").exists());
+    assert_eq!(archiver.artifact_records().unwrap(), moved);
+
+    // Artifacts rebuilt since replace the archived ones.
+    add_node_modules(&idle, 4096);
+    let again = archiver.archive_artifacts(false).unwrap();
+    assert_eq!(again[0].bytes, 4096);
+    let records = archiver.artifact_records().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].bytes, 4096);
+
+    let disabled = Archiver::new(Settings {
+        artifact_days: None,
+        ..archiver.settings().clone()
+    });
+    assert!(disabled.archive_artifacts(true).unwrap().is_empty());
+}
+
+#[test]
+fn it_restores_archived_artifacts_unless_rebuilt() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let idle = settings.projects_dirs[0].join("idle_project");
+    fs::create_dir(&idle).unwrap();
+    let ten_days_ago = (Utc::now() - Duration::days(10)).to_rfc3339();
+    init_git_repo_with_date(&idle, "commit", &ten_days_ago);
+    add_node_modules(&idle, 1024);
+    let archiver = Archiver::new(artifact_settings(settings));
+    let moved = archiver.archive_artifacts(false).unwrap();
+
+    fs::create_dir(idle.join("node_modules")).unwrap();
+    assert!(archiver.restore_artifacts("idle_project").is_err());
+    assert_eq!(archiver.artifact_records().unwrap().len(), 1);
+
+    fs::remove_dir(idle.join("node_modules")).unwrap();
+    archiver.restore_artifacts("idle_project").unwrap();
+    assert!(idle.join("node_modules/dep/index.js").exists());
+    assert!(!moved[0].archive_path.exists());
+    assert!(archiver.artifact_records().unwrap().is_empty());
+    assert!(archiver.restore_artifacts("idle_project").is_err());
+}
@@ -0,0 +1,115 @@
+use archiver_core::{ArchiveRoute, Archiver, Settings};
+use chrono::{Duration, Utc};
+use std::fs;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+/// Archives the stale projects, then backdates every record by `days`.
+fn archive_and_backdate(settings: &Settings, days: i64) {
+    Archiver::new(settings.clone())
+        .run_archive_process(false)
+        .unwrap();
+    let log_path = settings.archive_dir.join("archive.json");
+    let mut records: Vec<serde_json::Value> =
+        serde_json::from_str(&fs::read_to_string(&log_path).unwrap()).unwrap();
+    for record in &mut records {
+        record["archived_at"] = serde_json::json!(Utc::now() - Duration::days(days));
+    }
+    fs::write(&log_path, serde_json::to_string(&records).unwrap()).unwrap();
+}
+
+#[test]
+fn it_enforces_the_safety_floor_over_days_before_delete() {
+    let settings = Settings {
+        inactivity_days: 30,
+        days_before_delete: 10,
+        delete_safety_multiplier: 3,
+        enable_auto_delete: true,
+        ..Default::default()
+    };
+
+    let policy = Archiver::new(settings).deletion_policy();
+
+    assert_eq!(policy.min_archived_days, 90);
+}
+
+#[test]
+fn it_deletes_only_projects_archived_for_the_full_period() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.enable_auto_delete = true;
+    settings.days_before_delete = 10;
+    archive_and_backdate(&settings, 45);
+
+    // 45 days is past `days_before_delete` but within the 2x30 day safety floor.
+    let archiver = Archiver::new(settings.clone());
+    assert!(archiver.run_auto_delete(false).unwrap().is_empty());
+    assert!(settings.archive_dir.join("old_project").exists());
+
+    archive_and_backdate(&settings, 61);
+    let deleted = archiver.run_auto_delete(false).unwrap();
+
+    assert_eq!(deleted.len(), 1);
+    assert!(!settings.archive_dir.join("old_project").exists());
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+}
+
+#[test]
+fn it_never_deletes_projects_restored_by_hand() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.enable_auto_delete = true;
+    archive_and_backdate(&settings, 400);
+
+    // The project was copied back without going through `restore`.
+    fs::create_dir_all(settings.projects_dirs[0].join("old_project")).unwrap();
+
+    let archiver = Archiver::new(settings.clone());
+    assert!(archiver.run_auto_delete(false).unwrap().is_empty());
+    assert!(settings.archive_dir.join("old_project").exists());
+}
+
+#[test]
+fn it_never_deletes_when_the_period_is_too_long_to_represent() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.enable_auto_delete = true;
+    settings.days_before_delete = u64::MAX;
+    archive_and_backdate(&settings, 3650);
+
+    let archiver = Archiver::new(settings.clone());
+    assert!(archiver.run_auto_delete(false).unwrap().is_empty());
+    assert!(settings.archive_dir.join("old_project").exists());
+}
+
+#[test]
+fn it_deletes_projects_sharing_a_name_in_different_archive_directories() {
+    setup_tracing();
+    let (temp_dir, mut settings) = setup_test_env();
+    let work = temp_dir.path().join("work");
+    init_git_repo_with_date(&work.join("old_project"), "old", "2023-01-01T12:00:00Z");
+    let work_archive = temp_dir.path().join("work-archive");
+    settings.projects_dirs.push(work.clone());
+    settings.archive_routes = vec![ArchiveRoute {
+        name: "work".to_string(),
+        archive_dir: work_archive.clone(),
+        project_types: vec![],
+        roots: vec![work],
+        min_size_mb: None,
+        max_size_mb: None,
+    }];
+    settings.enable_auto_delete = true;
+    settings.days_before_delete = 10;
+    archive_and_backdate(&settings, 61);
+
+    let archiver = Archiver::new(settings.clone());
+    assert_eq!(archiver.get_archive_records().unwrap().len(), 2);
+    assert!(archiver.restore_project("old_project").is_err());
+
+    let deleted = archiver.run_auto_delete(false).unwrap();
+    assert_eq!(deleted.len(), 2);
+    assert!(!settings.archive_dir.join("old_project").exists());
+    assert!(!work_archive.join("old_project").exists());
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+}
@@ -65,5 +65,8 @@ fn core_l_01_it_fails_gracefully_with_corrupt_log_file() {
 
     assert!(result.is_err(), "Expected archiving to fail due to corrupt log");
     let error = result.err().unwrap();
-    assert!(matches!(error, Error::Json(_)), "Expected a JSON deserialization error");
+    assert!(
+        matches!(error, Error::CorruptArchiveLog { .. }),
+        "Expected a corrupt archive log error"
+    );
 }
@@ -0,0 +1,50 @@
+use archiver_core::migrate::{self, CURRENT_VERSION};
+use std::fs;
+
+#[test]
+fn it_migrates_a_legacy_settings_file_and_keeps_a_backup() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("settings.toml");
+    let legacy = "projects_dir = \"/home/me/projects\"\narchive_dir = \"/home/me/.archive\"\ninactivity_days = 45\nfavourite_color = \"blue\"\n";
+    fs::write(&path, legacy).unwrap();
+
+    let report = migrate::migrate_file(&path).unwrap();
+    assert_eq!(report.from_version, 1);
+    assert_eq!(report.applied.len(), 1);
+    assert_eq!(report.unknown_keys, ["favourite_color"]);
+
+    let backup = report.backup.unwrap();
+    assert_eq!(backup, temp_dir.path().join("settings.toml.v1.bak"));
+    assert_eq!(fs::read_to_string(&backup).unwrap(), legacy);
+
+    let migrated: toml::Table = fs::read_to_string(&path).unwrap().parse().unwrap();
+    assert_eq!(
+        migrated["projects_dirs"].as_array().unwrap()[0].as_str(),
+        Some("/home/me/projects")
+    );
+    assert!(!migrated.contains_key("projects_dir"));
+    assert_eq!(
+        migrated["version"].as_integer(),
+        Some(CURRENT_VERSION.into())
+    );
+    assert_eq!(migrated["inactivity_days"].as_integer(), Some(45));
+
+    // A current file is left untouched.
+    let again = migrate::migrate_file(&path).unwrap();
+    assert!(again.applied.is_empty());
+    assert!(again.backup.is_none());
+}
+
+#[test]
+fn it_leaves_current_settings_files_alone() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("settings.toml");
+    let current = "projects_dirs = [\"/a\", \"/b\"]\nexclude = [\"dotfiles\"]\n";
+    fs::write(&path, current).unwrap();
+
+    let report = migrate::migrate_file(&path).unwrap();
+    assert!(report.applied.is_empty());
+    assert!(report.unknown_keys.is_empty());
+    assert_eq!(fs::read_to_string(&path).unwrap(), current);
+    assert!(!temp_dir.path().join("settings.toml.v1.bak").exists());
+}
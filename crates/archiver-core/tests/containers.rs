@@ -0,0 +1,134 @@
+use archiver_core::{containers, ActionPlan, Archiver, Settings};
+use std::io::{Read, Write};
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+/// Serves `response` to every request on a Unix socket in `dir`, like a container engine,
+/// counting the requests.
+fn fake_engine(dir: &Path, status: &str, body: String) -> (PathBuf, Arc<AtomicUsize>) {
+    let socket = dir.join("docker.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let response = format!(
+        "HTTP/1.0 {}\r\nContent-Type: application/json\r\n\r\n{}",
+        status, body
+    );
+    let requests = Arc::new(AtomicUsize::new(0));
+    let served = requests.clone();
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request);
+            let _ = stream.write_all(response.as_bytes());
+            served.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    (socket, requests)
+}
+
+fn containers_json(project: &Path) -> String {
+    serde_json::json!([
+        {
+            "Names": ["/api-db-1"],
+            "Mounts": [
+                { "Type": "bind", "Source": project.join("data"), "Destination": "/var/lib/db" },
+                { "Type": "volume", "Source": "/var/lib/docker/volumes/x", "Destination": "/x" }
+            ]
+        },
+        { "Names": ["/unrelated"], "Mounts": [] }
+    ])
+    .to_string()
+}
+
+#[test]
+fn it_lists_running_containers_and_their_bind_mounts() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project = temp_dir.path().join("api");
+    let (socket, _) = fake_engine(temp_dir.path(), "200 OK", containers_json(&project));
+
+    let running = containers::running(&socket).unwrap();
+    assert_eq!(running.len(), 2);
+    assert_eq!(running[0].name, "api-db-1");
+    assert_eq!(running[0].mounts, [project.join("data")]);
+    assert!(running[0].mounts_from(&project));
+    assert!(!running[0].mounts_from(&temp_dir.path().join("ap")));
+    assert!(!running[1].mounts_from(&project));
+
+    let missing = containers::running(&temp_dir.path().join("nope.sock")).unwrap();
+    assert!(missing.is_empty());
+}
+
+#[test]
+fn it_skips_projects_mounted_by_running_containers() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let project = settings.projects_dirs[0].join("old_project");
+    let (socket, _) = fake_engine(temp_dir.path(), "200 OK", containers_json(&project));
+    let archiver = Archiver::new(Settings {
+        docker_socket: Some(socket),
+        ..settings.clone()
+    });
+
+    let plan = archiver.run_archive_process(true).unwrap();
+    assert!(plan
+        .iter()
+        .all(|action| matches!(action, ActionPlan::Nothing)));
+    let summary = archiver.run_with_summary(true, &[]);
+    let skipped = &summary.projects[0];
+    assert_eq!(skipped.name, "old_project");
+    assert_eq!(
+        skipped.reason.as_deref(),
+        Some("mounted by running container(s) api-db-1")
+    );
+
+    let unchecked = Archiver::new(Settings {
+        check_containers: false,
+        ..archiver.settings().clone()
+    });
+    let plan = unchecked.run_archive_process(true).unwrap();
+    assert!(matches!(plan[0], ActionPlan::Archive { .. }));
+}
+
+#[test]
+fn it_archives_when_the_engine_cannot_be_queried() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let (socket, _) = fake_engine(
+        temp_dir.path(),
+        "500 Internal Server Error",
+        "{}".to_string(),
+    );
+    let archiver = Archiver::new(Settings {
+        docker_socket: Some(socket),
+        ..settings
+    });
+
+    let plan = archiver.run_archive_process(true).unwrap();
+    assert!(matches!(plan[0], ActionPlan::Archive { .. }));
+}
+
+#[test]
+fn it_lists_the_containers_once_per_run() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    for name in ["old_api", "old_web"] {
+        let project = settings.projects_dirs[0].join(name);
+        std::fs::create_dir_all(&project).unwrap();
+        init_git_repo_with_date(&project, "old commit", "2023-01-01T12:00:00Z");
+    }
+    let unrelated = temp_dir.path().join("elsewhere");
+    let (socket, requests) = fake_engine(temp_dir.path(), "200 OK", containers_json(&unrelated));
+    let archiver = Archiver::new(Settings {
+        docker_socket: Some(socket),
+        ..settings
+    });
+
+    let plan = archiver.run_archive_process(true).unwrap();
+    assert!(plan.len() >= 3, "every old project is a candidate");
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,80 @@
+use archiver_core::{Archiver, Settings};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+use std::thread;
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+fn git(path: &std::path::Path, args: &[&str]) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed: {output:?}");
+}
+
+#[test]
+fn it_fetches_the_remote_of_a_project() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let project = settings.projects_dirs[0].join("new_project");
+    let upstream = temp_dir.path().join("upstream.git");
+    git(
+        temp_dir.path(),
+        &["clone", "--bare", project.to_str().unwrap(), "upstream.git"],
+    );
+    git(
+        &project,
+        &["remote", "add", "origin", upstream.to_str().unwrap()],
+    );
+    let archiver = Archiver::new(settings);
+
+    archiver.fetch(&project, "origin").unwrap();
+
+    let repo = git2::Repository::open(&project).unwrap();
+    let fetched = repo
+        .references_glob("refs/remotes/origin/*")
+        .unwrap()
+        .count();
+    assert!(fetched > 0);
+    assert!(archiver.fetch(&project, "missing").is_err());
+}
+
+#[test]
+fn it_gives_up_when_the_server_rejects_every_credential() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut request = [0; 4096];
+            let _ = stream.read(&mut request);
+            let _ = stream.write_all(
+                b"HTTP/1.1 401 Unauthorized\r\n\
+                  WWW-Authenticate: Basic realm=\"test\"\r\n\
+                  Content-Length: 0\r\nConnection: close\r\n\r\n",
+            );
+        }
+    });
+    let project = settings.projects_dirs[0].join("new_project");
+    git(
+        &project,
+        &[
+            "remote",
+            "add",
+            "origin",
+            &format!("http://127.0.0.1:{port}/private.git"),
+        ],
+    );
+    let archiver = Archiver::new(Settings {
+        network_timeout_secs: 5,
+        ..settings
+    });
+
+    assert!(archiver.fetch(&project, "origin").is_err());
+}
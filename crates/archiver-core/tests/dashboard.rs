@@ -0,0 +1,58 @@
+use archiver_core::{dashboard, Archiver, RunSummary, UsageSample};
+use chrono::Utc;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+fn get(addr: std::net::SocketAddr, request: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn it_renders_candidates_archive_and_recent_runs() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings);
+
+    let page = dashboard::render(&archiver, None, &[]).unwrap();
+    assert!(page.contains("<td>old_project</td>"));
+    assert!(page.contains("<tr><th>Last run</th><td>never</td></tr>"));
+
+    let summary = archiver.run_with_summary(false, &[]);
+    let sample = UsageSample {
+        archived_projects: 1,
+        ..archiver.usage_sample(0)
+    };
+    let page = dashboard::render(&archiver, Some(&summary), &[sample]).unwrap();
+    assert!(page.contains("<tr><th>Archive candidates</th><td>0</td></tr>"));
+    assert!(page.contains("<tr><th>Archived projects</th><td>1 ("));
+    assert!(page.contains(&Utc::now().format("<td>%Y-%m-%d").to_string()));
+    assert!(!page.contains("Last run failed"));
+
+    let mut failed = RunSummary::start(false);
+    failed.finish(Some("<disk full>".to_string()));
+    let page = dashboard::render(&archiver, Some(&failed), &[]).unwrap();
+    assert!(page.contains("Last run failed: &lt;disk full&gt;"));
+}
+
+#[test]
+fn it_serves_the_dashboard_read_only() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || dashboard::serve(&Archiver::new(settings), listener));
+
+    let page = get(addr, "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    assert!(page.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(page.contains("<td>old_project</td>"));
+    assert!(get(addr, "GET /other HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404"));
+    assert!(get(addr, "POST / HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 405"));
+}
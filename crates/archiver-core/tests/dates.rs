@@ -0,0 +1,38 @@
+use archiver_core::{DateFormatter, Settings};
+use chrono::{DateTime, Utc};
+
+fn at(timestamp: &str) -> DateTime<Utc> {
+    timestamp.parse().unwrap()
+}
+
+#[test]
+fn it_shows_dates_in_utc_by_default() {
+    let dates = Settings::default().date_formatter().unwrap();
+
+    assert_eq!(dates, DateFormatter::default());
+    assert_eq!(dates.date(at("2024-01-01T23:30:00Z")), "2024-01-01");
+    assert_eq!(
+        dates.date_time(at("2024-01-01T23:30:00Z")),
+        "2024-01-01 23:30 UTC"
+    );
+}
+
+#[test]
+fn it_shows_dates_in_the_configured_timezone_and_format() {
+    let dates = DateFormatter::new("Europe/Madrid", "%d/%m/%Y").unwrap();
+
+    // Past midnight in Madrid, still the previous day in UTC.
+    assert_eq!(dates.date(at("2024-01-01T23:30:00Z")), "02/01/2024");
+    assert_eq!(
+        dates.date_time(at("2024-07-01T12:00:00Z")),
+        "01/07/2024 14:00 CEST"
+    );
+    assert!(DateFormatter::new("local", "%Y-%m-%d").is_ok());
+    assert!(DateFormatter::new("utc", "%Y-%m-%d").is_ok());
+}
+
+#[test]
+fn it_rejects_unknown_timezones_and_formats() {
+    assert!(DateFormatter::new("Mars/Olympus", "%Y-%m-%d").is_err());
+    assert!(DateFormatter::new("UTC", "%Y-%m-%Q").is_err());
+}
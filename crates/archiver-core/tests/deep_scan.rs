@@ -0,0 +1,120 @@
+use archiver_core::{ActionPlan, ArchiveLayout, Archiver};
+use std::fs;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+fn planned_names(plan: Vec<ActionPlan>) -> Vec<String> {
+    let mut names: Vec<String> = plan
+        .into_iter()
+        .filter_map(|action| match action {
+            ActionPlan::Archive { project_name, .. } => Some(project_name.to_string()),
+            ActionPlan::Nothing => None,
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn it_treats_workspaces_as_a_single_project_when_deep_scanning() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.scan_depth = 3;
+    settings.inactivity_days = 0;
+
+    let group = settings.projects_dirs[0].join("group");
+    let workspace = group.join("mono");
+    for member in ["crates/a", "crates/b"] {
+        fs::create_dir_all(workspace.join(member)).unwrap();
+        fs::write(workspace.join(member).join("Cargo.toml"), "[package]").unwrap();
+    }
+    fs::write(
+        workspace.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\"]",
+    )
+    .unwrap();
+
+    let archiver = Archiver::new(settings);
+    let plan = archiver.run_archive_process(true).unwrap();
+
+    let names = planned_names(plan);
+    assert!(names.contains(&"mono".to_string()));
+    assert!(names.contains(&"old_project".to_string()));
+    assert!(!names.contains(&"group".to_string()));
+    assert!(!names.contains(&"a".to_string()) && !names.contains(&"crates".to_string()));
+}
+
+#[test]
+fn it_does_not_descend_into_directories_without_deep_scanning() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.inactivity_days = 0;
+    fs::create_dir_all(settings.projects_dirs[0].join("group/nested")).unwrap();
+
+    let archiver = Archiver::new(settings);
+    let names = planned_names(archiver.run_archive_process(true).unwrap());
+
+    assert!(names.contains(&"group".to_string()));
+    assert!(!names.contains(&"nested".to_string()));
+}
+
+#[test]
+fn it_preserves_org_style_layouts_in_the_archive() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.scan_depth = 3;
+    settings.layout = ArchiveLayout::Nested;
+
+    for org in ["org1", "org2"] {
+        let repo = settings.projects_dirs[0]
+            .join("github.com")
+            .join(org)
+            .join("api");
+        fs::create_dir_all(&repo).unwrap();
+        init_git_repo_with_date(&repo, "old commit", "2023-01-01T12:00:00Z");
+    }
+
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+
+    let archived = settings.archive_dir.join("github.com/org1/api");
+    assert!(archived.join("file.txt").exists());
+    assert!(settings.archive_dir.join("github.com/org2/api").exists());
+    assert!(settings.archive_dir.join("old_project").exists());
+
+    // Both repositories are called `api`, so the bare name is ambiguous.
+    assert!(archiver.restore_project("api").is_err());
+
+    archiver.restore_project("github.com/org1/api").unwrap();
+    assert!(settings.projects_dirs[0]
+        .join("github.com/org1/api/file.txt")
+        .exists());
+    assert!(!settings.archive_dir.join("github.com/org1").exists());
+    assert!(settings.archive_dir.join("github.com/org2/api").exists());
+
+    archiver.restore_project("api").unwrap();
+    assert!(settings.projects_dirs[0]
+        .join("github.com/org2/api")
+        .exists());
+}
+
+#[test]
+fn it_keeps_projects_without_markers_made_only_of_directories_whole() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.scan_depth = 3;
+    settings.inactivity_days = 0;
+
+    let notes = settings.projects_dirs[0].join("notes");
+    for dir in ["src", "docs"] {
+        fs::create_dir_all(notes.join(dir)).unwrap();
+        fs::write(notes.join(dir).join("index.md"), "#").unwrap();
+    }
+
+    let archiver = Archiver::new(settings);
+    let names = planned_names(archiver.run_archive_process(true).unwrap());
+
+    assert!(names.contains(&"notes".to_string()));
+    assert!(!names.contains(&"src".to_string()) && !names.contains(&"docs".to_string()));
+}
@@ -0,0 +1,110 @@
+use archiver_core::{Archiver, DuplicateKey, Settings};
+use chrono::{Duration, Utc};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_tracing};
+
+fn repo_with_remote(path: &Path, remote: &str, days_ago: i64) {
+    fs::create_dir_all(path).unwrap();
+    let date = (Utc::now() - Duration::days(days_ago)).to_rfc3339();
+    init_git_repo_with_date(path, "commit", &date);
+    Command::new("git")
+        .args(["remote", "add", "origin", remote])
+        .current_dir(path)
+        .output()
+        .unwrap();
+}
+
+fn plain_dir(path: &Path, content: &str) {
+    fs::create_dir_all(path.join("src")).unwrap();
+    fs::write(path.join("src/notes.txt"), content).unwrap();
+}
+
+fn settings(root: &Path) -> Settings {
+    Settings {
+        projects_dirs: vec![root.join("work"), root.join("side")],
+        archive_dir: root.join("archive"),
+        inactivity_days: 30,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn it_groups_copies_by_remote_and_by_content() {
+    setup_tracing();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    repo_with_remote(
+        &root.join("work/repo"),
+        "https://github.com/Owner/Repo.git",
+        2,
+    );
+    repo_with_remote(
+        &root.join("side/repo-clone"),
+        "git@github.com:owner/repo",
+        10,
+    );
+    repo_with_remote(
+        &root.join("side/other"),
+        "https://github.com/owner/other",
+        1,
+    );
+    plain_dir(&root.join("work/notes"), "same");
+    plain_dir(&root.join("side/notes-copy"), "same");
+    plain_dir(&root.join("side/notes-edited"), "diff");
+
+    let groups = Archiver::new(settings(root)).find_duplicates().unwrap();
+    assert_eq!(groups.len(), 2);
+
+    let by_remote = groups
+        .iter()
+        .find(|g| g.key == DuplicateKey::Remote("github.com/owner/repo".to_string()))
+        .unwrap();
+    let names: Vec<_> = by_remote.projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["repo", "repo-clone"]);
+
+    let by_content = groups
+        .iter()
+        .find(|g| matches!(g.key, DuplicateKey::Content(_)))
+        .unwrap();
+    let mut names: Vec<_> = by_content
+        .projects
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["notes", "notes-copy"]);
+}
+
+#[test]
+fn it_archives_older_copies_when_enabled() {
+    setup_tracing();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    repo_with_remote(&root.join("work/repo"), "https://github.com/owner/repo", 2);
+    repo_with_remote(
+        &root.join("side/repo-clone"),
+        "ssh://git@github.com:22/owner/repo.git",
+        10,
+    );
+
+    // Both copies are active: nothing is archived by default.
+    Archiver::new(settings(root))
+        .run_archive_process(false)
+        .unwrap();
+    assert!(root.join("side/repo-clone").is_dir());
+
+    let archiver = Archiver::new(Settings {
+        archive_duplicates: true,
+        ..settings(root)
+    });
+    archiver.run_archive_process(false).unwrap();
+    assert!(root.join("work/repo").is_dir());
+    assert!(!root.join("side/repo-clone").exists());
+    let records = archiver.get_archive_records().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].name, "repo-clone");
+}
@@ -0,0 +1,40 @@
+use archiver_core::{Archiver, ErrorCode};
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+#[test]
+fn it_assigns_stable_codes_to_errors() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+
+    let not_found = archiver.restore_project("missing").unwrap_err();
+    assert_eq!(not_found.code(), ErrorCode::ProjectNotFound);
+    assert_eq!(not_found.code().as_str(), "A006");
+
+    let unsafe_name = archiver.delete_project("../old_project").unwrap_err();
+    assert_eq!(unsafe_name.code(), ErrorCode::UnsafePath);
+
+    std::fs::write(settings.archive_dir.join("archive.json"), "{ not json").unwrap();
+    let corrupt = archiver.get_archive_records().unwrap_err();
+    assert_eq!(corrupt.code(), ErrorCode::CorruptArchiveLog);
+
+    // Other state files are not the archive log and have their own code.
+    std::fs::write(settings.archive_dir.join("restores.json"), "{ not json").unwrap();
+    let corrupt = archiver.restore_counts().unwrap_err();
+    assert_eq!(corrupt.code(), ErrorCode::CorruptStateFile);
+    assert_eq!(corrupt.code().as_str(), "A016");
+}
+
+#[test]
+fn it_parses_codes_case_insensitively() {
+    assert_eq!(
+        "a002".parse::<ErrorCode>().unwrap(),
+        ErrorCode::CorruptArchiveLog
+    );
+    assert!("A999".parse::<ErrorCode>().is_err());
+
+    let codes: std::collections::HashSet<_> = ErrorCode::ALL.iter().map(|c| c.as_str()).collect();
+    assert_eq!(codes.len(), ErrorCode::ALL.len(), "codes must be unique");
+}
@@ -0,0 +1,91 @@
+use archiver_core::{Archiver, RestoreFilter, Settings};
+use std::fs;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+fn group(name: &str) -> RestoreFilter {
+    RestoreFilter {
+        group: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn it_groups_projects_by_their_root() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(Settings {
+        group_by_root: true,
+        ..settings.clone()
+    });
+
+    archiver.run_archive_process(false).unwrap();
+
+    let records = archiver.get_archive_records().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].group.as_deref(), Some("projects"));
+    assert_eq!(
+        records[0].archive_path,
+        settings.archive_dir.join("projects").join("old_project")
+    );
+    assert!(records[0].archive_path.join("file.txt").exists());
+
+    archiver.restore_project("old_project").unwrap();
+    assert!(settings.projects_dirs[0].join("old_project").exists());
+}
+
+#[test]
+fn it_restores_and_deletes_one_group_at_a_time() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let client = Archiver::new(Settings {
+        archive_group: Some("client-x".to_string()),
+        group_by_root: true,
+        ..settings.clone()
+    });
+    client.run_archive_process(false).unwrap();
+
+    let other_path = settings.projects_dirs[0].join("other_project");
+    fs::create_dir(&other_path).unwrap();
+    init_git_repo_with_date(&other_path, "old commit", "2023-01-01T12:00:00Z");
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+
+    let records = archiver.get_archive_records().unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(settings
+        .archive_dir
+        .join("client-x/old_project/file.txt")
+        .exists());
+    assert!(settings.archive_dir.join("other_project").exists());
+
+    assert_eq!(archiver.restore_matching(&group("unknown")).unwrap(), 0);
+    assert_eq!(archiver.restore_matching(&group("client-x")).unwrap(), 1);
+    assert!(settings.projects_dirs[0].join("old_project").exists());
+    assert!(!other_path.exists());
+
+    client.run_archive_process(false).unwrap();
+    assert_eq!(archiver.delete_matching(&group("client-x")).unwrap(), 1);
+    assert!(!settings.archive_dir.join("client-x/old_project").exists());
+    let records = archiver.get_archive_records().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].name, "other_project");
+    assert_eq!(records[0].group, None);
+}
+
+#[test]
+fn it_rejects_invalid_group_names() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(Settings {
+        archive_group: Some("../outside".to_string()),
+        ..settings.clone()
+    });
+
+    let summary = archiver.run_with_summary(false, &[]);
+    assert_eq!(summary.archived, 0);
+    assert_eq!(summary.failed, 1);
+    assert!(settings.projects_dirs[0].join("old_project").exists());
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+}
@@ -0,0 +1,75 @@
+use archiver_core::{Archiver, HistoryTrim, Settings};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_tracing};
+
+fn git(path: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .env("GIT_AUTHOR_DATE", "2023-03-01T12:00:00Z")
+        .env("GIT_COMMITTER_DATE", "2023-03-01T12:00:00Z")
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// A repository with three old commits, pushed to a bare remote when `push` is set.
+fn old_repo(root: &Path, name: &str, push: bool) {
+    let path = root.join("projects").join(name);
+    fs::create_dir_all(&path).unwrap();
+    init_git_repo_with_date(&path, "first", "2023-01-01T12:00:00Z");
+    for message in ["second", "third"] {
+        fs::write(path.join("file.txt"), message).unwrap();
+        git(&path, &["commit", "-am", message]);
+    }
+    if push {
+        let remote = root.join(format!("{}.git", name));
+        git(root, &["init", "--bare", remote.to_str().unwrap()]);
+        git(
+            &path,
+            &["remote", "add", "origin", remote.to_str().unwrap()],
+        );
+        git(&path, &["push", "-u", "origin", "HEAD"]);
+    }
+}
+
+#[test]
+fn it_trims_the_history_of_pushed_repositories_only() {
+    setup_tracing();
+    let temp_dir = tempfile::tempdir().unwrap();
+    old_repo(temp_dir.path(), "pushed", true);
+    old_repo(temp_dir.path(), "local_only", false);
+    let settings = Settings {
+        projects_dirs: vec![temp_dir.path().join("projects")],
+        archive_dir: temp_dir.path().join("archive"),
+        trim_history: Some(HistoryTrim {
+            keep_commits: Some(1),
+            keep_days: None,
+        }),
+        ..Default::default()
+    };
+    let first_commit = git(
+        &temp_dir.path().join("projects/pushed"),
+        &["rev-list", "--max-parents=0", "HEAD"],
+    );
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+
+    let records = archiver.get_archive_records().unwrap();
+    let record = |name: &str| records.iter().find(|r| r.name == name).unwrap();
+    assert_eq!(record("pushed").trimmed_commits, Some(2));
+    assert_eq!(record("local_only").trimmed_commits, None);
+
+    let pushed = settings.archive_dir.join("pushed");
+    assert_eq!(git(&pushed, &["rev-list", "--count", "HEAD"]), "1");
+    assert_eq!(git(&pushed, &["log", "-1", "--format=%s"]), "third");
+    assert!(git(&pushed, &["status", "--porcelain"]).is_empty());
+    // The dropped commits are pruned, not just hidden.
+    assert!(git(&pushed, &["cat-file", "-t", &first_commit]).is_empty());
+    let local_only = settings.archive_dir.join("local_only");
+    assert_eq!(git(&local_only, &["rev-list", "--count", "HEAD"]), "3");
+}
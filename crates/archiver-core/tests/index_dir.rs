@@ -0,0 +1,53 @@
+use archiver_core::{Archiver, Settings};
+use std::fs;
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+#[test]
+fn it_keeps_the_archive_log_in_a_separate_index_dir() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let index_dir = temp_dir.path().join("state/archiver");
+    let archiver = Archiver::new(Settings {
+        index_dir: Some(index_dir.clone()),
+        ..settings.clone()
+    });
+
+    archiver.run_archive_process(false).unwrap();
+    assert_eq!(archiver.archive_log_path(), index_dir.join("archive.json"));
+    assert!(index_dir.join("archive.json").is_file());
+    assert!(!settings.archive_dir.join("archive.json").exists());
+    assert!(settings.archive_dir.join("old_project").is_dir());
+
+    archiver.restore_project("old_project").unwrap();
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+}
+
+#[test]
+fn it_moves_an_existing_archive_log_into_the_index_dir() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    Archiver::new(settings.clone())
+        .run_archive_process(false)
+        .unwrap();
+    assert!(settings.archive_dir.join("archive.json").is_file());
+
+    let index_dir = temp_dir.path().join("index");
+    let archiver = Archiver::new(Settings {
+        index_dir: Some(index_dir.clone()),
+        ..settings.clone()
+    });
+    let records = archiver.get_archive_records().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].name, "old_project");
+    assert!(index_dir.join("archive.json").is_file());
+    assert!(!settings.archive_dir.join("archive.json").exists());
+
+    assert_eq!(archiver.delete_all().unwrap(), 1);
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+    assert!(fs::read_dir(&settings.archive_dir)
+        .unwrap()
+        .next()
+        .is_none());
+}
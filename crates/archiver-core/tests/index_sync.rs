@@ -0,0 +1,105 @@
+use archiver_core::{Archiver, Settings};
+use std::fs;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+#[test]
+fn it_merges_the_logs_of_every_machine_sharing_the_archive() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.sync_index = true;
+
+    let mut laptop_settings = settings.clone();
+    laptop_settings.machine_id = Some("laptop".to_string());
+    let laptop = Archiver::new(laptop_settings);
+    laptop.run_archive_process(false).unwrap();
+
+    // The desktop archives its own project into the same archive directory.
+    let desktop_projects = settings.projects_dirs[0].with_file_name("desktop_projects");
+    fs::create_dir_all(desktop_projects.join("desktop_project")).unwrap();
+    fs::write(desktop_projects.join("desktop_project/file.txt"), "data").unwrap();
+    let mut desktop_settings = settings.clone();
+    desktop_settings.machine_id = Some("desktop".to_string());
+    desktop_settings.projects_dirs = vec![desktop_projects];
+    desktop_settings.inactivity_days = 0;
+    let desktop = Archiver::new(desktop_settings);
+    desktop.run_archive_process(false).unwrap();
+
+    assert!(settings.archive_dir.join("archive.laptop.json").exists());
+    assert!(settings.archive_dir.join("archive.desktop.json").exists());
+
+    let names = |archiver: &Archiver| -> Vec<String> {
+        let mut names: Vec<_> = archiver
+            .get_archive_records()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.name.to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(names(&laptop), vec!["desktop_project", "old_project"]);
+    assert_eq!(names(&desktop), names(&laptop));
+
+    // Restoring the laptop's project from the desktop updates only the laptop's log.
+    desktop.restore_project("old_project").unwrap();
+    assert_eq!(names(&laptop), vec!["desktop_project"]);
+    let laptop_log = fs::read_to_string(settings.archive_dir.join("archive.laptop.json")).unwrap();
+    assert_eq!(laptop_log.trim(), "[]");
+}
+
+#[test]
+fn it_keeps_the_sidecar_of_a_project_named_archive_out_of_the_index() {
+    setup_tracing();
+    let (temp_dir, mut settings) = setup_test_env();
+    // A project is never taken from a directory named like the archive directory.
+    settings.archive_dir = temp_dir.path().join("vault");
+    settings.sync_index = true;
+    let project = settings.projects_dirs[0].join("archive");
+    init_git_repo_with_date(&project, "old commit", "2023-01-01T12:00:00Z");
+
+    for machine in ["laptop", "meta"] {
+        let archiver = Archiver::new(Settings {
+            machine_id: Some(machine.to_string()),
+            ..settings.clone()
+        });
+        archiver.run_archive_process(false).unwrap();
+        let sidecar = settings.archive_dir.join("archive.meta.json");
+        let records = archiver.get_archive_records().unwrap();
+        assert!(records.iter().any(|r| r.name == "archive"));
+        assert!(fs::read_to_string(&sidecar)
+            .unwrap()
+            .contains("original_path"));
+
+        archiver.restore_project("archive").unwrap();
+        archiver.restore_project("old_project").unwrap();
+        assert!(archiver.get_archive_records().unwrap().is_empty());
+        assert!(!sidecar.exists());
+    }
+    assert!(settings.archive_dir.join("archive.meta_.json").exists());
+}
+
+#[test]
+fn it_only_writes_the_log_of_its_own_machine() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.sync_index = true;
+    let desktop_log = settings.archive_dir.join("archive.desktop.json");
+    // Written by the desktop, in whatever form its version of the archiver writes.
+    let desktop_records = format!(
+        r#"[{{"name":"desktop_project","original_path":"/desktop/desktop_project","archive_path":{:?},"archived_at":"2024-01-01T00:00:00Z","machine":"desktop"}}]"#,
+        settings.archive_dir.join("desktop_project")
+    );
+    fs::write(&desktop_log, &desktop_records).unwrap();
+
+    let laptop = Archiver::new(Settings {
+        machine_id: Some("laptop".to_string()),
+        ..settings.clone()
+    });
+    laptop.run_archive_process(false).unwrap();
+    laptop.restore_project("old_project").unwrap();
+
+    assert_eq!(fs::read_to_string(&desktop_log).unwrap(), desktop_records);
+    assert_eq!(laptop.get_archive_records().unwrap().len(), 1);
+}
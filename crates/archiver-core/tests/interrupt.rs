@@ -0,0 +1,57 @@
+use archiver_core::{interrupt, Archiver};
+use std::fs;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+// The stop request is global to the process: this file holds a single test so that no other
+// run sees it.
+#[test]
+fn it_stops_at_a_safe_point_and_resumes_with_the_projects_left() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let other = settings.projects_dirs[0].join("other_project");
+    fs::create_dir(&other).unwrap();
+    init_git_repo_with_date(&other, "old commit", "2023-01-01T12:00:00Z");
+    let archiver = Archiver::new(settings);
+    assert!(archiver.resume_run().unwrap().is_none());
+
+    interrupt::request();
+    let summary = archiver.run_with_summary(false, &["client-x".to_string()]);
+    interrupt::clear();
+    assert_eq!(summary.archived, 0);
+    assert!(summary.error.unwrap().contains("2 project(s) left"));
+    assert!(other.exists());
+    let resume_point = archiver.resume_point().unwrap().unwrap();
+    assert_eq!(resume_point.archive.len(), 2);
+    assert_eq!(resume_point.tags, vec!["client-x".to_string()]);
+
+    // Dry runs ignore the request.
+    interrupt::request();
+    let plan = archiver.run_with_summary(true, &[]);
+    interrupt::clear();
+    assert!(plan.error.is_none());
+    assert!(archiver.resume_point().unwrap().is_some());
+
+    let resumed = archiver.resume_run().unwrap().unwrap();
+    assert_eq!(resumed.archived, 2);
+    assert!(resumed.error.is_none());
+    assert!(!other.exists());
+    assert!(archiver.resume_point().unwrap().is_none());
+    let records = archiver.get_archive_records().unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(records
+        .iter()
+        .all(|r| r.tags == vec!["client-x".to_string()]));
+
+    // A complete run supersedes an interrupted one.
+    archiver.restore_project("other_project").unwrap();
+    interrupt::request();
+    archiver.run_with_summary(false, &[]);
+    interrupt::clear();
+    assert!(archiver.resume_point().unwrap().is_some());
+    let summary = archiver.run_with_summary(false, &[]);
+    assert!(summary.error.is_none());
+    assert_eq!(summary.archived, 1);
+    assert!(archiver.resume_point().unwrap().is_none());
+}
@@ -0,0 +1,58 @@
+use archiver_core::jetbrains::remove_recent_project;
+use std::fs;
+use tempfile::tempdir;
+
+const RECENT_PROJECTS_XML: &str = r#"<application>
+  <component name="RecentProjectsManager">
+    <option name="additionalInfo">
+      <map>
+        <entry key="/work/projects/old_project">
+          <value>
+            <RecentProjectMetaInfo frameTitle="old_project">
+              <option name="projectOpenTimestamp" value="1672574400000" />
+            </RecentProjectMetaInfo>
+          </value>
+        </entry>
+        <entry key="/work/projects/new_project">
+          <value>
+            <RecentProjectMetaInfo frameTitle="new_project" />
+          </value>
+        </entry>
+      </map>
+    </option>
+    <option name="recentPaths">
+      <list>
+        <option value="/work/projects/old_project" />
+        <option value="/work/projects/new_project" />
+      </list>
+    </option>
+  </component>
+</application>
+"#;
+
+#[test]
+fn it_removes_only_the_archived_project_from_recent_projects() {
+    let temp_dir = tempdir().unwrap();
+    let file = temp_dir.path().join("recentProjects.xml");
+    fs::write(&file, RECENT_PROJECTS_XML).unwrap();
+
+    let changed = remove_recent_project(&file, "/work/projects/old_project".as_ref()).unwrap();
+    assert!(changed);
+
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(!content.contains("old_project"));
+    assert!(content.contains(r#"<entry key="/work/projects/new_project">"#));
+    assert!(content.contains(r#"<option value="/work/projects/new_project" />"#));
+    assert!(content.trim_end().ends_with("</application>"));
+}
+
+#[test]
+fn it_leaves_the_file_untouched_when_the_project_is_not_listed() {
+    let temp_dir = tempdir().unwrap();
+    let file = temp_dir.path().join("recentProjects.xml");
+    fs::write(&file, RECENT_PROJECTS_XML).unwrap();
+
+    let changed = remove_recent_project(&file, "/work/projects/unknown".as_ref()).unwrap();
+    assert!(!changed);
+    assert_eq!(fs::read_to_string(&file).unwrap(), RECENT_PROJECTS_XML);
+}
@@ -0,0 +1,46 @@
+use archiver_core::{jobs, Archiver, Settings};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::thread;
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+#[test]
+fn it_keeps_results_in_order_and_caps_the_threads() {
+    let items: Vec<u64> = (0..100).collect();
+    let threads = Mutex::new(HashSet::new());
+    let squares = jobs::map(3, &items, |n| {
+        threads.lock().unwrap().insert(thread::current().id());
+        n * n
+    });
+    assert_eq!(squares, items.iter().map(|n| n * n).collect::<Vec<_>>());
+    assert!(threads.lock().unwrap().len() <= 3);
+
+    let single = jobs::map(1, &items, |_| thread::current().id());
+    assert!(single.iter().all(|id| *id == thread::current().id()));
+    assert!(jobs::map(8, &Vec::<u64>::new(), |n| *n).is_empty());
+}
+
+#[test]
+fn it_scans_the_same_projects_whatever_the_number_of_jobs() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let scan = |jobs| {
+        let mut projects = Archiver::new(Settings {
+            jobs,
+            ..settings.clone()
+        })
+        .scan_projects()
+        .unwrap();
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+        projects
+            .into_iter()
+            .map(|p| (p.name.to_string(), p.last_activity))
+            .collect::<Vec<_>>()
+    };
+    let sequential = scan(1);
+    assert_eq!(sequential.len(), 3);
+    assert_eq!(scan(4), sequential);
+    assert_eq!(scan(0), sequential);
+}
@@ -0,0 +1,171 @@
+use archiver_core::{journal, ArchivedRecord, Archiver, IndexFormat, ProjectName, Settings};
+use chrono::{TimeZone, Utc};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::thread;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+fn jsonl(settings: &Settings) -> Settings {
+    Settings {
+        index_format: IndexFormat::Jsonl,
+        ..settings.clone()
+    }
+}
+
+fn lines(path: &std::path::Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn it_appends_records_and_tombstones() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(jsonl(&settings));
+    let log_path = settings.archive_dir.join("archive.jsonl");
+
+    archiver.run_archive_process(false).unwrap();
+    assert_eq!(archiver.archive_log_path(), log_path);
+    assert!(!settings.archive_dir.join("archive.json").exists());
+    assert_eq!(lines(&log_path).len(), 1);
+    assert_eq!(archiver.get_archive_records().unwrap().len(), 1);
+
+    archiver.restore_project("old_project").unwrap();
+    let log = lines(&log_path);
+    assert_eq!(log.len(), 2);
+    assert!(log[0].contains("\"op\":\"add\""));
+    assert!(log[1].contains("\"op\":\"remove\""));
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+
+    archiver.run_archive_process(false).unwrap();
+    assert_eq!(archiver.get_archive_records().unwrap().len(), 1);
+    assert_eq!(archiver.compact_archive_log().unwrap(), 2);
+    assert_eq!(lines(&log_path).len(), 1);
+    assert_eq!(
+        archiver.get_archive_records().unwrap()[0].name,
+        "old_project"
+    );
+}
+
+#[test]
+fn it_skips_a_line_cut_short_by_a_crash() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(jsonl(&settings));
+    archiver.run_archive_process(false).unwrap();
+    let log_path = archiver.archive_log_path();
+    let mut log = OpenOptions::new().append(true).open(&log_path).unwrap();
+    log.write_all(br#"{"op":"add","record":{"name":"half"#)
+        .unwrap();
+
+    assert_eq!(archiver.get_archive_records().unwrap().len(), 1);
+
+    let other_path = settings.projects_dirs[0].join("other_project");
+    fs::create_dir(&other_path).unwrap();
+    init_git_repo_with_date(&other_path, "old commit", "2023-01-01T12:00:00Z");
+    archiver.run_archive_process(false).unwrap();
+    let records = archiver.get_archive_records().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].name, "other_project");
+}
+
+#[test]
+fn it_fails_on_a_corrupt_line() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(jsonl(&settings));
+    archiver.run_archive_process(false).unwrap();
+    let log_path = archiver.archive_log_path();
+    let content = fs::read_to_string(&log_path).unwrap();
+    fs::write(&log_path, format!("not json\n{content}")).unwrap();
+
+    assert!(archiver.get_archive_records().is_err());
+}
+
+#[test]
+fn it_converts_between_formats() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let json = Archiver::new(settings.clone());
+    json.run_archive_process(false).unwrap();
+    let archiver = Archiver::new(jsonl(&settings));
+
+    // The JSON log is not silently ignored.
+    assert!(archiver.get_archive_records().is_err());
+
+    assert_eq!(archiver.convert_archive_log().unwrap(), Some(1));
+    assert!(!settings.archive_dir.join("archive.json").exists());
+    assert_eq!(archiver.get_archive_records().unwrap().len(), 1);
+    assert_eq!(archiver.convert_archive_log().unwrap(), None);
+
+    assert_eq!(json.convert_archive_log().unwrap(), Some(1));
+    assert!(!settings.archive_dir.join("archive.jsonl").exists());
+    assert_eq!(json.get_archive_records().unwrap()[0].name, "old_project");
+}
+
+fn record(name: &str, archive_dir: &Path) -> ArchivedRecord {
+    ArchivedRecord {
+        name: ProjectName::new(name).unwrap(),
+        original_path: Path::new("/projects").join(name),
+        archive_path: archive_dir.join(name),
+        relative_path: None,
+        archived_at: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        machine: None,
+        tags: Vec::new(),
+        code_stats: None,
+        trimmed_commits: None,
+        manifest: None,
+        group: None,
+        destination: None,
+    }
+}
+
+#[test]
+fn it_keeps_records_appended_after_a_writer_read_the_log() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let log_path = temp_dir.path().join("archive.jsonl");
+    let first = record("first", temp_dir.path());
+    journal::append_records(&log_path, std::slice::from_ref(&first)).unwrap();
+
+    // One writer reads the log, another appends to it, then the first removes what it read.
+    let seen = journal::read(&log_path).unwrap();
+    let second = record("second", temp_dir.path());
+    journal::append_records(&log_path, std::slice::from_ref(&second)).unwrap();
+    journal::remove_records(&log_path, &seen).unwrap();
+
+    let names: Vec<_> = journal::read(&log_path)
+        .unwrap()
+        .into_iter()
+        .map(|record| record.name.to_string())
+        .collect();
+    assert_eq!(names, ["second"]);
+}
+
+#[test]
+fn it_keeps_records_appended_while_compacting() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let log_path = temp_dir.path().join("archive.jsonl");
+    let dir = temp_dir.path().to_path_buf();
+    journal::append_records(&log_path, &[record("seed", &dir)]).unwrap();
+
+    let appender = {
+        let log_path = log_path.clone();
+        thread::spawn(move || {
+            for index in 0..200 {
+                journal::append_records(&log_path, &[record(&format!("p{index}"), &dir)]).unwrap();
+            }
+        })
+    };
+    while !appender.is_finished() {
+        journal::compact(&log_path).unwrap();
+    }
+    appender.join().unwrap();
+
+    assert_eq!(journal::read(&log_path).unwrap().len(), 201);
+}
@@ -0,0 +1,49 @@
+use archiver_core::Archiver;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::process::Command;
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+#[test]
+fn it_keeps_repositories_with_open_pull_requests() {
+    setup_tracing();
+    let (temp_dir, mut settings) = setup_test_env();
+    settings.check_open_reviews = true;
+
+    // A fake `gh` reporting one open pull request authored by the user.
+    let bin_dir = temp_dir.path().join("bin");
+    fs::create_dir_all(&bin_dir).unwrap();
+    let gh = bin_dir.join("gh");
+    fs::write(
+        &gh,
+        "#!/bin/sh\ncase \"$*\" in *--author*) echo '[{\"number\":1}]' ;; *) echo '[]' ;; esac\n",
+    )
+    .unwrap();
+    fs::set_permissions(&gh, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = std::env::var("PATH").unwrap_or_default();
+    std::env::set_var("PATH", format!("{}:{}", bin_dir.display(), path));
+
+    let old_project_path = settings.projects_dirs[0].join("old_project");
+    let output = Command::new("git")
+        .args([
+            "remote",
+            "add",
+            "origin",
+            "git@github.com:me/old_project.git",
+        ])
+        .current_dir(&old_project_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+
+    assert!(
+        old_project_path.exists(),
+        "Open reviews keep the project active"
+    );
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+}
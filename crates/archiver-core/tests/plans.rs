@@ -0,0 +1,86 @@
+use archiver_core::{Archiver, ErrorCode, SavedPlan, Settings};
+use chrono::Utc;
+use std::fs;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+#[test]
+fn it_applies_exactly_the_saved_plan() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    let plan_path = temp_dir.path().join("plan.json");
+    archiver.plan().unwrap().write(&plan_path).unwrap();
+    assert!(settings.projects_dirs[0].join("old_project").exists());
+
+    // Projects that became inactive after planning are left for the next plan.
+    let later = settings.projects_dirs[0].join("later_project");
+    fs::create_dir(&later).unwrap();
+    init_git_repo_with_date(&later, "old commit", "2023-01-01T12:00:00Z");
+
+    let plan = SavedPlan::read(&plan_path).unwrap();
+    assert_eq!(plan.archive.len(), 1);
+    assert!(archiver.plan_changes(&plan).unwrap().is_empty());
+    let summary = archiver.apply_plan(&plan).unwrap();
+    assert_eq!(summary.archived, 1);
+    assert!(summary.error.is_none());
+    assert!(!settings.projects_dirs[0].join("old_project").exists());
+    assert!(later.exists());
+    let records = archiver.get_archive_records().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].name, "old_project");
+
+    // Applying it twice finds the project gone.
+    let stale = archiver.apply_plan(&plan).unwrap_err();
+    assert_eq!(stale.code(), ErrorCode::StalePlan);
+}
+
+#[test]
+fn it_refuses_plans_the_world_has_moved_past() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    let plan = archiver.plan().unwrap();
+
+    let old_project = settings.projects_dirs[0].join("old_project");
+    fs::write(old_project.join("file.txt"), "new work").unwrap();
+    init_git_repo_with_date(&old_project, "new work", &Utc::now().to_rfc3339());
+
+    let changes = archiver.plan_changes(&plan).unwrap();
+    assert_eq!(changes, vec!["'old_project' has new activity".to_string()]);
+    let error = archiver.apply_plan(&plan).unwrap_err();
+    assert_eq!(error.code(), ErrorCode::StalePlan);
+    assert!(old_project.exists());
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+}
+
+#[test]
+fn it_plans_and_applies_deletions() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+    let deleting = Archiver::new(Settings {
+        enable_auto_delete: true,
+        days_before_delete: 0,
+        delete_safety_multiplier: 0,
+        ..settings
+    });
+
+    let plan = deleting.plan().unwrap();
+    assert!(plan.archive.is_empty());
+    assert_eq!(plan.delete.len(), 1);
+
+    // With deletion turned off since, the archive is no longer due.
+    let changes = archiver.plan_changes(&plan).unwrap();
+    assert_eq!(
+        changes,
+        vec!["'old_project' is no longer due for deletion".to_string()]
+    );
+
+    let summary = deleting.apply_plan(&plan).unwrap();
+    assert_eq!(summary.deleted, 1);
+    assert!(!plan.delete[0].archive_path.exists());
+    assert!(deleting.get_archive_records().unwrap().is_empty());
+}
@@ -0,0 +1,74 @@
+use archiver_core::config::CleanupRule;
+use archiver_core::{Archiver, Outcome, Settings};
+use std::fs::{self, Permissions};
+use std::os::unix::fs::PermissionsExt;
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+/// Privileged users (e.g. root in a container) pass every permission check.
+fn is_privileged(locked_file: &std::path::Path) -> bool {
+    fs::read(locked_file).is_ok()
+}
+
+#[test]
+fn it_skips_projects_with_unreadable_files() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let project = settings.projects_dirs[0].join("old_project");
+    let locked = project.join("target/root-owned.o");
+    fs::create_dir_all(locked.parent().unwrap()).unwrap();
+    fs::write(&locked, "x").unwrap();
+    fs::set_permissions(&locked, Permissions::from_mode(0o000)).unwrap();
+    if is_privileged(&locked) {
+        return;
+    }
+
+    let archiver = Archiver::new(settings.clone());
+    let summary = archiver.run_with_summary(false, &[]);
+    assert!(summary.error.is_none());
+    let skipped = summary.with_outcome(Outcome::Skipped).next().unwrap();
+    assert_eq!(skipped.name, "old_project");
+    assert!(skipped.reason.as_deref().unwrap().contains("root-owned.o"));
+    assert!(project.is_dir());
+
+    fs::set_permissions(&locked, Permissions::from_mode(0o644)).unwrap();
+    let summary = archiver.run_with_summary(false, &[]);
+    assert_eq!(summary.archived, 1);
+}
+
+#[test]
+fn it_skips_projects_whose_cleanup_folders_cannot_be_emptied() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let project = settings.projects_dirs[0].join("old_project");
+    let modules = project.join("node_modules/dep");
+    fs::create_dir_all(&modules).unwrap();
+    fs::write(project.join("package.json"), "{}").unwrap();
+    fs::write(modules.join("index.js"), "x").unwrap();
+    // Unreadable files in a folder that gets deleted do not matter, only the folder does.
+    fs::set_permissions(modules.join("index.js"), Permissions::from_mode(0o000)).unwrap();
+    fs::set_permissions(&modules, Permissions::from_mode(0o555)).unwrap();
+    if is_privileged(&modules.join("index.js")) {
+        return;
+    }
+
+    let archiver = Archiver::new(Settings {
+        cleanup_rules: vec![CleanupRule {
+            detection_file: "package.json".to_string(),
+            folders_to_delete: vec!["node_modules".to_string()],
+            keep: vec![],
+        }],
+        ..settings
+    });
+    let summary = archiver.run_with_summary(false, &[]);
+    let skipped = summary.with_outcome(Outcome::Skipped).next().unwrap();
+    let reason = skipped.reason.as_deref().unwrap();
+    assert!(reason.contains("node_modules/dep"));
+    assert!(!reason.contains("index.js"));
+    assert!(modules.join("index.js").exists());
+
+    fs::set_permissions(&modules, Permissions::from_mode(0o755)).unwrap();
+    let summary = archiver.run_with_summary(false, &[]);
+    assert_eq!(summary.archived, 1);
+}
@@ -0,0 +1,85 @@
+use archiver_core::{ActivityProbe, Archiver, Settings};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::time::Instant;
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+fn probe(detection_file: &str, command: &str) -> ActivityProbe {
+    ActivityProbe {
+        detection_file: detection_file.to_string(),
+        command: command.to_string(),
+        timeout_secs: 30,
+    }
+}
+
+fn thesis_activity(settings: &Settings, probes: Vec<ActivityProbe>) -> DateTime<Utc> {
+    let archiver = Archiver::new(Settings {
+        activity_probes: probes,
+        ..settings.clone()
+    });
+    archiver
+        .scan_projects()
+        .unwrap()
+        .into_iter()
+        .find(|project| project.name == "thesis")
+        .unwrap()
+        .last_activity
+}
+
+#[test]
+fn it_uses_the_activity_printed_by_a_matching_probe() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let thesis = settings.projects_dirs[0].join("thesis");
+    fs::create_dir(&thesis).unwrap();
+    fs::write(thesis.join("chapter1.tex"), "\\section{Intro}").unwrap();
+
+    let activity = thesis_activity(
+        &settings,
+        vec![
+            probe("*.ipynb", "echo 2021-01-01"),
+            probe("*.tex", "echo building; echo 2020-03-04T05:06:07+01:00"),
+        ],
+    );
+    assert_eq!(
+        activity,
+        "2020-03-04T04:06:07Z".parse::<DateTime<Utc>>().unwrap()
+    );
+
+    let activity = thesis_activity(&settings, vec![probe("chapter1.tex", "echo 1577836800")]);
+    assert_eq!(
+        activity,
+        "2020-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+    );
+
+    let archiver = Archiver::new(Settings {
+        activity_probes: vec![probe("*.tex", "echo 2020-01-01")],
+        ..settings.clone()
+    });
+    archiver.run_archive_process(false).unwrap();
+    assert!(settings.archive_dir.join("thesis/chapter1.tex").exists());
+}
+
+#[test]
+fn it_falls_back_when_a_probe_fails() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let thesis = settings.projects_dirs[0].join("thesis");
+    fs::create_dir(&thesis).unwrap();
+    fs::write(thesis.join("main.tex"), "").unwrap();
+    let recent = Utc::now() - chrono::Duration::minutes(5);
+
+    for command in ["exit 1", "echo not a date", "true"] {
+        assert!(thesis_activity(&settings, vec![probe("main.tex", command)]) > recent);
+    }
+
+    let started = Instant::now();
+    let slow = ActivityProbe {
+        timeout_secs: 1,
+        ..probe("main.tex", "sleep 10; echo 2020-01-01")
+    };
+    assert!(thesis_activity(&settings, vec![slow]) > recent);
+    assert!(started.elapsed().as_secs() < 5);
+}
@@ -0,0 +1,132 @@
+use archiver_core::{names, Archiver, Error, ProjectName, RestoreFilter, Settings};
+use std::fs;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+#[test]
+fn it_rejects_names_that_could_escape_a_directory() {
+    for name in ["", ".", "..", "a/b", "../a", "/etc", "a\\b"] {
+        assert!(ProjectName::new(name).is_err(), "'{}' was accepted", name);
+    }
+    assert!(ProjectName::new("my-project.v2").is_ok());
+}
+
+#[test]
+fn it_refuses_crafted_archive_log_entries() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    let log_path = settings.archive_dir.join("archive.json");
+
+    fs::write(
+        &log_path,
+        r#"[{"name":"..","original_path":"/tmp/x","archive_path":"/tmp/y","archived_at":"2024-01-01T00:00:00Z"}]"#,
+    )
+    .unwrap();
+    assert!(archiver.get_archive_records().is_err());
+
+    // A valid name whose archive path points outside the archive directory.
+    let victim = temp_dir.path().join("victim");
+    fs::create_dir(&victim).unwrap();
+    fs::write(
+        &log_path,
+        format!(
+            r#"[{{"name":"victim","original_path":{:?},"archive_path":{:?},"archived_at":"2024-01-01T00:00:00Z"}}]"#,
+            settings.projects_dirs[0].join("victim"),
+            victim
+        ),
+    )
+    .unwrap();
+    assert!(archiver.delete_project("victim").is_err());
+    assert!(archiver.restore_project("victim").is_err());
+    assert!(victim.exists());
+
+    assert!(archiver.delete_project("../victim").is_err());
+}
+
+#[test]
+fn it_refuses_bulk_restores_with_a_record_outside_the_archive() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    let archived = settings.archive_dir.join("archived");
+    fs::create_dir(&archived).unwrap();
+    let victim = temp_dir.path().join("victim");
+    fs::create_dir(&victim).unwrap();
+    fs::write(
+        settings.archive_dir.join("archive.json"),
+        format!(
+            r#"[{{"name":"archived","original_path":{:?},"archive_path":{:?},"archived_at":"2024-01-01T00:00:00Z"}},{{"name":"victim","original_path":{:?},"archive_path":{:?},"archived_at":"2024-01-01T00:00:00Z"}}]"#,
+            settings.projects_dirs[0].join("archived"),
+            archived,
+            settings.projects_dirs[0].join("victim"),
+            victim
+        ),
+    )
+    .unwrap();
+
+    assert!(archiver.restore_all().is_err());
+    assert!(archiver
+        .restore_matching(&RestoreFilter::default())
+        .is_err());
+
+    // Nothing was moved, not even the record that is in the archive.
+    assert!(victim.exists());
+    assert!(archived.exists());
+    assert!(!settings.projects_dirs[0].join("archived").exists());
+    assert_eq!(archiver.get_archive_records().unwrap().len(), 2);
+}
+
+#[test]
+fn it_matches_names_loosely_only_when_enabled() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let project_path = settings.projects_dirs[0].join("Caf\u{e9}-App");
+    fs::create_dir(&project_path).unwrap();
+    init_git_repo_with_date(&project_path, "old commit", "2023-01-01T12:00:00Z");
+    Archiver::new(settings.clone())
+        .run_archive_process(false)
+        .unwrap();
+
+    // Lowercase, with the accent typed as a combining character.
+    let typed = "cafe\u{301}-app";
+    let strict = Archiver::new(settings.clone());
+    match strict.preview_restore(typed).unwrap_err() {
+        Error::ProjectNotFound { suggestions, .. } => assert_eq!(suggestions, ["Caf\u{e9}-App"]),
+        e => panic!("unexpected error: {e}"),
+    }
+
+    let loose = Archiver::new(Settings {
+        loose_name_matching: true,
+        ..settings
+    });
+    let record = loose.restore_project(typed).unwrap();
+    assert_eq!(record.name, "Caf\u{e9}-App");
+    assert!(project_path.exists());
+}
+
+#[test]
+fn it_suggests_close_names_when_nothing_matches() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings);
+    archiver.run_archive_process(false).unwrap();
+
+    let error = archiver.restore_project("old_projcet").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Project 'old_projcet' not found in archive log. Did you mean 'old_project'?"
+    );
+    match archiver.delete_project("unrelated").unwrap_err() {
+        Error::ProjectNotFound { suggestions, .. } => assert!(suggestions.is_empty()),
+        e => panic!("unexpected error: {e}"),
+    }
+
+    assert_eq!(
+        names::suggestions("archiver", ["archiver-core", "archiver-cli", "zed"]),
+        ["archiver-cli", "archiver-core"]
+    );
+    assert!(names::loosely_equal("My-Project", "my-project"));
+    assert!(names::loosely_equal("\u{ff2d}y-Project", "my-project"));
+}
@@ -0,0 +1,47 @@
+use archiver_core::prompt::{self, PromptStatus};
+use archiver_core::{Archiver, Settings};
+use chrono::{Duration, Utc};
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+#[test]
+fn it_counts_and_caches_pending_candidates() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    assert_eq!(archiver.pending_candidates().unwrap(), 1);
+
+    let excluding = Archiver::new(Settings {
+        exclude: vec!["old_project".into()],
+        ..settings
+    });
+    assert_eq!(excluding.pending_candidates().unwrap(), 0);
+
+    let path = temp_dir.path().join("state/prompt-status.json");
+    assert_eq!(PromptStatus::read(&path).unwrap(), None);
+    let status = PromptStatus::new(1);
+    status.write(&path).unwrap();
+    assert_eq!(PromptStatus::read(&path).unwrap(), Some(status.clone()));
+
+    let max_age = Duration::minutes(60);
+    assert!(status.is_fresh(max_age, Utc::now()));
+    assert!(!status.is_fresh(max_age, Utc::now() + Duration::minutes(61)));
+}
+
+#[test]
+fn it_lets_one_background_refresh_run_at_a_time() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let marker = temp_dir.path().join("state/prompt-status.refreshing");
+    let now = Utc::now();
+
+    assert!(prompt::claim_refresh(&marker, now).unwrap());
+    assert!(!prompt::claim_refresh(&marker, now + Duration::minutes(1)).unwrap());
+
+    // A refresh that died without releasing the marker is taken over after a while.
+    assert!(prompt::claim_refresh(&marker, now + Duration::minutes(20)).unwrap());
+
+    prompt::release_refresh(&marker);
+    assert!(!marker.exists());
+    assert!(prompt::claim_refresh(&marker, now).unwrap());
+}
@@ -0,0 +1,210 @@
+use archiver_core::{
+    sidecar, ArchivedRecord, Archiver, ErrorCode, Exclusion, Outcome, ProjectType, RemoteHost,
+    Settings,
+};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+/// Writes a stand-in for `ssh` that runs the remote command locally. It fails unless the host
+/// comes after `--`, as it must so a host cannot pass as an option.
+fn fake_ssh(dir: &Path) -> String {
+    let path = dir.join("fake-ssh");
+    fs::write(
+        &path,
+        "#!/bin/sh\n[ \"$1\" = -- ] || exit 2\nshift 2\nexec sh -c \"$1\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+fn remote_settings(temp_dir: &Path, settings: Settings) -> Settings {
+    let remote = RemoteHost {
+        name: "box".to_string(),
+        host: "user@box".to_string(),
+        projects_dirs: settings.projects_dirs.clone(),
+        archive_dir: temp_dir.join("remote-archive"),
+    };
+    Settings {
+        remotes: vec![remote],
+        ssh_program: fake_ssh(temp_dir),
+        ..settings
+    }
+}
+
+#[test]
+fn it_scans_a_remote_machine() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(remote_settings(temp_dir.path(), settings));
+
+    let remote = archiver.remote("box").unwrap();
+    let mut projects: Vec<_> = archiver
+        .scan_remote(remote)
+        .unwrap()
+        .into_iter()
+        .map(|remote_project| remote_project.project)
+        .collect();
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+    let names: Vec<_> = projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["empty_project", "new_project", "old_project"]);
+    assert_eq!(
+        projects[2].last_activity.to_rfc3339(),
+        "2023-01-01T12:00:00+00:00"
+    );
+}
+
+#[test]
+fn it_archives_inactive_remote_projects_into_the_remote_archive() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let projects_dir = settings.projects_dirs[0].clone();
+    let local_archive = settings.archive_dir.clone();
+    let archiver = Archiver::new(remote_settings(temp_dir.path(), settings));
+    let remote = archiver.remote("box").unwrap();
+
+    let plan = archiver.run_remote(remote, true, &[]);
+    assert!(plan.error.is_none());
+    let planned: Vec<_> = plan.with_outcome(Outcome::WouldArchive).collect();
+    assert_eq!(planned.len(), 1);
+    assert_eq!(planned[0].name, "old_project");
+    assert!(projects_dir.join("old_project").exists());
+
+    let summary = archiver.run_remote(remote, false, &["spring".to_string()]);
+    assert!(summary.error.is_none());
+    assert_eq!(summary.with_outcome(Outcome::Archived).count(), 1);
+    let archived = remote.archive_dir.join("old_project");
+    assert!(archived.join("file.txt").exists());
+    assert!(!projects_dir.join("old_project").exists());
+    assert!(projects_dir.join("new_project").exists());
+
+    let content = fs::read_to_string(sidecar::path_for(&archived)).unwrap();
+    let record: ArchivedRecord = serde_json::from_str(&content).unwrap();
+    assert_eq!(record.machine.as_deref(), Some("box"));
+    assert_eq!(record.tags, ["spring"]);
+    assert_eq!(record.original_path, projects_dir.join("old_project"));
+
+    // Remote projects are not recorded in the local archive.
+    assert!(fs::read_dir(&local_archive).unwrap().next().is_none());
+}
+
+#[test]
+fn it_refuses_to_overwrite_a_remote_archive_entry() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let projects_dir = settings.projects_dirs[0].clone();
+    let archiver = Archiver::new(remote_settings(temp_dir.path(), settings));
+    let remote = archiver.remote("box").unwrap();
+    fs::create_dir_all(remote.archive_dir.join("old_project")).unwrap();
+
+    let summary = archiver.run_remote(remote, false, &[]);
+    assert!(summary
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("destination already exists"));
+    assert_eq!(summary.with_outcome(Outcome::Failed).count(), 1);
+    assert!(projects_dir.join("old_project").join("file.txt").exists());
+}
+
+#[test]
+fn it_reports_unknown_and_unreachable_remotes() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let mut settings = remote_settings(temp_dir.path(), settings);
+
+    let unknown = Archiver::new(settings.clone()).remote("nope").unwrap_err();
+    assert_eq!(unknown.code(), ErrorCode::Remote);
+
+    settings.ssh_program = temp_dir.path().join("missing-ssh").display().to_string();
+    let archiver = Archiver::new(settings);
+    let unreachable = archiver
+        .scan_remote(archiver.remote("box").unwrap())
+        .unwrap_err();
+    assert_eq!(unreachable.code(), ErrorCode::Remote);
+}
+
+#[test]
+fn it_applies_the_inactivity_threshold_of_the_project_type_on_remotes() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let projects_dir = settings.projects_dirs[0].clone();
+    let old_crate = projects_dir.join("old_crate");
+    init_git_repo_with_date(&old_crate, "old crate", "2023-01-01T12:00:00Z");
+    fs::write(
+        old_crate.join("Cargo.toml"),
+        "[package]\nname = \"old_crate\"\n",
+    )
+    .unwrap();
+    let mut settings = remote_settings(temp_dir.path(), settings);
+    settings
+        .inactivity_days_by_type
+        .insert(ProjectType::Rust, 100 * 365);
+    let archiver = Archiver::new(settings);
+    let remote = archiver.remote("box").unwrap();
+
+    let scanned = archiver.scan_remote(remote).unwrap();
+    let kind_of = |name: &str| {
+        scanned
+            .iter()
+            .find(|remote_project| remote_project.project.name.as_str() == name)
+            .unwrap()
+            .kind
+    };
+    assert_eq!(kind_of("old_crate"), Some(ProjectType::Rust));
+    assert_eq!(kind_of("old_project"), None);
+
+    let plan = archiver.run_remote(remote, true, &[]);
+    let planned: Vec<_> = plan
+        .with_outcome(Outcome::WouldArchive)
+        .map(|project| project.name.as_str())
+        .collect();
+    assert_eq!(planned, ["old_project"]);
+}
+
+#[test]
+fn it_skips_the_remote_archive_and_excluded_projects() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let projects_dir = settings.projects_dirs[0].clone();
+    let mut settings = remote_settings(temp_dir.path(), settings);
+    settings.exclude = vec![Exclusion::from("empty_*")];
+    // The archive inside the projects directory, holding an old project.
+    let remote_archive = projects_dir.join("vault");
+    init_git_repo_with_date(
+        &remote_archive.join("archived"),
+        "old commit",
+        "2022-01-01T12:00:00Z",
+    );
+    settings.remotes[0].archive_dir = remote_archive.clone();
+    // Another directory with the name of the archive is skipped too.
+    settings.remotes[0]
+        .projects_dirs
+        .push(temp_dir.path().join("more"));
+    init_git_repo_with_date(
+        &temp_dir.path().join("more/vault"),
+        "old commit",
+        "2022-01-01T12:00:00Z",
+    );
+    let archiver = Archiver::new(settings);
+    let remote = archiver.remote("box").unwrap();
+
+    let mut names: Vec<_> = archiver
+        .scan_remote(remote)
+        .unwrap()
+        .into_iter()
+        .map(|remote_project| remote_project.project.name.to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["new_project", "old_project"]);
+
+    let summary = archiver.run_remote(remote, false, &[]);
+    assert!(summary.error.is_none());
+    assert_eq!(summary.with_outcome(Outcome::Archived).count(), 1);
+    assert!(remote_archive.join("old_project").exists());
+    assert!(remote_archive.join("archived").exists());
+}
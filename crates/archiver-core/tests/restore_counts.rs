@@ -0,0 +1,63 @@
+use archiver_core::{Archiver, RestoreFilter, Settings};
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+#[test]
+fn it_counts_restores_per_project() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    assert!(archiver.restore_counts().unwrap().is_empty());
+
+    archiver.run_archive_process(false).unwrap();
+    archiver.restore_project("old_project").unwrap();
+    archiver.run_archive_process(false).unwrap();
+    archiver
+        .restore_matching(&RestoreFilter::default())
+        .unwrap();
+    archiver.run_archive_process(false).unwrap();
+    archiver.restore_all().unwrap();
+
+    let counts = archiver.restore_counts().unwrap();
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts[0].name, "old_project");
+    assert_eq!(
+        counts[0].path,
+        settings.projects_dirs[0].join("old_project")
+    );
+    assert_eq!(counts[0].count, 3);
+}
+
+#[test]
+fn it_suggests_excluding_projects_restored_repeatedly() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(Settings {
+        suggest_exclusion_after: 2,
+        ..settings.clone()
+    });
+
+    archiver.run_archive_process(false).unwrap();
+    archiver.restore_project("old_project").unwrap();
+    let plan = archiver.run_with_summary(true, &[]);
+    assert!(archiver.exclusion_suggestions(&plan).is_empty());
+
+    archiver.run_archive_process(false).unwrap();
+    archiver.restore_project("old_project").unwrap();
+    let plan = archiver.run_with_summary(true, &[]);
+    let suggestions = archiver.exclusion_suggestions(&plan);
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].name, "old_project");
+
+    let run = archiver.run_with_summary(false, &[]);
+    assert_eq!(archiver.exclusion_suggestions(&run).len(), 1);
+    let restored = archiver.run_with_summary(true, &[]);
+    assert!(archiver.exclusion_suggestions(&restored).is_empty());
+
+    let disabled = Archiver::new(Settings {
+        suggest_exclusion_after: 0,
+        ..settings
+    });
+    assert!(disabled.exclusion_suggestions(&run).is_empty());
+}
@@ -0,0 +1,96 @@
+use archiver_core::{ArchiveRoute, Archiver, ProjectType, Settings};
+use std::fs;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+fn route(name: &str, archive_dir: std::path::PathBuf) -> ArchiveRoute {
+    ArchiveRoute {
+        name: name.to_string(),
+        archive_dir,
+        project_types: vec![],
+        roots: vec![],
+        min_size_mb: None,
+        max_size_mb: None,
+    }
+}
+
+#[test]
+fn it_routes_projects_to_the_first_matching_destination() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let projects_dir = settings.projects_dirs[0].clone();
+    let rust_project = projects_dir.join("rust_project");
+    fs::create_dir(&rust_project).unwrap();
+    fs::write(rust_project.join("Cargo.toml"), "[package]").unwrap();
+    init_git_repo_with_date(&rust_project, "old commit", "2023-01-01T12:00:00Z");
+    let big_project = projects_dir.join("big_project");
+    fs::create_dir(&big_project).unwrap();
+    fs::write(big_project.join("data.bin"), vec![0u8; 2 * 1024 * 1024]).unwrap();
+    init_git_repo_with_date(&big_project, "old commit", "2023-01-01T12:00:00Z");
+
+    let external = temp_dir.path().join("external");
+    let code = temp_dir.path().join("code");
+    let archiver = Archiver::new(Settings {
+        archive_routes: vec![
+            ArchiveRoute {
+                min_size_mb: Some(1),
+                ..route("external", external.clone())
+            },
+            ArchiveRoute {
+                project_types: vec![ProjectType::Rust],
+                roots: vec![projects_dir.clone()],
+                ..route("code", code.clone())
+            },
+        ],
+        ..settings.clone()
+    });
+
+    archiver.run_archive_process(false).unwrap();
+
+    let records = archiver.get_archive_records().unwrap();
+    assert_eq!(records.len(), 3);
+    let destination = |name: &str| {
+        let record = records.iter().find(|r| r.name == name).unwrap();
+        (record.destination.clone(), record.archive_path.clone())
+    };
+    assert_eq!(
+        destination("big_project"),
+        (Some("external".to_string()), external.join("big_project"))
+    );
+    assert_eq!(
+        destination("rust_project"),
+        (Some("code".to_string()), code.join("rust_project"))
+    );
+    assert_eq!(
+        destination("old_project"),
+        (None, settings.archive_dir.join("old_project"))
+    );
+
+    archiver.restore_project("big_project").unwrap();
+    assert!(big_project.join("data.bin").exists());
+    assert!(!external.join("big_project").exists());
+
+    assert_eq!(archiver.delete_all().unwrap(), 2);
+    assert!(!code.join("rust_project").exists());
+    assert!(code.exists());
+    assert!(archiver.get_archive_records().unwrap().is_empty());
+}
+
+#[test]
+fn it_only_acts_on_records_inside_an_archive_directory() {
+    setup_tracing();
+    let (temp_dir, settings) = setup_test_env();
+    let external = temp_dir.path().join("external");
+    let routed = Archiver::new(Settings {
+        archive_routes: vec![route("external", external.clone())],
+        ..settings.clone()
+    });
+    routed.run_archive_process(false).unwrap();
+    assert!(external.join("old_project").exists());
+
+    // Without the route, its directory is no longer part of the archive.
+    let unrouted = Archiver::new(settings);
+    assert!(unrouted.restore_project("old_project").is_err());
+    routed.restore_project("old_project").unwrap();
+}
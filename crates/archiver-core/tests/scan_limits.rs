@@ -0,0 +1,73 @@
+use archiver_core::{Archiver, Outcome, ScanLimit, Settings};
+use std::fs::{self, File};
+use std::time::{Duration, SystemTime};
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+/// Creates a project without Git of `files` files last modified a year ago.
+fn create_dump(dir: &std::path::Path, files: usize) {
+    fs::create_dir(dir).unwrap();
+    let year_ago = SystemTime::now() - Duration::from_secs(365 * 24 * 3600);
+    for index in 0..files {
+        let path = dir.join(format!("part-{index}.csv"));
+        fs::write(&path, "x").unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(year_ago)
+            .unwrap();
+    }
+}
+
+#[test]
+fn it_skips_inactive_projects_whose_scan_stopped_early() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let dump = settings.projects_dirs[0].join("dump");
+    create_dump(&dump, 5);
+
+    let limited = Archiver::new(Settings {
+        mtime_scan_max_files: Some(3),
+        ..settings.clone()
+    });
+    let scanned = limited.scan_projects().unwrap();
+    let project = scanned.iter().find(|p| p.name == "dump").unwrap();
+    assert_eq!(project.scan_limit, Some(ScanLimit::MaxFiles(3)));
+
+    let summary = limited.run_with_summary(false, &[]);
+    assert!(summary.error.is_none());
+    let skipped = summary.with_outcome(Outcome::Skipped).next().unwrap();
+    assert_eq!(skipped.name, "dump");
+    assert_eq!(
+        skipped.reason.as_deref(),
+        Some("activity scan stopped after 3 files")
+    );
+    assert!(dump.exists());
+    assert!(settings.archive_dir.join("old_project").exists());
+
+    let unlimited = Archiver::new(Settings {
+        mtime_scan_max_files: Some(5),
+        ..settings.clone()
+    });
+    unlimited.run_archive_process(false).unwrap();
+    assert!(settings.archive_dir.join("dump").exists());
+}
+
+#[test]
+fn it_stops_scanning_a_project_after_its_time_budget() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    create_dump(&settings.projects_dirs[0].join("dump"), 2);
+
+    let archiver = Archiver::new(Settings {
+        mtime_scan_timeout_secs: Some(0),
+        ..settings
+    });
+    let scanned = archiver.scan_projects().unwrap();
+    let project = scanned.iter().find(|p| p.name == "dump").unwrap();
+    assert_eq!(project.scan_limit, Some(ScanLimit::Timeout(0)));
+    let old = scanned.iter().find(|p| p.name == "old_project").unwrap();
+    assert_eq!(old.scan_limit, None);
+}
@@ -0,0 +1,47 @@
+use archiver_core::{sidecar, Archiver};
+use std::fs;
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+#[test]
+fn it_keeps_a_metadata_sidecar_next_to_each_archived_project() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    archiver
+        .run_tagged_archive_process(false, &["client-x".to_string()])
+        .unwrap();
+
+    let sidecar_path = settings.archive_dir.join("old_project.meta.json");
+    assert!(sidecar_path.is_file());
+    let record = &archiver.get_archive_records().unwrap()[0];
+    assert_eq!(sidecar::path_for(&record.archive_path), sidecar_path);
+
+    // The sidecars alone describe the archive when the log is lost.
+    fs::remove_file(settings.archive_dir.join("archive.json")).unwrap();
+    let rebuilt = sidecar::read_all(&settings.archive_dir).unwrap();
+    assert_eq!(rebuilt.len(), 1);
+    assert_eq!(rebuilt[0].name, "old_project");
+    assert_eq!(rebuilt[0].original_path, record.original_path);
+    assert_eq!(rebuilt[0].archived_at, record.archived_at);
+    assert_eq!(rebuilt[0].tags, vec!["client-x".to_string()]);
+}
+
+#[test]
+fn it_removes_the_sidecar_when_a_project_leaves_the_archive() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+    let sidecar_path = settings.archive_dir.join("old_project.meta.json");
+    assert!(sidecar_path.is_file());
+
+    archiver.restore_project("old_project").unwrap();
+    assert!(!sidecar_path.exists());
+
+    archiver.run_archive_process(false).unwrap();
+    archiver.delete_project("old_project").unwrap();
+    assert!(!sidecar_path.exists());
+    assert!(sidecar::read_all(&settings.archive_dir).unwrap().is_empty());
+}
@@ -0,0 +1,48 @@
+use archiver_core::simulate::{self, SimulationSpec};
+use archiver_core::{Archiver, Settings};
+
+mod helpers;
+use helpers::setup_tracing;
+
+#[test]
+fn it_generates_a_reproducible_tree_the_pipeline_can_archive() {
+    setup_tracing();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let spec = SimulationSpec {
+        projects: 20,
+        max_size: 1024 * 1024,
+        seed: 7,
+    };
+    let settings = Settings {
+        projects_dirs: vec![temp_dir.path().join("projects")],
+        archive_dir: temp_dir.path().join("archive"),
+        ..Default::default()
+    };
+
+    let paths = simulate::generate(&settings.projects_dirs[0], &spec).unwrap();
+    assert_eq!(paths.len(), 20);
+
+    let archiver = Archiver::new(settings.clone());
+    let dry_run = archiver.run_with_summary(true, &[]);
+    assert!(dry_run.error.is_none());
+    assert_eq!(dry_run.scanned, 20);
+    assert!(dry_run.archived > 0 && dry_run.archived < 20);
+    assert!(dry_run.bytes_moved <= spec.max_size + 64 * 1024);
+
+    // The same seed produces the same candidates.
+    let other_dir = tempfile::tempdir().unwrap();
+    let other = Settings {
+        projects_dirs: vec![other_dir.path().join("projects")],
+        archive_dir: other_dir.path().join("archive"),
+        ..Default::default()
+    };
+    simulate::generate(&other.projects_dirs[0], &spec).unwrap();
+    let again = Archiver::new(other).run_with_summary(true, &[]);
+    let names = |s: &archiver_core::RunSummary| -> Vec<String> {
+        s.projects.iter().map(|p| p.name.clone()).collect()
+    };
+    assert_eq!(names(&dry_run), names(&again));
+
+    let summary = archiver.run_with_summary(false, &[]);
+    assert_eq!(summary.archived, dry_run.archived);
+}
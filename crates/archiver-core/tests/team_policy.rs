@@ -0,0 +1,240 @@
+use archiver_core::team::{self, TeamPolicy};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const POLICY: &str = r#"
+exclude = ["shared-tools"]
+days_before_delete = 90
+archive_dir = "/elsewhere"
+"#;
+
+fn policy(url: String) -> TeamPolicy {
+    TeamPolicy {
+        url,
+        refresh_minutes: 0,
+        allowed_signers: None,
+    }
+}
+
+/// Serves `POLICY` with an ETag, answering 304 to requests that send it back. Returns the
+/// URL and the `If-None-Match` header of every request.
+fn serve_policy() -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/policy.toml", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut if_none_match = None;
+            for line in BufReader::new(&stream).lines() {
+                let line = line.unwrap();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some(value) = line.strip_prefix("If-None-Match: ") {
+                    if_none_match = Some(value.to_string());
+                }
+            }
+            let response = if if_none_match.as_deref() == Some("\"v1\"") {
+                "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_string()
+            } else {
+                format!(
+                    "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    POLICY.len(),
+                    POLICY
+                )
+            };
+            seen.lock().unwrap().push(if_none_match);
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    (url, requests)
+}
+
+#[test]
+fn it_caches_the_policy_and_revalidates_it_with_its_etag() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_path = temp_dir.path().join("team-policy.json");
+    let (url, requests) = serve_policy();
+    let timeout = Duration::from_secs(5);
+
+    let settings = team::load(&policy(url.clone()), &cache_path, timeout).unwrap();
+    assert!(settings.contains("shared-tools"));
+    assert!(settings.contains("days_before_delete = 90"));
+    assert!(!settings.contains("archive_dir"));
+
+    let again = team::load(&policy(url.clone()), &cache_path, timeout).unwrap();
+    assert_eq!(again, settings);
+    assert_eq!(
+        *requests.lock().unwrap(),
+        [None, Some("\"v1\"".to_string())]
+    );
+
+    let fresh = TeamPolicy {
+        refresh_minutes: 60,
+        ..policy(url)
+    };
+    team::load(&fresh, &cache_path, timeout).unwrap();
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn it_uses_the_cached_policy_while_the_url_is_unreachable() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_path = temp_dir.path().join("team-policy.json");
+    let file = temp_dir.path().join("policy.toml");
+    fs::write(&file, POLICY).unwrap();
+    let url = format!("file://{}", file.display());
+    let timeout = Duration::from_secs(5);
+
+    let settings = team::load(&policy(url.clone()), &cache_path, timeout).unwrap();
+    fs::remove_file(&file).unwrap();
+    assert_eq!(
+        team::load(&policy(url), &cache_path, timeout).unwrap(),
+        settings
+    );
+
+    let missing = format!("file://{}", temp_dir.path().join("missing.toml").display());
+    assert!(team::load(&policy(missing), &cache_path, timeout).is_err());
+    let plain_http = policy("http://example.com/policy.toml".to_string());
+    assert!(team::load(&plain_http, &cache_path, timeout).is_err());
+}
+
+fn ssh_keygen(dir: &Path, args: &[&str]) {
+    let output = Command::new("ssh-keygen")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "ssh-keygen {args:?}: {output:?}");
+}
+
+#[test]
+fn it_refuses_policies_without_a_valid_signature() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    let cache_path = dir.join("team-policy.json");
+    ssh_keygen(dir, &["-q", "-t", "ed25519", "-N", "", "-f", "key"]);
+    let public_key = fs::read_to_string(dir.join("key.pub")).unwrap();
+    fs::write(
+        dir.join("allowed_signers"),
+        format!("ops@example.com namespaces=\"archiver\" {public_key}"),
+    )
+    .unwrap();
+    fs::write(dir.join("policy.toml"), POLICY).unwrap();
+    let signed = TeamPolicy {
+        allowed_signers: Some(dir.join("allowed_signers")),
+        ..policy(format!("file://{}", dir.join("policy.toml").display()))
+    };
+    let timeout = Duration::from_secs(5);
+
+    assert!(team::load(&signed, &cache_path, timeout).is_err());
+
+    ssh_keygen(
+        dir,
+        &[
+            "-q",
+            "-Y",
+            "sign",
+            "-f",
+            "key",
+            "-n",
+            "archiver",
+            "policy.toml",
+        ],
+    );
+    assert!(team::load(&signed, &cache_path, timeout)
+        .unwrap()
+        .contains("shared-tools"));
+
+    fs::remove_file(&cache_path).unwrap();
+    fs::write(dir.join("policy.toml"), "enable_auto_delete = true\n").unwrap();
+    assert!(team::load(&signed, &cache_path, timeout).is_err());
+}
+
+#[test]
+fn it_checks_the_cached_policy_against_the_current_signers() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    let cache_path = dir.join("team-policy.json");
+    for key in ["key", "other-key"] {
+        ssh_keygen(dir, &["-q", "-t", "ed25519", "-N", "", "-f", key]);
+    }
+    let allowed_signers = |key: &str| {
+        let public_key = fs::read_to_string(dir.join(format!("{key}.pub"))).unwrap();
+        fs::write(
+            dir.join("allowed_signers"),
+            format!("ops@example.com namespaces=\"archiver\" {public_key}"),
+        )
+        .unwrap();
+    };
+    allowed_signers("key");
+    fs::write(dir.join("policy.toml"), POLICY).unwrap();
+    ssh_keygen(
+        dir,
+        &[
+            "-q",
+            "-Y",
+            "sign",
+            "-f",
+            "key",
+            "-n",
+            "archiver",
+            "policy.toml",
+        ],
+    );
+    let signed = TeamPolicy {
+        refresh_minutes: 60,
+        allowed_signers: Some(dir.join("allowed_signers")),
+        ..policy(format!("file://{}", dir.join("policy.toml").display()))
+    };
+    let timeout = Duration::from_secs(5);
+    assert!(team::load(&signed, &cache_path, timeout).is_ok());
+
+    // The cached copy is still fresh, but was not signed by the signers now allowed.
+    allowed_signers("other-key");
+    assert!(team::load(&signed, &cache_path, timeout).is_err());
+}
+
+#[test]
+fn it_does_not_follow_redirects_away_from_https() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/policy.toml", listener.local_addr().unwrap());
+    let moved = format!("{url}.moved");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut redirected = false;
+            for line in BufReader::new(&stream).lines() {
+                let line = line.unwrap();
+                if line.is_empty() {
+                    break;
+                }
+                redirected |= line.starts_with("GET /policy.toml.moved ");
+            }
+            let response = if redirected {
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    POLICY.len(),
+                    POLICY
+                )
+            } else {
+                format!(
+                    "HTTP/1.1 302 Found\r\nLocation: {moved}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                )
+            };
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_path = temp_dir.path().join("team-policy.json");
+
+    assert!(team::load(&policy(url), &cache_path, Duration::from_secs(5)).is_err());
+}
@@ -0,0 +1,31 @@
+use archiver_core::testing::TestEnv;
+use archiver_core::Archiver;
+use chrono::{Duration, Utc};
+
+#[test]
+fn it_builds_environments_with_back_dated_projects() {
+    let env = TestEnv::new().configure(|settings| settings.inactivity_days = 60);
+    let report = env
+        .project("report")
+        .file_modified_at("data/2023.csv", "a,b", Utc::now() - Duration::days(90))
+        .file_modified_at("README.md", "#", Utc::now() - Duration::days(120));
+    env.project("thesis")
+        .git_commit("draft", "2023-01-01T12:00:00Z")
+        .git_commit("final", "2023-02-01T12:00:00Z");
+    env.project("active")
+        .git_commit("today", &Utc::now().to_rfc3339());
+    assert!(report.path().join("data/2023.csv").is_file());
+
+    let archiver = Archiver::new(env.settings().clone());
+    let scanned = archiver.scan_projects().unwrap();
+    let thesis = scanned.iter().find(|p| p.name == "thesis").unwrap();
+    assert_eq!(
+        thesis.last_activity.to_rfc3339(),
+        "2023-02-01T12:00:00+00:00"
+    );
+
+    archiver.run_archive_process(false).unwrap();
+    assert!(env.archive_dir().join("report").exists());
+    assert!(env.archive_dir().join("thesis").exists());
+    assert!(env.projects_dir().join("active").exists());
+}
@@ -0,0 +1,43 @@
+use archiver_core::{tree, Archiver, Settings};
+use chrono::{Datelike, Utc};
+use std::fs;
+
+mod helpers;
+use helpers::{init_git_repo_with_date, setup_test_env, setup_tracing};
+
+#[test]
+fn it_builds_a_tree_of_groups_and_projects_with_their_sizes() {
+    setup_tracing();
+    let (_temp_dir, settings) = setup_test_env();
+    let client = Archiver::new(Settings {
+        archive_group: Some("client-x".to_string()),
+        ..settings.clone()
+    });
+    client.run_archive_process(false).unwrap();
+    let other_path = settings.projects_dirs[0].join("other_project");
+    fs::create_dir(&other_path).unwrap();
+    fs::write(other_path.join("big.bin"), vec![0; 64 * 1024]).unwrap();
+    init_git_repo_with_date(&other_path, "old commit", "2023-01-01T12:00:00Z");
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+    fs::remove_dir_all(settings.archive_dir.join("client-x/old_project")).unwrap();
+
+    let records = archiver.get_archive_records().unwrap();
+    let roots = tree::build(&settings, records);
+
+    assert_eq!(roots.len(), 1);
+    let root = &roots[0];
+    assert_eq!(root.name, settings.archive_dir.display().to_string());
+    let branches: Vec<_> = root.children.iter().map(|b| b.name.as_str()).collect();
+    let year = Utc::now().year().to_string();
+    assert_eq!(branches, [year.as_str(), "client-x"]);
+    let other = &root.children[0].children[0];
+    assert_eq!(other.name, "other_project");
+    assert!(other.bytes >= 64 * 1024);
+    assert!(!other.missing);
+    assert!(other.archived_at.is_some());
+    assert_eq!(root.bytes, root.children[0].bytes + root.children[1].bytes);
+    let old = &root.children[1].children[0];
+    assert!(old.missing);
+    assert_eq!(old.bytes, 0);
+}
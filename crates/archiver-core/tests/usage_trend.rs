@@ -0,0 +1,145 @@
+use archiver_core::trend::{self, Period, UsageSample};
+use archiver_core::{digest, Archiver, Settings};
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use std::fs;
+
+mod helpers;
+use helpers::{setup_test_env, setup_tracing};
+
+fn sample(day: u32, month: u32, active_bytes: u64, freed_bytes: u64) -> UsageSample {
+    UsageSample {
+        at: Utc.with_ymd_and_hms(2026, month, day, 12, 0, 0).unwrap(),
+        active_bytes,
+        archive_bytes: 1_000 - active_bytes,
+        freed_bytes,
+        archived_projects: 0,
+        deleted_projects: 0,
+    }
+}
+
+#[test]
+fn it_groups_the_usage_history_by_week_and_month() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("state/usage-history.jsonl");
+    // Monday 2 and Sunday 8 of March share a week; Monday 9 starts the next one.
+    for s in [
+        sample(9, 3, 500, 5),
+        sample(2, 3, 800, 10),
+        sample(8, 3, 700, 20),
+    ] {
+        trend::append(&path, &s).unwrap();
+    }
+    fs::write(
+        &path,
+        fs::read_to_string(&path).unwrap()
+            + "not json\n"
+            + &serde_json::to_string(&sample(1, 4, 400, 1)).unwrap()
+            + "\n",
+    )
+    .unwrap();
+
+    let history = trend::read(&path).unwrap();
+    assert_eq!(history.len(), 4);
+
+    let weeks = trend::trend(&history, Period::Week);
+    let starts: Vec<_> = weeks.iter().map(|w| w.period_start).collect();
+    assert_eq!(
+        starts,
+        [
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 3, 9).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 3, 30).unwrap(),
+        ]
+    );
+    assert_eq!(weeks[0].active_bytes, 700);
+    assert_eq!(weeks[0].freed_bytes, 30);
+    assert_eq!(weeks[0].runs, 2);
+
+    let months = trend::trend(&history, Period::Month);
+    assert_eq!(months.len(), 2);
+    assert_eq!(months[0].active_bytes, 500);
+    assert_eq!(months[0].freed_bytes, 35);
+    assert_eq!(months[1].archive_bytes, 600);
+}
+
+#[test]
+fn it_measures_active_usage_without_the_archive() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let projects_dir = temp_dir.path().join("projects");
+    let archive_dir = projects_dir.join(".archive");
+    fs::create_dir_all(projects_dir.join("app")).unwrap();
+    fs::create_dir_all(archive_dir.join("old_app")).unwrap();
+    fs::write(projects_dir.join("app/main.rs"), "0123456789").unwrap();
+    fs::write(archive_dir.join("old_app/main.rs"), "01234").unwrap();
+
+    let archiver = Archiver::new(Settings {
+        projects_dirs: vec![projects_dir],
+        archive_dir,
+        ..Default::default()
+    });
+    let usage = archiver.usage_sample(42);
+    assert_eq!(usage.active_bytes, 10);
+    assert_eq!(usage.archive_bytes, 5);
+    assert_eq!(usage.freed_bytes, 42);
+}
+
+#[test]
+fn it_digests_the_last_complete_period() {
+    let mut history = vec![
+        sample(1, 3, 900, 1),
+        sample(2, 3, 800, 10),
+        sample(8, 3, 700, 20),
+        sample(9, 3, 500, 5),
+    ];
+    history[1].archived_projects = 2;
+    history[2].archived_projects = 1;
+    history[2].deleted_projects = 3;
+    let date = |day, month| NaiveDate::from_ymd_opt(2026, month, day).unwrap();
+
+    // On Wednesday 11 March, the last complete week ran from Monday 2 to Sunday 8.
+    let week = digest::build(&history, Period::Week, date(11, 3), Vec::new());
+    assert_eq!((week.start, week.end), (date(2, 3), date(8, 3)));
+    assert_eq!(week.runs, 2);
+    assert_eq!(week.archived_projects, 3);
+    assert_eq!(week.deleted_projects, 3);
+    assert_eq!(week.freed_bytes, 30);
+    assert_eq!(week.last_sample.unwrap().active_bytes, 700);
+
+    let month = digest::build(&history, Period::Month, date(1, 4), Vec::new());
+    assert_eq!((month.start, month.end), (date(1, 3), date(31, 3)));
+    assert_eq!(month.runs, 4);
+    assert_eq!(month.freed_bytes, 36);
+
+    let quiet = digest::build(&history, Period::Week, date(1, 6), Vec::new());
+    assert_eq!(quiet.runs, 0);
+    assert!(quiet.last_sample.is_none());
+}
+
+#[test]
+fn it_lists_the_deletions_due_in_the_next_period() {
+    setup_tracing();
+    let (_temp_dir, mut settings) = setup_test_env();
+    settings.enable_auto_delete = true;
+    settings.days_before_delete = 60;
+    let archiver = Archiver::new(settings.clone());
+    archiver.run_archive_process(false).unwrap();
+    assert!(archiver
+        .digest(&[], Period::Week)
+        .unwrap()
+        .upcoming_deletions
+        .is_empty());
+
+    let log_path = settings.archive_dir.join("archive.json");
+    let mut records: Vec<serde_json::Value> =
+        serde_json::from_str(&fs::read_to_string(&log_path).unwrap()).unwrap();
+    records[0]["archived_at"] = serde_json::json!(Utc::now() - Duration::days(55));
+    fs::write(&log_path, serde_json::to_string(&records).unwrap()).unwrap();
+
+    let digest = archiver.digest(&[], Period::Week).unwrap();
+    assert_eq!(digest.upcoming_deletions.len(), 1);
+    assert_eq!(digest.upcoming_deletions[0].name, "old_project");
+
+    settings.enable_auto_delete = false;
+    let disabled = Archiver::new(settings).digest(&[], Period::Week).unwrap();
+    assert!(disabled.upcoming_deletions.is_empty());
+}
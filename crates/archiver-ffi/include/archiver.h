@@ -0,0 +1,49 @@
+/*
+ * C interface to the archiver engine (libarchiver_ffi).
+ *
+ * Strings returned as `char *` are UTF-8 JSON owned by the caller and must be
+ * released with archiver_string_free(). On failure, functions return NULL or
+ * ARCHIVER_ERROR; archiver_last_error() and archiver_last_error_code() then
+ * describe the failure (see `archiver explain <code>`). Passing a NULL handle
+ * and an internal panic are reported the same way.
+ */
+#ifndef ARCHIVER_H
+#define ARCHIVER_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ARCHIVER_OK 0
+#define ARCHIVER_ERROR -1
+
+typedef struct ArchiverHandle ArchiverHandle;
+
+/* Engine from the user's settings file and ARCHIVER__* environment variables. */
+ArchiverHandle *archiver_new(void);
+/* Engine from a JSON object with the keys of settings.toml. */
+ArchiverHandle *archiver_new_from_json(const char *settings_json);
+void archiver_free(ArchiverHandle *handle);
+void archiver_string_free(char *value);
+
+/* Last failure on the calling thread; valid until the next call on that thread. */
+const char *archiver_last_error(void);
+const char *archiver_last_error_code(void);
+
+/* JSON array of {name, path, last_activity}. */
+char *archiver_scan(const ArchiverHandle *handle);
+/* JSON array of {name, path} for the projects a run would archive now. */
+char *archiver_plan(const ArchiverHandle *handle);
+/* JSON run summary; a failed run reports its error in the "error" key. */
+char *archiver_archive(const ArchiverHandle *handle, bool dry_run);
+int archiver_restore(const ArchiverHandle *handle, const char *name);
+/* JSON array of archive records. */
+char *archiver_list(const ArchiverHandle *handle);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ARCHIVER_H */
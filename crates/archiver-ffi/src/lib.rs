@@ -0,0 +1,272 @@
+//! Stable C ABI over `archiver-core`, declared in `include/archiver.h`.
+//!
+//! An engine is an opaque `ArchiverHandle` created by `archiver_new*` and released with
+//! `archiver_free`. Structured results (projects, plans, records, summaries) are returned as
+//! newly allocated UTF-8 JSON strings, in the same shape as the archive log, which the caller
+//! releases with `archiver_string_free`.
+//!
+//! Functions that can fail return `NULL` or a negative status; the message and stable error
+//! code (e.g. `A006`) of the last failure on the calling thread are then available from
+//! `archiver_last_error` and `archiver_last_error_code`. A panic inside the library never
+//! unwinds into the caller: it is reported the same way, as a failure with code `A099`.
+use archiver_core::{ActionPlan, Archiver, Error, Settings};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// Opaque engine handle.
+pub struct ArchiverHandle {
+    inner: Archiver,
+}
+
+/// Status returned by functions without a result value.
+pub const ARCHIVER_OK: c_int = 0;
+pub const ARCHIVER_ERROR: c_int = -1;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(CString, CString)>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(error: &Error) {
+    let message = CString::new(error.to_string().replace('\0', " ")).unwrap_or_default();
+    let code = CString::new(error.code().as_str()).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some((message, code)));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Records the error of a failed call, passing successful values through.
+fn check<T>(result: archiver_core::Result<T>) -> Option<T> {
+    match result {
+        Ok(value) => {
+            clear_last_error();
+            Some(value)
+        }
+        Err(error) => {
+            set_last_error(&error);
+            None
+        }
+    }
+}
+
+/// Runs the body of an exported function, turning a panic into `fallback` and a recorded
+/// error instead of unwinding across the C boundary.
+fn guard<T>(fallback: T, body: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let reason = payload
+            .downcast_ref::<&str>()
+            .map(|reason| reason.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".to_string());
+        set_last_error(&Error::Custom(format!("Internal panic: {}", reason)));
+        fallback
+    })
+}
+
+/// Serializes a value into a caller-owned C string, or `NULL` on failure.
+fn to_json<T: Serialize>(value: &T) -> *mut c_char {
+    check(serde_json::to_string(value).map_err(Error::from))
+        .and_then(|json| CString::new(json).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Reads a required, UTF-8 C string argument.
+unsafe fn read_str<'a>(value: *const c_char) -> Option<&'a str> {
+    if value.is_null() {
+        set_last_error(&Error::Custom("Unexpected NULL argument.".to_string()));
+        return None;
+    }
+    let text = CStr::from_ptr(value).to_str();
+    check(text.map_err(|e| Error::Custom(format!("Argument is not valid UTF-8: {}", e))))
+}
+
+/// Borrows the engine behind a handle, recording an error for `NULL`.
+unsafe fn handle_ref<'a>(handle: *const ArchiverHandle) -> Option<&'a ArchiverHandle> {
+    let handle = handle.as_ref();
+    if handle.is_none() {
+        set_last_error(&Error::Custom("Unexpected NULL handle.".to_string()));
+    }
+    handle
+}
+
+fn into_handle(settings: Settings) -> *mut ArchiverHandle {
+    Box::into_raw(Box::new(ArchiverHandle {
+        inner: Archiver::new(settings),
+    }))
+}
+
+/// Creates an engine from the user's settings file and `ARCHIVER__*` environment variables.
+/// Returns `NULL` on failure.
+#[no_mangle]
+pub extern "C" fn archiver_new() -> *mut ArchiverHandle {
+    guard(ptr::null_mut(), || {
+        check(Settings::new()).map_or(ptr::null_mut(), into_handle)
+    })
+}
+
+/// Creates an engine from settings given as a JSON object with the keys of `settings.toml`;
+/// missing keys take their default values. Returns `NULL` on failure.
+///
+/// # Safety
+/// `settings_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn archiver_new_from_json(
+    settings_json: *const c_char,
+) -> *mut ArchiverHandle {
+    guard(ptr::null_mut(), || {
+        let Some(json) = read_str(settings_json) else {
+            return ptr::null_mut();
+        };
+        check(serde_json::from_str::<Settings>(json).map_err(Error::from))
+            .map_or(ptr::null_mut(), into_handle)
+    })
+}
+
+/// Releases an engine. Passing `NULL` is a no-op.
+///
+/// # Safety
+/// `handle` must come from `archiver_new*` and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn archiver_free(handle: *mut ArchiverHandle) {
+    guard((), || {
+        if !handle.is_null() {
+            drop(Box::from_raw(handle));
+        }
+    })
+}
+
+/// Releases a string returned by this library. Passing `NULL` is a no-op.
+///
+/// # Safety
+/// `value` must come from this library and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn archiver_string_free(value: *mut c_char) {
+    guard((), || {
+        if !value.is_null() {
+            drop(CString::from_raw(value));
+        }
+    })
+}
+
+/// Message of the last failure on this thread, or `NULL`. Owned by the library and valid
+/// until the next call on the same thread.
+#[no_mangle]
+pub extern "C" fn archiver_last_error() -> *const c_char {
+    guard(ptr::null(), || {
+        LAST_ERROR.with(|last| {
+            last.borrow()
+                .as_ref()
+                .map_or(ptr::null(), |(message, _)| message.as_ptr())
+        })
+    })
+}
+
+/// Stable code (e.g. `A006`) of the last failure on this thread, or `NULL`. Same lifetime as
+/// `archiver_last_error`.
+#[no_mangle]
+pub extern "C" fn archiver_last_error_code() -> *const c_char {
+    guard(ptr::null(), || {
+        LAST_ERROR.with(|last| {
+            last.borrow()
+                .as_ref()
+                .map_or(ptr::null(), |(_, code)| code.as_ptr())
+        })
+    })
+}
+
+/// Scans the projects directory: a JSON array of `{name, path, last_activity}`.
+///
+/// # Safety
+/// `handle` must be a live handle from `archiver_new*`.
+#[no_mangle]
+pub unsafe extern "C" fn archiver_scan(handle: *const ArchiverHandle) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let Some(handle) = handle_ref(handle) else {
+            return ptr::null_mut();
+        };
+        check(handle.inner.scan_projects()).map_or(ptr::null_mut(), |projects| to_json(&projects))
+    })
+}
+
+/// The projects a run would archive now: a JSON array of `{name, path}`.
+///
+/// # Safety
+/// `handle` must be a live handle from `archiver_new*`.
+#[no_mangle]
+pub unsafe extern "C" fn archiver_plan(handle: *const ArchiverHandle) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let Some(handle) = handle_ref(handle) else {
+            return ptr::null_mut();
+        };
+        let Some(plan) = check(handle.inner.run_archive_process(true)) else {
+            return ptr::null_mut();
+        };
+        let planned: Vec<_> = plan
+            .into_iter()
+            .filter_map(|action| match action {
+                ActionPlan::Archive { project_name, path } => {
+                    Some(serde_json::json!({ "name": project_name, "path": path }))
+                }
+                ActionPlan::Nothing => None,
+            })
+            .collect();
+        to_json(&planned)
+    })
+}
+
+/// Runs the archive process (and auto-deletion when enabled): the JSON run summary. A failed
+/// run still returns its summary, with the error in its `error` key.
+///
+/// # Safety
+/// `handle` must be a live handle from `archiver_new*`.
+#[no_mangle]
+pub unsafe extern "C" fn archiver_archive(
+    handle: *const ArchiverHandle,
+    dry_run: bool,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let Some(handle) = handle_ref(handle) else {
+            return ptr::null_mut();
+        };
+        to_json(&handle.inner.run_with_summary(dry_run, &[]))
+    })
+}
+
+/// Restores a project by name or relative path. Returns `ARCHIVER_OK` or `ARCHIVER_ERROR`.
+///
+/// # Safety
+/// `handle` must be a live handle from `archiver_new*` and `name` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn archiver_restore(
+    handle: *const ArchiverHandle,
+    name: *const c_char,
+) -> c_int {
+    guard(ARCHIVER_ERROR, || {
+        let Some(handle) = handle_ref(handle) else {
+            return ARCHIVER_ERROR;
+        };
+        let Some(name) = read_str(name) else {
+            return ARCHIVER_ERROR;
+        };
+        check(handle.inner.restore_project(name)).map_or(ARCHIVER_ERROR, |_| ARCHIVER_OK)
+    })
+}
+
+/// Every archived project: a JSON array of archive records.
+///
+/// # Safety
+/// `handle` must be a live handle from `archiver_new*`.
+#[no_mangle]
+pub unsafe extern "C" fn archiver_list(handle: *const ArchiverHandle) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let Some(handle) = handle_ref(handle) else {
+            return ptr::null_mut();
+        };
+        check(handle.inner.get_archive_records())
+            .map_or(ptr::null_mut(), |records| to_json(&records))
+    })
+}
@@ -0,0 +1,98 @@
+use archiver_core::simulate::{self, SimulationSpec};
+use archiver_ffi::*;
+use std::ffi::{CStr, CString};
+
+unsafe fn take_json(value: *mut std::ffi::c_char) -> serde_json::Value {
+    assert!(!value.is_null(), "call failed");
+    let json = serde_json::from_str(CStr::from_ptr(value).to_str().unwrap()).unwrap();
+    archiver_string_free(value);
+    json
+}
+
+#[test]
+fn it_drives_the_engine_through_the_c_interface() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let projects_dir = temp_dir.path().join("projects");
+    simulate::generate(
+        &projects_dir,
+        &SimulationSpec {
+            projects: 10,
+            max_size: 64 * 1024,
+            seed: 3,
+        },
+    )
+    .unwrap();
+    let settings = serde_json::json!({
+        "projects_dirs": [projects_dir],
+        "archive_dir": temp_dir.path().join("archive"),
+    });
+    let settings = CString::new(settings.to_string()).unwrap();
+
+    unsafe {
+        let handle = archiver_new_from_json(settings.as_ptr());
+        assert!(!handle.is_null());
+
+        let scanned = take_json(archiver_scan(handle));
+        assert_eq!(scanned.as_array().unwrap().len(), 10);
+
+        let plan = take_json(archiver_plan(handle));
+        let planned = plan.as_array().unwrap().len();
+        assert!(planned > 0);
+
+        let summary = take_json(archiver_archive(handle, false));
+        assert_eq!(summary["archived"], planned);
+        assert!(summary.get("error").is_none());
+
+        let records = take_json(archiver_list(handle));
+        assert_eq!(records.as_array().unwrap().len(), planned);
+
+        let name = CString::new(records[0]["name"].as_str().unwrap()).unwrap();
+        assert_eq!(archiver_restore(handle, name.as_ptr()), ARCHIVER_OK);
+        assert!(archiver_last_error().is_null());
+
+        // The project is no longer archived: the error carries its stable code.
+        assert_eq!(archiver_restore(handle, name.as_ptr()), ARCHIVER_ERROR);
+        assert_eq!(
+            CStr::from_ptr(archiver_last_error_code()).to_str().unwrap(),
+            "A006"
+        );
+        assert!(!archiver_last_error().is_null());
+
+        archiver_free(handle);
+    }
+}
+
+#[test]
+fn it_rejects_invalid_settings() {
+    let settings = CString::new("{\"inactivity_days\": \"soon\"}").unwrap();
+    unsafe {
+        assert!(archiver_new_from_json(settings.as_ptr()).is_null());
+        assert!(archiver_new_from_json(std::ptr::null()).is_null());
+        assert!(!archiver_last_error().is_null());
+    }
+}
+
+#[test]
+fn it_reports_null_handles() {
+    let name = CString::new("project").unwrap();
+    unsafe {
+        assert!(archiver_scan(std::ptr::null()).is_null());
+        assert!(!archiver_last_error().is_null());
+        assert_eq!(
+            CStr::from_ptr(archiver_last_error()).to_str().unwrap(),
+            "Unexpected NULL handle."
+        );
+        assert_eq!(
+            CStr::from_ptr(archiver_last_error_code()).to_str().unwrap(),
+            "A099"
+        );
+
+        assert_eq!(
+            archiver_restore(std::ptr::null(), name.as_ptr()),
+            ARCHIVER_ERROR
+        );
+        assert!(!archiver_last_error().is_null());
+        assert!(archiver_list(std::ptr::null()).is_null());
+        assert!(!archiver_last_error().is_null());
+    }
+}
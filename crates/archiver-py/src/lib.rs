@@ -0,0 +1,183 @@
+//! Python bindings for `archiver-core`, importable as the `archiver` module.
+//!
+//! Values cross the boundary as plain Python objects (dicts, lists, strings), produced from the
+//! same serde representation as the archive log, so scripts see exactly what the CLI writes.
+//!
+//! ```python
+//! import archiver
+//!
+//! engine = archiver.Archiver.from_config()
+//! for project in engine.plan():
+//!     print(project["name"], project["path"])
+//! ```
+use archiver_core::{ActionPlan, Archiver as CoreArchiver, RestoreFilter, Settings};
+use chrono::{DateTime, Duration, Utc};
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::Serialize;
+
+create_exception!(
+    archiver,
+    ArchiverError,
+    PyException,
+    "Raised when the archiver engine fails. `code` holds the stable error code (e.g. A006)."
+);
+
+/// Converts an engine error into `ArchiverError`, with its code as an attribute.
+fn to_py_err(error: archiver_core::Error) -> PyErr {
+    let code = error.code().as_str();
+    let err = ArchiverError::new_err(format!("[{}] {}", code, error));
+    Python::attach(|py| {
+        let _ = err.value(py).setattr("code", code);
+    });
+    err
+}
+
+/// Converts any serializable value into the equivalent Python object through JSON.
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<Py<PyAny>> {
+    let json =
+        serde_json::to_string(value).map_err(|e| to_py_err(archiver_core::Error::Json(e)))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+/// Parses an optional ISO 8601 timestamp argument.
+fn parse_instant(value: Option<&str>) -> PyResult<Option<DateTime<Utc>>> {
+    value
+        .map(|v| {
+            DateTime::parse_from_rfc3339(v)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| ArchiverError::new_err(format!("Invalid timestamp '{}': {}", v, e)))
+        })
+        .transpose()
+}
+
+/// The archiver engine, configured either from the user's settings file or from a dict with
+/// the same keys as `settings.toml`.
+#[pyclass(name = "Archiver", module = "archiver", frozen)]
+struct PyArchiver {
+    inner: CoreArchiver,
+}
+
+#[pymethods]
+impl PyArchiver {
+    /// Creates an engine from a settings dict; missing keys take their default values.
+    #[new]
+    fn new(py: Python<'_>, settings: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let json: String = py
+            .import("json")?
+            .call_method1("dumps", (settings,))?
+            .extract()?;
+        let settings: Settings =
+            serde_json::from_str(&json).map_err(|e| to_py_err(archiver_core::Error::Json(e)))?;
+        Ok(Self {
+            inner: CoreArchiver::new(settings),
+        })
+    }
+
+    /// Creates an engine from the settings file and `ARCHIVER__*` environment variables.
+    #[staticmethod]
+    fn from_config() -> PyResult<Self> {
+        let settings = Settings::new().map_err(to_py_err)?;
+        Ok(Self {
+            inner: CoreArchiver::new(settings),
+        })
+    }
+
+    /// The effective settings, as a dict.
+    fn settings(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        to_py(py, self.inner.settings())
+    }
+
+    /// Every project in the projects directory with its last activity.
+    fn scan(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let projects = py
+            .detach(|| self.inner.scan_projects())
+            .map_err(to_py_err)?;
+        to_py(py, &projects)
+    }
+
+    /// The projects a run would archive now, as `{"name", "path"}` dicts.
+    fn plan(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let plan = py
+            .detach(|| self.inner.run_archive_process(true))
+            .map_err(to_py_err)?;
+        let planned: Vec<_> = plan
+            .into_iter()
+            .filter_map(|action| match action {
+                ActionPlan::Archive { project_name, path } => Some(serde_json::json!({
+                    "name": project_name,
+                    "path": path,
+                })),
+                ActionPlan::Nothing => None,
+            })
+            .collect();
+        to_py(py, &planned)
+    }
+
+    /// Runs the archive process (and auto-deletion when enabled) and returns the run summary.
+    /// Failures are reported in the summary's `error` key rather than raised.
+    #[pyo3(signature = (dry_run = false, tags = Vec::new()))]
+    fn archive(&self, py: Python<'_>, dry_run: bool, tags: Vec<String>) -> PyResult<Py<PyAny>> {
+        let summary = py.detach(|| self.inner.run_with_summary(dry_run, &tags));
+        to_py(py, &summary)
+    }
+
+    /// Projects that will become archive candidates within `days` days.
+    #[pyo3(signature = (days = 30))]
+    fn forecast(&self, py: Python<'_>, days: i64) -> PyResult<Py<PyAny>> {
+        let upcoming = py
+            .detach(|| self.inner.forecast(Duration::days(days)))
+            .map_err(to_py_err)?;
+        to_py(py, &upcoming)
+    }
+
+    /// Every archived project, as stored in the archive log.
+    fn records(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let records = self.inner.get_archive_records().map_err(to_py_err)?;
+        to_py(py, &records)
+    }
+
+    /// What restoring a project would do, without changing anything.
+    fn preview_restore(&self, py: Python<'_>, name: &str) -> PyResult<Py<PyAny>> {
+        let preview = self.inner.preview_restore(name).map_err(to_py_err)?;
+        to_py(py, &preview)
+    }
+
+    /// Restores a project by name or relative path and returns its former record.
+    fn restore(&self, py: Python<'_>, name: &str) -> PyResult<Py<PyAny>> {
+        let record = py
+            .detach(|| self.inner.restore_project(name))
+            .map_err(to_py_err)?;
+        to_py(py, &record)
+    }
+
+    /// Restores every project matching all the given filters (ISO 8601 timestamps) and returns
+    /// how many were restored.
+    #[pyo3(signature = (archived_after = None, archived_before = None, tag = None, group = None))]
+    fn restore_matching(
+        &self,
+        py: Python<'_>,
+        archived_after: Option<&str>,
+        archived_before: Option<&str>,
+        tag: Option<String>,
+        group: Option<String>,
+    ) -> PyResult<usize> {
+        let filter = RestoreFilter {
+            archived_after: parse_instant(archived_after)?,
+            archived_before: parse_instant(archived_before)?,
+            tag,
+            group,
+        };
+        py.detach(|| self.inner.restore_matching(&filter))
+            .map_err(to_py_err)
+    }
+}
+
+#[pymodule]
+fn archiver(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyArchiver>()?;
+    m.add("ArchiverError", m.py().get_type::<ArchiverError>())?;
+    Ok(())
+}